@@ -0,0 +1,69 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use nalgebra::DMatrix;
+use qc_file_parsers::array_text::fast::parse_file_into_matrix;
+use qc_file_parsers::array_text::writer::{write_matrix, TextFormat};
+use qc_file_parsers::array_text::{parse_text_into_matrix, MatrixLayout};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::PathBuf;
+
+const N: usize = 1000;
+
+/// Writes a dense N x N matrix to a temporary file once.
+fn setup_matrix_file() -> PathBuf {
+    let path = std::env::temp_dir().join("qc_file_parsers_bench_matrix.dat");
+    let matrix = DMatrix::<f64>::from_fn(N, N, |i, j| (i as f64 - j as f64) / (1.0 + i as f64));
+    let mut dest = BufWriter::new(File::create(&path).unwrap());
+    write_matrix(&mut dest, &matrix, &TextFormat::default()).unwrap();
+    path
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let path = setup_matrix_file();
+    let mut group = c.benchmark_group("parse_1000x1000");
+    group.sample_size(10);
+    group.bench_function("parse_text_into_matrix", |b| {
+        b.iter(|| {
+            let mut source = BufReader::new(File::open(&path).unwrap());
+            let parsed: DMatrix<f64> =
+                parse_text_into_matrix(&mut source, " ", N, N, MatrixLayout::RowMajor).unwrap();
+            parsed
+        })
+    });
+    group.bench_function("parse_file_into_matrix", |b| {
+        b.iter(|| {
+            let parsed: DMatrix<f64> =
+                parse_file_into_matrix(&path, " ", N, N, MatrixLayout::RowMajor).unwrap();
+            parsed
+        })
+    });
+    group.finish();
+}
+
+fn bench_parse_rectangular(c: &mut Criterion) {
+    let path = setup_matrix_file();
+    // The N x N values read as a row-major (N / 4) x (4 N) matrix.
+    let (nrows, ncols) = (N / 4, 4 * N);
+    let mut group = c.benchmark_group("parse_250x4000");
+    group.sample_size(10);
+    group.bench_function("parse_text_into_matrix", |b| {
+        b.iter(|| {
+            let mut source = BufReader::new(File::open(&path).unwrap());
+            let parsed: DMatrix<f64> =
+                parse_text_into_matrix(&mut source, " ", ncols, nrows, MatrixLayout::RowMajor)
+                    .unwrap();
+            parsed
+        })
+    });
+    group.bench_function("parse_file_into_matrix", |b| {
+        b.iter(|| {
+            let parsed: DMatrix<f64> =
+                parse_file_into_matrix(&path, " ", ncols, nrows, MatrixLayout::RowMajor).unwrap();
+            parsed
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_parse_rectangular);
+criterion_main!(benches);
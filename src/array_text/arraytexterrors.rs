@@ -0,0 +1,60 @@
+use std::error;
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum ParseArrayTextError {
+    /// Raised when the underlying reader fails.
+    Io(std::io::Error),
+    /// Raised when a token cannot be parsed into the requested type.
+    ValueError(String),
+    /// Raised when the number of parsed elements does not fit the requested shape.
+    ShapeMismatch { expected: usize, found: usize },
+    /// Raised when the number of elements is not that of a packed triangular matrix.
+    NotTriangular { found: usize },
+    /// Raised when an index lies outside of the requested shape.
+    IndexOutOfBounds {
+        index: Vec<usize>,
+        shape: Vec<usize>,
+    },
+}
+
+impl Display for ParseArrayTextError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Could not read from the source: {}", e),
+            Self::ValueError(token) => {
+                write!(f, "Could not parse '{}' into the requested type.", token)
+            }
+            Self::ShapeMismatch { expected, found } => write!(
+                f,
+                "Expected {} elements for the requested shape, but found {}.",
+                expected, found
+            ),
+            Self::NotTriangular { found } => write!(
+                f,
+                "{} elements do not form a packed triangular matrix.",
+                found
+            ),
+            Self::IndexOutOfBounds { index, shape } => write!(
+                f,
+                "Index {:?} is out of bounds for shape {:?}.",
+                index, shape
+            ),
+        }
+    }
+}
+
+impl error::Error for ParseArrayTextError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseArrayTextError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
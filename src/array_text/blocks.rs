@@ -0,0 +1,264 @@
+//! Reading of matrices as they are printed by quantum chemistry programs, i.e. in blocks of a few
+//! columns, each headed by a line of column indices and each row starting with a label.
+//! ```text
+//!                 1             2             3
+//!       1  1   O  1S          0.100000D+01
+//!       2         2S          0.236704D+00  0.100000D+01
+//!       3         2PX         0.000000D+00  0.000000D+00  0.100000D+01
+//! ```
+use nalgebra::{DMatrix, Scalar};
+use num::Zero;
+use std::io::BufRead;
+use std::iter::Peekable;
+use std::str::FromStr;
+
+use super::arraytexterrors::ParseArrayTextError;
+use super::parse_token;
+
+/// A matrix together with the labels of its rows and columns as printed in the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabeledMatrix<T>
+where
+    T: Scalar,
+{
+    /// The stitched matrix.
+    pub matrix: DMatrix<T>,
+    /// Labels in front of the rows with white space collapsed, e.g. `1 C 1s`.
+    pub row_labels: Vec<String>,
+    /// Column indices from the block headers.
+    pub col_labels: Vec<String>,
+}
+
+/// Returns the tokens of a block header, i.e. a line of consecutive ascending column indices.
+fn header_tokens(line: &str) -> Option<Vec<&str>> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let indices: Vec<usize> = tokens
+        .iter()
+        .map(|t| t.parse::<usize>().ok())
+        .collect::<Option<Vec<usize>>>()?;
+    let consecutive = indices.windows(2).all(|w| w[1] == w[0] + 1);
+    (!indices.is_empty() && consecutive).then_some(tokens)
+}
+
+/// Splits a data line into the row label and the trailing `nvalues` values.
+fn split_data_line<T>(line: &str, nvalues: usize) -> Option<(String, Vec<T>)>
+where
+    T: FromStr,
+{
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    if nvalues == 0 || tokens.len() < nvalues {
+        return None;
+    }
+    let (label, values) = tokens.split_at(tokens.len() - nvalues);
+    let values = values
+        .iter()
+        .map(|v| parse_token::<T>(v).ok())
+        .collect::<Option<Vec<T>>>()?;
+    Some((label.join(" "), values))
+}
+
+/// Function to parse a labeled block matrix from an iterator over lines. Parsing stops at the end
+/// of the iterator or at the first line, which is neither blank, a block header or a data line.
+/// This line is not consumed. Such a line within a later block, i.e. before its rows are
+/// complete, raises a `ShapeMismatch`.
+/// # Arguments
+///  * `lines` - Peekable iterator over the lines, positioned at or before the first header.
+///  * `triangular` - If only the lower triangle is printed. The matrix is then symmetrized.
+pub fn parse_labeled_block_lines<L, S, T>(
+    lines: &mut Peekable<L>,
+    triangular: bool,
+) -> Result<LabeledMatrix<T>, ParseArrayTextError>
+where
+    L: Iterator<Item = S>,
+    S: AsRef<str>,
+    T: FromStr + Scalar + Zero,
+{
+    let mut entries: Vec<(usize, usize, T)> = Vec::new();
+    let mut row_labels: Vec<String> = Vec::new();
+    let mut col_labels: Vec<String> = Vec::new();
+    let mut nrows: Option<usize> = None;
+    'blocks: loop {
+        while lines.peek().is_some_and(|l| l.as_ref().trim().is_empty()) {
+            lines.next();
+        }
+        let header: Vec<String> = match lines.peek().and_then(|l| header_tokens(l.as_ref())) {
+            Some(h) => h.into_iter().map(String::from).collect(),
+            None => break,
+        };
+        lines.next();
+        let col_start = col_labels.len();
+        let ncols_block = header.len();
+        col_labels.extend(header);
+        let mut row = if triangular { col_start } else { 0 };
+        loop {
+            if nrows.is_some_and(|n| row >= n) {
+                break;
+            }
+            while lines.peek().is_some_and(|l| l.as_ref().trim().is_empty()) {
+                lines.next();
+            }
+            let Some(line) = lines.peek() else {
+                break;
+            };
+            if header_tokens(line.as_ref()).is_some() {
+                break;
+            }
+            let nvalues = if triangular {
+                ncols_block.min(row + 1 - col_start)
+            } else {
+                ncols_block
+            };
+            let Some((label, values)) = split_data_line::<T>(line.as_ref(), nvalues) else {
+                // Only the first block may end the matrix, later blocks have a known length.
+                if let Some(n) = nrows {
+                    return Err(ParseArrayTextError::ShapeMismatch {
+                        expected: n,
+                        found: row,
+                    });
+                }
+                nrows = Some(row);
+                break 'blocks;
+            };
+            lines.next();
+            if nrows.is_none() {
+                row_labels.push(label);
+            }
+            for (k, v) in values.into_iter().enumerate() {
+                entries.push((row, col_start + k, v));
+            }
+            row += 1;
+        }
+        match nrows {
+            None => nrows = Some(row),
+            Some(n) if row != n => {
+                return Err(ParseArrayTextError::ShapeMismatch {
+                    expected: n,
+                    found: row,
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    let nrows = nrows.unwrap_or(0);
+    let ncols = col_labels.len();
+    if triangular && nrows != ncols {
+        return Err(ParseArrayTextError::ShapeMismatch {
+            expected: ncols,
+            found: nrows,
+        });
+    }
+    let mut matrix = DMatrix::<T>::zeros(nrows, ncols);
+    for (i, j, v) in entries {
+        if triangular {
+            matrix[(j, i)] = v.clone();
+        }
+        matrix[(i, j)] = v;
+    }
+    Ok(LabeledMatrix {
+        matrix,
+        row_labels,
+        col_labels,
+    })
+}
+
+/// Function to parse a labeled block matrix from a buffer, see [`parse_labeled_block_lines`].
+/// # Arguments
+///  * `source` - mutable reference of the file buffer, positioned before the first header.
+///  * `triangular` - If only the lower triangle is printed. The matrix is then symmetrized.
+pub fn parse_labeled_blocks<I, T>(
+    source: &mut I,
+    triangular: bool,
+) -> Result<LabeledMatrix<T>, ParseArrayTextError>
+where
+    I: BufRead,
+    T: FromStr + Scalar + Zero,
+{
+    let lines = source.lines().collect::<Result<Vec<String>, _>>()?;
+    parse_labeled_block_lines(&mut lines.into_iter().peekable(), triangular)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_full_blocks() {
+        let text = "
+                 1                   2                   3
+
+    1     1.00000000000000     0.23670392788000     0.00000000000000
+    2     0.23670392788000     1.00000000000000     0.50000000000000
+
+                 4
+
+    1     0.10000000000000
+    2     0.20000000000000
+
+  Some trailing text
+";
+        let mut lines = text.lines().peekable();
+        let parsed: LabeledMatrix<f64> = parse_labeled_block_lines(&mut lines, false).unwrap();
+        assert_eq!(
+            parsed.matrix,
+            DMatrix::from_row_slice(
+                2,
+                4,
+                &[
+                    1.0,
+                    0.2367039278800,
+                    0.0,
+                    0.1,
+                    0.2367039278800,
+                    1.0,
+                    0.5,
+                    0.2
+                ]
+            )
+        );
+        assert_eq!(parsed.row_labels, vec!["1", "2"]);
+        assert_eq!(parsed.col_labels, vec!["1", "2", "3", "4"]);
+        assert_eq!(lines.next().unwrap().trim(), "Some trailing text");
+    }
+
+    #[test]
+    fn test_triangular_blocks() {
+        let text = "                1             2
+      1  1   O  1S          0.100000D+01
+      2         2S          0.236704D+00  0.100000D+01
+      3  2   H  1S          0.500000D-01  0.400000D+00
+                3
+      3  2   H  1S          0.100000D+01
+";
+        let parsed: LabeledMatrix<f64> =
+            parse_labeled_blocks(&mut Cursor::new(text), true).unwrap();
+        assert_eq!(
+            parsed.matrix,
+            DMatrix::from_row_slice(
+                3,
+                3,
+                &[1.0, 0.236704, 0.05, 0.236704, 1.0, 0.4, 0.05, 0.4, 1.0]
+            )
+        );
+        assert_eq!(parsed.row_labels, vec!["1 1 O 1S", "2 2S", "3 2 H 1S"]);
+    }
+
+    #[test]
+    fn test_truncated_block() {
+        let text = "                 1                   2
+    1     1.00000000000000     2.00000000000000
+    2     3.00000000000000     4.00000000000000
+                 3
+    1     5.00000000000000
+  Total energy
+";
+        let parsed = parse_labeled_blocks::<_, f64>(&mut Cursor::new(text), false);
+        assert!(matches!(
+            parsed,
+            Err(ParseArrayTextError::ShapeMismatch {
+                expected: 2,
+                found: 1
+            })
+        ));
+    }
+}
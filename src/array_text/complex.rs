@@ -0,0 +1,174 @@
+//! Parsing of complex valued matrices. Three layouts of the real and imaginary parts are
+//! supported:
+//! ```text
+//! Tuples:          (1.0,2.0) (3.0D0,-1.0D0)
+//! Interleaved:      1.0 2.0 3.0 -1.0
+//! Separate blocks:  1.0 3.0
+//!                   2.0 -1.0
+//! ```
+use nalgebra::{DMatrix, Dyn, Scalar};
+use num::Complex;
+use std::io::BufRead;
+use std::str::FromStr;
+
+use super::arraytexterrors::ParseArrayTextError;
+use super::{parse_token, tokenize, values_into_matrix, MatrixLayout};
+
+/// How the real and imaginary parts of the elements are stored in the file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ComplexLayout {
+    /// Each element is a parenthesized pair `(re,im)`, like Fortran list-directed output.
+    #[default]
+    Tuples,
+    /// Real and imaginary part of each element follow each other.
+    Interleaved,
+    /// All real parts come first, followed by all imaginary parts.
+    SeparateBlocks,
+}
+
+/// Parses all `(re,im)` tuples in a line.
+fn parse_tuples<T>(line: &str, values: &mut Vec<Complex<T>>) -> Result<(), ParseArrayTextError>
+where
+    T: FromStr,
+{
+    let mut rest = line;
+    while let Some(open) = rest.find('(') {
+        let close = rest[open..]
+            .find(')')
+            .ok_or_else(|| ParseArrayTextError::ValueError(rest[open..].to_string()))?
+            + open;
+        let tuple = &rest[open + 1..close];
+        let (re, im) = tuple
+            .split_once(',')
+            .ok_or_else(|| ParseArrayTextError::ValueError(tuple.to_string()))?;
+        values.push(Complex::new(
+            parse_token(re.trim())?,
+            parse_token(im.trim())?,
+        ));
+        rest = &rest[close + 1..];
+    }
+    Ok(())
+}
+
+/// Function to parse a block of complex data into a matrix.
+/// # Arguments
+///  * `source` - mutable reference of the file buffer.
+///  * `separator` - Seperator of the datapoints. Ignored for tuples.
+///  * `ncols` - Desired number of columns in target matrix.
+///  * `nrows` - Desired number of rows in target matrix.
+///  * `layout` - Order in which the elements are stored in the file.
+///  * `complex_layout` - How real and imaginary parts are stored.
+pub fn parse_complex_text_into_matrix<I, T>(
+    source: &mut I,
+    separator: &str,
+    ncols: usize,
+    nrows: usize,
+    layout: MatrixLayout,
+    complex_layout: ComplexLayout,
+) -> Result<DMatrix<Complex<T>>, ParseArrayTextError>
+where
+    I: BufRead,
+    T: FromStr + Scalar,
+{
+    let nelements = nrows * ncols;
+    let mut values: Vec<Complex<T>> = Vec::with_capacity(nelements);
+    if complex_layout == ComplexLayout::Tuples {
+        for line in source.lines() {
+            parse_tuples(&line?, &mut values)?;
+        }
+    } else {
+        let mut reals: Vec<T> = Vec::with_capacity(2 * nelements);
+        for line in source.lines() {
+            let line = line?;
+            for ele in tokenize(&line, separator) {
+                reals.push(parse_token(ele)?);
+            }
+        }
+        if reals.len() != 2 * nelements {
+            return Err(ParseArrayTextError::ShapeMismatch {
+                expected: 2 * nelements,
+                found: reals.len(),
+            });
+        }
+        if complex_layout == ComplexLayout::Interleaved {
+            values.extend(
+                reals
+                    .chunks_exact(2)
+                    .map(|p| Complex::new(p[0].clone(), p[1].clone())),
+            );
+        } else {
+            let imaginaries = reals.split_off(nelements);
+            values.extend(
+                reals
+                    .into_iter()
+                    .zip(imaginaries)
+                    .map(|(re, im)| Complex::new(re, im)),
+            );
+        }
+    }
+    values_into_matrix(values, Dyn(nrows), Dyn(ncols), layout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn expected() -> DMatrix<Complex<f64>> {
+        DMatrix::from_row_slice(
+            2,
+            2,
+            &[
+                Complex::new(1.0, 2.0),
+                Complex::new(3.0, -1.0),
+                Complex::new(0.5, 0.0),
+                Complex::new(-2.0, 0.25),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_tuples() {
+        let text = "(1.0,2.0) ( 3.0D0, -1.0D0 )\n(0.5,0.0)(-2.0,2.5d-1)\n";
+        let parsed: DMatrix<Complex<f64>> = parse_complex_text_into_matrix(
+            &mut Cursor::new(text),
+            " ",
+            2,
+            2,
+            MatrixLayout::RowMajor,
+            ComplexLayout::Tuples,
+        )
+        .unwrap();
+        assert_eq!(parsed, expected());
+    }
+
+    #[test]
+    fn test_interleaved() {
+        let text = "1.0 2.0 3.0 -1.0\n0.5 0.0 -2.0 0.25\n";
+        let parsed: DMatrix<Complex<f64>> = parse_complex_text_into_matrix(
+            &mut Cursor::new(text),
+            " ",
+            2,
+            2,
+            MatrixLayout::RowMajor,
+            ComplexLayout::Interleaved,
+        )
+        .unwrap();
+        assert_eq!(parsed, expected());
+    }
+
+    #[test]
+    fn test_separate_blocks() {
+        let text = "1.0 0.5\n3.0 -2.0\n\n2.0 0.0\n-1.0 0.25\n";
+        let parsed: DMatrix<Complex<f64>> = parse_complex_text_into_matrix(
+            &mut Cursor::new(text),
+            " ",
+            2,
+            2,
+            MatrixLayout::ColumnMajor,
+            ComplexLayout::SeparateBlocks,
+        )
+        .unwrap();
+        assert_eq!(parsed, expected());
+    }
+}
@@ -0,0 +1,203 @@
+//! High-throughput parsing of large files of uniform floating point data, e.g. dense matrices
+//! of large CI calculations. The file is memory mapped, split into chunks on line boundaries and
+//! the chunks are parsed in parallel directly into the storage of the resulting matrix.
+//!
+//! Requires the `parallel` feature.
+use fast_float2::FastFloat;
+use memmap2::Mmap;
+use nalgebra::{DMatrix, Scalar};
+use num::Zero;
+use rayon::prelude::*;
+use std::fs::File;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::arraytexterrors::ParseArrayTextError;
+use super::{parse_token, MatrixLayout};
+
+/// Chunks smaller than this are not worth a task of their own.
+const MIN_CHUNK_BYTES: usize = 1 << 16;
+
+/// Pointer to the storage of the resulting matrix, shared by the tasks writing disjoint elements.
+struct Storage<T>(*mut T);
+
+// SAFETY: Every element is written by exactly one task and read only after all have finished.
+unsafe impl<T: Send> Sync for Storage<T> {}
+
+impl<T> Storage<T> {
+    fn get(&self) -> *mut T {
+        self.0
+    }
+}
+
+/// Splits the data into about `nchunks` pieces, each ending after a line break.
+fn split_on_lines(data: &[u8], nchunks: usize) -> Vec<&[u8]> {
+    let target = (data.len() / nchunks.max(1)).max(1);
+    let mut chunks = Vec::with_capacity(nchunks);
+    let mut rest = data;
+    while !rest.is_empty() {
+        let end = if rest.len() <= target {
+            rest.len()
+        } else {
+            rest[target..]
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(rest.len(), |p| target + p + 1)
+        };
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks
+}
+
+/// Iterates over the tokens of a chunk, which are separated by white space or `separator`.
+fn tokens(chunk: &[u8], separator: Option<u8>) -> impl Iterator<Item = &[u8]> {
+    chunk
+        .split(move |&b| b.is_ascii_whitespace() || Some(b) == separator)
+        .filter(|t| !t.is_empty())
+}
+
+/// Parses a single token, falling back to the slower parser for Fortran `D` exponents.
+fn parse_bytes<T>(token: &[u8]) -> Result<T, ParseArrayTextError>
+where
+    T: FastFloat + FromStr,
+{
+    fast_float2::parse::<T, _>(token).or_else(|_| {
+        let token = String::from_utf8_lossy(token);
+        parse_token::<T>(&token)
+    })
+}
+
+/// Function to parse a large file of uniform floating point data into a matrix in parallel.
+/// The result is identical to that of [`parse_text_into_matrix`](super::parse_text_into_matrix).
+/// # Arguments
+///  * `path` - Path of the file.
+///  * `separator` - Seperator of the datapoints, either white space or a single ASCII character.
+///  * `ncols` - Desired number of columns in target matrix.
+///  * `nrows` - Desired number of rows in target matrix.
+///  * `layout` - Order in which the elements are stored in the file.
+pub fn parse_file_into_matrix<P, T>(
+    path: P,
+    separator: &str,
+    ncols: usize,
+    nrows: usize,
+    layout: MatrixLayout,
+) -> Result<DMatrix<T>, ParseArrayTextError>
+where
+    P: AsRef<Path>,
+    T: FastFloat + FromStr + Scalar + Zero + Send + Sync,
+{
+    let separator = match separator.trim().as_bytes() {
+        [] => None,
+        [b] => Some(*b),
+        _ => {
+            return Err(ParseArrayTextError::ValueError(format!(
+                "separator '{}'",
+                separator
+            )))
+        }
+    };
+    let file = File::open(path)?;
+    // SAFETY: The map is only read and dropped before returning. Modifying the file while it
+    // is parsed is undefined behaviour, as for every memory mapped file.
+    let data = unsafe { Mmap::map(&file)? };
+    let nchunks = (rayon::current_num_threads() * 4).min(data.len() / MIN_CHUNK_BYTES + 1);
+    let chunks = split_on_lines(&data, nchunks);
+    let counts: Vec<usize> = chunks
+        .par_iter()
+        .map(|c| tokens(c, separator).count())
+        .collect();
+    let found: usize = counts.iter().sum();
+    if found != nrows * ncols {
+        return Err(ParseArrayTextError::ShapeMismatch {
+            expected: nrows * ncols,
+            found,
+        });
+    }
+    let mut result = DMatrix::<T>::zeros(nrows, ncols);
+    let offsets: Vec<usize> = counts
+        .iter()
+        .scan(0, |offset, count| {
+            let start = *offset;
+            *offset += count;
+            Some(start)
+        })
+        .collect();
+    let storage = Storage(result.as_mut_slice().as_mut_ptr());
+    chunks
+        .par_iter()
+        .zip(offsets.into_par_iter())
+        .try_for_each(|(chunk, offset)| {
+            for (k, token) in tokens(chunk, separator).enumerate() {
+                // Row-major values are scattered directly into their column-major position.
+                let index = match layout {
+                    MatrixLayout::RowMajor => {
+                        let (row, col) = ((offset + k) / ncols, (offset + k) % ncols);
+                        col * nrows + row
+                    }
+                    MatrixLayout::ColumnMajor => offset + k,
+                };
+                // SAFETY: The counts of all chunks sum up to `nrows * ncols`, so each index is
+                // in bounds and written by exactly one chunk.
+                unsafe { storage.get().add(index).write(parse_bytes(token)?) };
+            }
+            Ok::<(), ParseArrayTextError>(())
+        })?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array_text::parse_text_into_matrix;
+    use std::fs::File;
+    use std::io::BufReader;
+
+    #[test]
+    fn test_split_on_lines() {
+        let data = b"1 2\n3 4\n5 6\n";
+        let chunks = split_on_lines(data, 3);
+        assert_eq!(chunks.concat(), data.to_vec());
+        assert!(chunks.iter().all(|c| c.ends_with(b"\n")));
+        assert_eq!(tokens(b"1.0,2.0 ,3.0\n", Some(b',')).count(), 3);
+    }
+
+    #[test]
+    fn test_same_as_serial() {
+        let mut test_buffer = BufReader::new(File::open("tests/test_array_text.dat").unwrap());
+        let serial: DMatrix<f64> =
+            parse_text_into_matrix(&mut test_buffer, " ", 9, 9, MatrixLayout::RowMajor).unwrap();
+        let parallel: DMatrix<f64> = parse_file_into_matrix(
+            "tests/test_array_text.dat",
+            " ",
+            9,
+            9,
+            MatrixLayout::RowMajor,
+        )
+        .unwrap();
+        assert_eq!(serial, parallel);
+        let rectangular: DMatrix<f64> = parse_file_into_matrix(
+            "tests/test_array_text.dat",
+            " ",
+            27,
+            3,
+            MatrixLayout::ColumnMajor,
+        )
+        .unwrap();
+        assert_eq!(rectangular[(1, 1)], 0.0);
+        assert_eq!(rectangular[(0, 1)], -0.0463821695);
+        let mut test_buffer = BufReader::new(File::open("tests/test_array_text.dat").unwrap());
+        let serial: DMatrix<f64> =
+            parse_text_into_matrix(&mut test_buffer, " ", 27, 3, MatrixLayout::RowMajor).unwrap();
+        let parallel: DMatrix<f64> = parse_file_into_matrix(
+            "tests/test_array_text.dat",
+            " ",
+            27,
+            3,
+            MatrixLayout::RowMajor,
+        )
+        .unwrap();
+        assert_eq!(serial, parallel);
+    }
+}
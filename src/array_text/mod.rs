@@ -1,38 +1,222 @@
 //! This module provides a parser to retrieve arrays of a uniform type, e.g. only f64 or only i32
 //! seperated by a given seperator from a file.
 
-use nalgebra::DMatrix;
+use crate::format_string::parse_fortran_real;
+use nalgebra::allocator::Allocator;
+use nalgebra::{Const, DMatrix, DefaultAllocator, Dim, Dyn, OMatrix, SMatrix};
 use std::{io::BufRead, str::FromStr};
 
+use self::arraytexterrors::ParseArrayTextError;
+pub mod arraytexterrors;
+pub mod blocks;
+pub mod complex;
+#[cfg(feature = "parallel")]
+pub mod fast;
+pub mod packed;
+pub mod sparse;
+pub mod tensor;
+pub mod writer;
+
+/// Order in which the elements of a matrix are stored in the file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixLayout {
+    /// The elements of the first row come first, then the second row etc.
+    #[default]
+    RowMajor,
+    /// The elements of the first column come first, then the second column etc. This is the
+    /// storage order of Fortran arrays.
+    ColumnMajor,
+}
+
+/// Splits a line into its tokens. A single white space as separator splits by any amount of
+/// white space.
+fn tokenize<'a>(line: &'a str, separator: &str) -> Vec<&'a str> {
+    if separator.trim().is_empty() {
+        line.split_whitespace().collect()
+    } else {
+        line.split(separator)
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .collect()
+    }
+}
+
+/// Parses a single token into T. Fortran `D` exponents are accepted.
+fn parse_token<T>(token: &str) -> Result<T, ParseArrayTextError>
+where
+    T: FromStr,
+{
+    parse_fortran_real::<T>(token).map_err(|_| ParseArrayTextError::ValueError(token.to_string()))
+}
+
+/// Builds a matrix from values in the given layout.
+fn values_into_matrix<T, R, C>(
+    values: Vec<T>,
+    nrows: R,
+    ncols: C,
+    layout: MatrixLayout,
+) -> Result<OMatrix<T, R, C>, ParseArrayTextError>
+where
+    T: nalgebra::Scalar,
+    R: Dim,
+    C: Dim,
+    DefaultAllocator: Allocator<T, R, C>,
+{
+    let expected = nrows.value() * ncols.value();
+    if values.len() != expected {
+        return Err(ParseArrayTextError::ShapeMismatch {
+            expected,
+            found: values.len(),
+        });
+    }
+    match layout {
+        MatrixLayout::RowMajor => Ok(OMatrix::from_row_iterator_generic(nrows, ncols, values)),
+        MatrixLayout::ColumnMajor => Ok(OMatrix::from_vec_generic(nrows, ncols, values)),
+    }
+}
+
 /// Functio to parse a block of uniform data into a matrix.
 /// # Arguments
 ///  * `source` - mutable reference of the file buffer.
 ///  * `seperator` - Seperator of the datapoints.
 ///  * `ncols` - Desired number of columns in target matrix.
 ///  * `nrows` - Desired number of rows in target matrix.
+///  * `layout` - Order in which the elements are stored in the file.
 pub fn parse_text_into_matrix<I, T>(
     source: &mut I,
     separator: &str,
     ncols: usize,
     nrows: usize,
-) -> DMatrix<T>
+    layout: MatrixLayout,
+) -> Result<DMatrix<T>, ParseArrayTextError>
 where
     I: BufRead + std::fmt::Debug,
     T: FromStr + std::fmt::Debug + nalgebra::Scalar,
     <T as FromStr>::Err: std::fmt::Debug,
 {
-    let mut result: Vec<T> = Vec::new();
-    let splitter = str::split_whitespace;
-    if separator != " " {
-        let splitter = str::split::<char>;
-        unimplemented!("Splitting by another separator is not implemented, yet!");
-    }
-    let line_iter = source.lines().map(|l| l.unwrap());
-    for line in line_iter {
-        let sep_split = splitter(&line);
-        for ele in sep_split {
-            result.push(ele.parse::<T>().unwrap());
+    parse_text_into_omatrix(source, separator, Dyn(ncols), Dyn(nrows), layout)
+}
+
+/// Function to parse a block of uniform data into a matrix, whose dimensions may be known at
+/// compile time, e.g. `Const<3>` rows and `Dyn` columns for a gradient.
+/// # Arguments
+///  * `source` - mutable reference of the file buffer.
+///  * `separator` - Seperator of the datapoints.
+///  * `ncols` - Desired number of columns in target matrix.
+///  * `nrows` - Desired number of rows in target matrix.
+///  * `layout` - Order in which the elements are stored in the file.
+pub fn parse_text_into_omatrix<I, T, R, C>(
+    source: &mut I,
+    separator: &str,
+    ncols: C,
+    nrows: R,
+    layout: MatrixLayout,
+) -> Result<OMatrix<T, R, C>, ParseArrayTextError>
+where
+    I: BufRead,
+    T: FromStr + nalgebra::Scalar,
+    R: Dim,
+    C: Dim,
+    DefaultAllocator: Allocator<T, R, C>,
+{
+    let mut result: Vec<T> = Vec::with_capacity(nrows.value() * ncols.value());
+    for line in source.lines() {
+        let line = line?;
+        for ele in tokenize(&line, separator) {
+            result.push(parse_token(ele)?);
         }
     }
-    DMatrix::from_vec(nrows, ncols, result)
+    values_into_matrix(result, nrows, ncols, layout)
+}
+
+/// Function to parse a block of uniform data into a matrix with dimensions known at compile
+/// time, e.g. 3x3 lattice vectors.
+/// # Arguments
+///  * `source` - mutable reference of the file buffer.
+///  * `separator` - Seperator of the datapoints.
+///  * `layout` - Order in which the elements are stored in the file.
+pub fn parse_text_into_smatrix<I, T, const R: usize, const C: usize>(
+    source: &mut I,
+    separator: &str,
+    layout: MatrixLayout,
+) -> Result<SMatrix<T, R, C>, ParseArrayTextError>
+where
+    I: BufRead,
+    T: FromStr + nalgebra::Scalar,
+{
+    parse_text_into_omatrix(source, separator, Const::<C>, Const::<R>, layout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_layout() {
+        let text = "1 2 3\n4 5 6\n";
+        let row_major: DMatrix<i32> =
+            parse_text_into_matrix(&mut Cursor::new(text), " ", 3, 2, MatrixLayout::RowMajor)
+                .unwrap();
+        assert_eq!(
+            row_major,
+            DMatrix::from_row_slice(2, 3, &[1, 2, 3, 4, 5, 6])
+        );
+        let col_major: DMatrix<i32> =
+            parse_text_into_matrix(&mut Cursor::new(text), " ", 3, 2, MatrixLayout::ColumnMajor)
+                .unwrap();
+        assert_eq!(col_major, DMatrix::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]));
+    }
+
+    #[test]
+    fn test_separator() {
+        let text = "1.0, 2.0\n3.0,4.0\n";
+        let parsed: DMatrix<f64> =
+            parse_text_into_matrix(&mut Cursor::new(text), ",", 2, 2, MatrixLayout::RowMajor)
+                .unwrap();
+        assert_eq!(parsed, DMatrix::from_row_slice(2, 2, &[1.0, 2.0, 3.0, 4.0]));
+    }
+
+    #[test]
+    fn test_static_shapes() {
+        let text = "1.0 0.0 0.0\n0.0 2.0 0.0\n0.0 0.0 3.0\n";
+        let lattice: SMatrix<f64, 3, 3> =
+            parse_text_into_smatrix(&mut Cursor::new(text), " ", MatrixLayout::RowMajor).unwrap();
+        assert_eq!(
+            lattice,
+            nalgebra::Matrix3::from_diagonal(&nalgebra::Vector3::new(1.0, 2.0, 3.0))
+        );
+        let gradient: OMatrix<f64, Const<3>, Dyn> = parse_text_into_omatrix(
+            &mut Cursor::new("1 2 3\n4 5 6\n"),
+            " ",
+            Dyn(2),
+            Const::<3>,
+            MatrixLayout::ColumnMajor,
+        )
+        .unwrap();
+        assert_eq!(gradient.column(1), nalgebra::Vector3::new(4.0, 5.0, 6.0));
+        let wrong: Result<SMatrix<f64, 3, 3>, _> =
+            parse_text_into_smatrix(&mut Cursor::new("1 2 3\n"), " ", MatrixLayout::RowMajor);
+        assert!(matches!(
+            wrong,
+            Err(ParseArrayTextError::ShapeMismatch {
+                expected: 9,
+                found: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn test_shape_mismatch() {
+        let text = "1 2 3\n4 5\n";
+        let parsed: Result<DMatrix<i32>, _> =
+            parse_text_into_matrix(&mut Cursor::new(text), " ", 3, 2, MatrixLayout::RowMajor);
+        assert!(matches!(
+            parsed,
+            Err(ParseArrayTextError::ShapeMismatch {
+                expected: 6,
+                found: 5
+            })
+        ));
+    }
 }
@@ -0,0 +1,260 @@
+//! Reading and writing of packed triangular matrices, as they are commonly used to store
+//! Hessians, overlap or density matrices.
+//! ```text
+//! Lower triangle with diagonal, row by row:
+//!  a00
+//!  a10 a11
+//!  a20 a21 a22
+//! ```
+use nalgebra::{DMatrix, Scalar};
+use num::Zero;
+use std::fmt::{Display, LowerExp};
+use std::io::{BufRead, Write};
+use std::ops::Neg;
+use std::str::FromStr;
+
+use super::arraytexterrors::ParseArrayTextError;
+use super::writer::TextFormat;
+use super::{parse_token, tokenize};
+
+/// Which triangle of the matrix is stored, row by row.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TriangularPacking {
+    /// a00, a10, a11, a20, a21, a22, ...
+    #[default]
+    Lower,
+    /// a00, a01, a02, ..., a11, a12, ...
+    Upper,
+}
+
+/// How the missing triangle is reconstructed from the stored one.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixSymmetry {
+    /// a_ji = a_ij
+    #[default]
+    Symmetric,
+    /// a_ji = -a_ij
+    Antisymmetric,
+}
+
+/// Function to determine the order n of a square matrix from the number of elements in its
+/// packed triangle. Returns `None` if the number is not a triangular number.
+/// # Arguments
+///  * `nelements` - Number of packed elements.
+///  * `with_diagonal` - If the diagonal is part of the packed data, i.e. n(n+1)/2 instead of
+///    n(n-1)/2 elements.
+pub fn packed_order(nelements: usize, with_diagonal: bool) -> Option<usize> {
+    let root = ((8 * nelements + 1) as f64).sqrt().round() as usize;
+    if root * root != 8 * nelements + 1 {
+        return None;
+    }
+    let n = if with_diagonal {
+        (root - 1) / 2
+    } else {
+        root.div_ceil(2)
+    };
+    Some(n)
+}
+
+/// Yields the (row, column) indices of the packed elements in storage order.
+fn packed_indices(
+    n: usize,
+    packing: TriangularPacking,
+    with_diagonal: bool,
+) -> impl Iterator<Item = (usize, usize)> {
+    let offset = usize::from(!with_diagonal);
+    (0..n).flat_map(move |i| {
+        let columns = match packing {
+            TriangularPacking::Lower => 0..(i + 1).saturating_sub(offset),
+            TriangularPacking::Upper => (i + offset).min(n)..n,
+        };
+        columns.map(move |j| (i, j))
+    })
+}
+
+/// Function to parse a packed triangular matrix into a full square matrix. The order of the
+/// matrix is deduced from the number of elements.
+/// # Arguments
+///  * `source` - mutable reference of the file buffer.
+///  * `separator` - Seperator of the datapoints.
+///  * `packing` - Which triangle is stored.
+///  * `with_diagonal` - If the diagonal is stored. Otherwise it is set to zero.
+///  * `symmetry` - How to fill the other triangle.
+pub fn parse_packed_triangular<I, T>(
+    source: &mut I,
+    separator: &str,
+    packing: TriangularPacking,
+    with_diagonal: bool,
+    symmetry: MatrixSymmetry,
+) -> Result<DMatrix<T>, ParseArrayTextError>
+where
+    I: BufRead,
+    T: FromStr + Scalar + Zero + Neg<Output = T>,
+{
+    let mut packed: Vec<T> = Vec::new();
+    for line in source.lines() {
+        let line = line?;
+        for ele in tokenize(&line, separator) {
+            packed.push(parse_token(ele)?);
+        }
+    }
+    unpack_triangular(packed, packing, with_diagonal, symmetry)
+}
+
+/// Function to expand already parsed packed data into a full square matrix.
+/// # Arguments
+///  * `packed` - The packed elements in storage order.
+///  * `packing` - Which triangle is stored.
+///  * `with_diagonal` - If the diagonal is stored. Otherwise it is set to zero.
+///  * `symmetry` - How to fill the other triangle.
+pub fn unpack_triangular<T>(
+    packed: Vec<T>,
+    packing: TriangularPacking,
+    with_diagonal: bool,
+    symmetry: MatrixSymmetry,
+) -> Result<DMatrix<T>, ParseArrayTextError>
+where
+    T: Scalar + Zero + Neg<Output = T>,
+{
+    let n =
+        packed_order(packed.len(), with_diagonal).ok_or(ParseArrayTextError::NotTriangular {
+            found: packed.len(),
+        })?;
+    let mut result = DMatrix::<T>::zeros(n, n);
+    for ((i, j), value) in packed_indices(n, packing, with_diagonal).zip(packed) {
+        if i != j {
+            result[(j, i)] = match symmetry {
+                MatrixSymmetry::Symmetric => value.clone(),
+                MatrixSymmetry::Antisymmetric => -value.clone(),
+            };
+        } else if symmetry == MatrixSymmetry::Antisymmetric {
+            // The diagonal of an antisymmetric matrix vanishes by definition.
+            continue;
+        }
+        result[(i, j)] = value;
+    }
+    Ok(result)
+}
+
+/// Function to write one triangle of a square matrix in packed form. Without
+/// `values_per_line` each row of the triangle is written on its own line.
+/// # Arguments
+///  * `dest` - Where to write to.
+///  * `matrix` - Square matrix to pack.
+///  * `packing` - Which triangle to store.
+///  * `with_diagonal` - If the diagonal is stored.
+///  * `format` - How to print the values. The layout is ignored.
+pub fn write_packed_triangular<W, T>(
+    dest: &mut W,
+    matrix: &DMatrix<T>,
+    packing: TriangularPacking,
+    with_diagonal: bool,
+    format: &TextFormat,
+) -> std::io::Result<()>
+where
+    W: Write,
+    T: Scalar + Display + LowerExp,
+{
+    if !matrix.is_square() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Only square matrices can be packed.",
+        ));
+    }
+    let n = matrix.nrows();
+    let mut indices = packed_indices(n, packing, with_diagonal).peekable();
+    let rows = (0..n).map(|i| {
+        let mut row: Vec<&T> = Vec::new();
+        while let Some((_, j)) = indices.next_if(|(r, _)| *r == i) {
+            row.push(&matrix[(i, j)]);
+        }
+        row
+    });
+    format.write_lines(dest, rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_packed_order() {
+        assert_eq!(packed_order(6, true), Some(3));
+        assert_eq!(packed_order(3, false), Some(3));
+        assert_eq!(packed_order(0, true), Some(0));
+        assert_eq!(packed_order(5, true), None);
+    }
+
+    #[test]
+    fn test_lower_and_upper() {
+        let expected =
+            DMatrix::from_row_slice(3, 3, &[1.0, 2.0, 4.0, 2.0, 3.0, 5.0, 4.0, 5.0, 6.0]);
+        let lower: DMatrix<f64> = parse_packed_triangular(
+            &mut Cursor::new("1.0\n2.0 3.0\n4.0 5.0 6.0\n"),
+            " ",
+            TriangularPacking::Lower,
+            true,
+            MatrixSymmetry::Symmetric,
+        )
+        .unwrap();
+        assert_eq!(lower, expected);
+        let upper: DMatrix<f64> = parse_packed_triangular(
+            &mut Cursor::new("1.0 2.0 4.0\n3.0 5.0\n6.0\n"),
+            " ",
+            TriangularPacking::Upper,
+            true,
+            MatrixSymmetry::Symmetric,
+        )
+        .unwrap();
+        assert_eq!(upper, expected);
+    }
+
+    #[test]
+    fn test_antisymmetric_without_diagonal() {
+        let parsed: DMatrix<i32> = parse_packed_triangular(
+            &mut Cursor::new("1 2 3"),
+            " ",
+            TriangularPacking::Lower,
+            false,
+            MatrixSymmetry::Antisymmetric,
+        )
+        .unwrap();
+        assert_eq!(
+            parsed,
+            DMatrix::from_row_slice(3, 3, &[0, -1, -2, 1, 0, -3, 2, 3, 0])
+        );
+    }
+
+    #[test]
+    fn test_write_round_trip() {
+        let matrix = DMatrix::from_row_slice(3, 3, &[1, 2, 4, 2, 3, 5, 4, 5, 6]);
+        let mut written: Vec<u8> = Vec::new();
+        let format = TextFormat {
+            width: 0,
+            values_per_line: Some(4),
+            ..Default::default()
+        };
+        write_packed_triangular(
+            &mut written,
+            &matrix,
+            TriangularPacking::Upper,
+            true,
+            &format,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(written.clone()).unwrap(),
+            "1 2 4 3\n5 6\n"
+        );
+        let parsed: DMatrix<i32> = parse_packed_triangular(
+            &mut Cursor::new(written),
+            " ",
+            TriangularPacking::Upper,
+            true,
+            MatrixSymmetry::Symmetric,
+        )
+        .unwrap();
+        assert_eq!(parsed, matrix);
+    }
+}
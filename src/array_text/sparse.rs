@@ -0,0 +1,360 @@
+//! Reading of sparse data stored as lines of indices and a value, e.g. two-electron integrals
+//! `i j k l value` or sparse Hamiltonians `i j value`.
+//! With the `sparse` feature the entries can be converted into `nalgebra-sparse` matrices.
+use nalgebra::{DMatrix, Scalar};
+use num::Zero;
+use std::io::BufRead;
+use std::str::FromStr;
+
+use super::arraytexterrors::ParseArrayTextError;
+use super::parse_token;
+use super::tensor::Tensor;
+
+/// Whether the indices in the file count from zero or from one.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum IndexBase {
+    Zero,
+    /// Fortran style indices.
+    #[default]
+    One,
+}
+
+/// Position of the value relative to the indices on each line.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ValuePosition {
+    /// `value i j k l`, as in FCIDUMP files.
+    First,
+    /// `i j k l value`
+    #[default]
+    Last,
+}
+
+/// Permutational symmetry of the stored entries, which is used to unfold them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum IndexSymmetry {
+    /// Every element is stored explicitly.
+    #[default]
+    None,
+    /// Two index quantity with a_ij = a_ji.
+    Symmetric,
+    /// Real two-electron integrals in chemists' notation with
+    /// (ij|kl) = (ji|kl) = (ij|lk) = (ji|lk) = (kl|ij) = (lk|ij) = (kl|ji) = (lk|ji).
+    EightFold,
+}
+
+/// Entries of a sparse tensor of arbitrary rank with zero based indices.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseEntries<T> {
+    /// Number of indices per entry.
+    pub rank: usize,
+    /// The indices of all entries, `rank` consecutive ones belong to one entry.
+    pub indices: Vec<usize>,
+    /// The values of all entries.
+    pub values: Vec<T>,
+}
+
+impl<T> SparseEntries<T> {
+    /// Constructor for an empty set of entries.
+    /// # Arguments
+    ///  * `rank` - Number of indices per entry.
+    pub fn new(rank: usize) -> Self {
+        Self {
+            rank,
+            indices: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Adds an entry.
+    /// # Arguments
+    ///  * `index` - Zero based indices of the entry, must have length `rank`.
+    ///  * `value` - The value of the entry.
+    pub fn push(&mut self, index: &[usize], value: T) {
+        assert_eq!(index.len(), self.rank, "Index does not match the rank!");
+        self.indices.extend_from_slice(index);
+        self.values.push(value);
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Iterates over the indices and values of all entries.
+    pub fn iter(&self) -> impl Iterator<Item = (&[usize], &T)> {
+        self.indices
+            .chunks_exact(self.rank.max(1))
+            .zip(self.values.iter())
+    }
+
+    /// Returns the entries with all indices implied by the permutational symmetry added
+    /// explicitly. Each unique index occurs once per stored entry.
+    /// # Arguments
+    ///  * `symmetry` - The symmetry of the stored entries.
+    pub fn unfold(&self, symmetry: IndexSymmetry) -> Result<Self, ParseArrayTextError>
+    where
+        T: Clone,
+    {
+        let required_rank = match symmetry {
+            IndexSymmetry::None => self.rank,
+            IndexSymmetry::Symmetric => 2,
+            IndexSymmetry::EightFold => 4,
+        };
+        if self.rank != required_rank {
+            return Err(ParseArrayTextError::ShapeMismatch {
+                expected: required_rank,
+                found: self.rank,
+            });
+        }
+        let mut unfolded = Self::new(self.rank);
+        for (index, value) in self.iter() {
+            let mut permutations: Vec<Vec<usize>> = match symmetry {
+                IndexSymmetry::None => vec![index.to_vec()],
+                IndexSymmetry::Symmetric => {
+                    vec![vec![index[0], index[1]], vec![index[1], index[0]]]
+                }
+                IndexSymmetry::EightFold => {
+                    let (i, j, k, l) = (index[0], index[1], index[2], index[3]);
+                    vec![
+                        vec![i, j, k, l],
+                        vec![j, i, k, l],
+                        vec![i, j, l, k],
+                        vec![j, i, l, k],
+                        vec![k, l, i, j],
+                        vec![l, k, i, j],
+                        vec![k, l, j, i],
+                        vec![l, k, j, i],
+                    ]
+                }
+            };
+            permutations.sort_unstable();
+            permutations.dedup();
+            for p in permutations {
+                unfolded.push(&p, value.clone());
+            }
+        }
+        Ok(unfolded)
+    }
+
+    /// Fills a dense tensor with the entries. Missing elements are zero.
+    /// # Arguments
+    ///  * `shape` - Dimension of each index.
+    pub fn to_dense_tensor(&self, shape: &[usize]) -> Result<Tensor<T>, ParseArrayTextError>
+    where
+        T: Clone + Zero,
+    {
+        if shape.len() != self.rank {
+            return Err(ParseArrayTextError::ShapeMismatch {
+                expected: self.rank,
+                found: shape.len(),
+            });
+        }
+        let mut tensor = Tensor::from_element(shape.to_vec(), T::zero());
+        for (index, value) in self.iter() {
+            let element =
+                tensor
+                    .get_mut(index)
+                    .ok_or_else(|| ParseArrayTextError::IndexOutOfBounds {
+                        index: index.to_vec(),
+                        shape: shape.to_vec(),
+                    })?;
+            *element = value.clone();
+        }
+        Ok(tensor)
+    }
+
+    /// Fills a dense matrix with the entries of a two index quantity. Missing elements are zero.
+    /// # Arguments
+    ///  * `nrows` - Number of rows.
+    ///  * `ncols` - Number of columns.
+    pub fn to_dense_matrix(
+        &self,
+        nrows: usize,
+        ncols: usize,
+    ) -> Result<DMatrix<T>, ParseArrayTextError>
+    where
+        T: Scalar + Zero,
+    {
+        let tensor = self.to_dense_tensor(&[nrows, ncols])?;
+        tensor
+            .matrix(&[])
+            .ok_or(ParseArrayTextError::ShapeMismatch {
+                expected: 2,
+                found: self.rank,
+            })
+    }
+
+    /// Converts the entries into a sparse matrix in coordinate format. The indices of a four
+    /// index quantity are combined pairwise, i.e. (ij|kl) becomes the element (i * n2 + j,
+    /// k * n4 + l).
+    /// # Arguments
+    ///  * `shape` - Dimension of each of the two or four indices.
+    #[cfg(feature = "sparse")]
+    pub fn to_coo(
+        &self,
+        shape: &[usize],
+    ) -> Result<nalgebra_sparse::CooMatrix<T>, ParseArrayTextError>
+    where
+        T: Scalar,
+    {
+        let (nrows, ncols) = match shape {
+            [n1, n2] if self.rank == 2 => (*n1, *n2),
+            [n1, n2, n3, n4] if self.rank == 4 => (n1 * n2, n3 * n4),
+            _ => {
+                return Err(ParseArrayTextError::ShapeMismatch {
+                    expected: self.rank,
+                    found: shape.len(),
+                })
+            }
+        };
+        let mut coo = nalgebra_sparse::CooMatrix::new(nrows, ncols);
+        for (index, value) in self.iter() {
+            if index.iter().zip(shape.iter()).any(|(i, n)| i >= n) {
+                return Err(ParseArrayTextError::IndexOutOfBounds {
+                    index: index.to_vec(),
+                    shape: shape.to_vec(),
+                });
+            }
+            let (row, col) = match index {
+                [i, j] => (*i, *j),
+                [i, j, k, l] => (i * shape[1] + j, k * shape[3] + l),
+                _ => unreachable!(),
+            };
+            coo.push(row, col, value.clone());
+        }
+        Ok(coo)
+    }
+
+    /// Converts the entries into a sparse matrix in compressed row format, see
+    /// [`to_coo`](Self::to_coo). Duplicate entries are summed.
+    /// # Arguments
+    ///  * `shape` - Dimension of each of the two or four indices.
+    #[cfg(feature = "sparse")]
+    pub fn to_csr(
+        &self,
+        shape: &[usize],
+    ) -> Result<nalgebra_sparse::CsrMatrix<T>, ParseArrayTextError>
+    where
+        T: Scalar + Zero + std::ops::AddAssign,
+    {
+        Ok(nalgebra_sparse::CsrMatrix::from(&self.to_coo(shape)?))
+    }
+}
+
+/// Function to parse lines of indices and a value. Blank lines and lines starting with `#` or
+/// `!` are skipped.
+/// # Arguments
+///  * `source` - mutable reference of the file buffer.
+///  * `rank` - Number of indices on each line.
+///  * `base` - Whether the indices count from zero or one.
+///  * `position` - Whether the value precedes or follows the indices.
+pub fn parse_indexed_values<I, T>(
+    source: &mut I,
+    rank: usize,
+    base: IndexBase,
+    position: ValuePosition,
+) -> Result<SparseEntries<T>, ParseArrayTextError>
+where
+    I: BufRead,
+    T: FromStr,
+{
+    let mut entries = SparseEntries::new(rank);
+    let mut index = vec![0; rank];
+    for line in source.lines() {
+        let line = line?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with(['#', '!']) {
+            continue;
+        }
+        let tokens: Vec<&str> = trimmed.split_whitespace().collect();
+        if tokens.len() != rank + 1 {
+            return Err(ParseArrayTextError::ShapeMismatch {
+                expected: rank + 1,
+                found: tokens.len(),
+            });
+        }
+        let (value, index_tokens) = match position {
+            ValuePosition::First => (tokens[0], &tokens[1..]),
+            ValuePosition::Last => (tokens[rank], &tokens[..rank]),
+        };
+        for (i, token) in index.iter_mut().zip(index_tokens) {
+            let parsed: usize = parse_token(token)?;
+            *i = match base {
+                IndexBase::Zero => parsed,
+                IndexBase::One => parsed
+                    .checked_sub(1)
+                    .ok_or_else(|| ParseArrayTextError::ValueError(token.to_string()))?,
+            };
+        }
+        entries.push(&index, parse_token(value)?);
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_symmetric_matrix() {
+        let text = "# i j value\n1 1 2.0\n2 1 -1.0\n\n3 3 4.0\n";
+        let parsed: SparseEntries<f64> = parse_indexed_values(
+            &mut Cursor::new(text),
+            2,
+            IndexBase::One,
+            ValuePosition::Last,
+        )
+        .unwrap();
+        assert_eq!(parsed.len(), 3);
+        let dense = parsed
+            .unfold(IndexSymmetry::Symmetric)
+            .unwrap()
+            .to_dense_matrix(3, 3)
+            .unwrap();
+        assert_eq!(
+            dense,
+            DMatrix::from_row_slice(3, 3, &[2.0, -1.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0, 4.0])
+        );
+    }
+
+    #[test]
+    fn test_eight_fold() {
+        let text = "0.5 0 1 2 3\n0.25 1 1 0 0\n";
+        let parsed: SparseEntries<f64> = parse_indexed_values(
+            &mut Cursor::new(text),
+            4,
+            IndexBase::Zero,
+            ValuePosition::First,
+        )
+        .unwrap();
+        let unfolded = parsed.unfold(IndexSymmetry::EightFold).unwrap();
+        // (01|23) has eight distinct permutations, (11|00) only two.
+        assert_eq!(unfolded.len(), 10);
+        let tensor = unfolded.to_dense_tensor(&[4, 4, 4, 4]).unwrap();
+        assert_eq!(tensor[&[3, 2, 1, 0][..]], 0.5);
+        assert_eq!(tensor[&[2, 3, 0, 1][..]], 0.5);
+        assert_eq!(tensor[&[0, 0, 1, 1][..]], 0.25);
+        assert_eq!(tensor[&[0, 1, 0, 1][..]], 0.0);
+        assert!(matches!(
+            unfolded.to_dense_tensor(&[2, 2, 2, 2]),
+            Err(ParseArrayTextError::IndexOutOfBounds { .. })
+        ));
+    }
+
+    #[cfg(feature = "sparse")]
+    #[test]
+    fn test_csr() {
+        let mut entries = SparseEntries::new(2);
+        entries.push(&[0, 1], 1.5);
+        entries.push(&[2, 0], -1.0);
+        let csr = entries.to_csr(&[3, 3]).unwrap();
+        assert_eq!(csr.nnz(), 2);
+        assert_eq!(DMatrix::from(&csr), entries.to_dense_matrix(3, 3).unwrap());
+    }
+}
@@ -0,0 +1,340 @@
+//! Reading of several consecutive matrices and of higher rank tensors flattened to text, e.g.
+//! integral dumps, cubic force fields or polarizability derivatives.
+use nalgebra::{DMatrix, Dyn, Scalar};
+use std::io::BufRead;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+use super::arraytexterrors::ParseArrayTextError;
+use super::{parse_token, tokenize, values_into_matrix, MatrixLayout};
+
+/// How consecutive matrices are delimited in the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockSeparation {
+    /// Exactly this number of matrices follow each other. Only the lines holding them are read.
+    Count(usize),
+    /// The matrices are separated by blank lines. The source is read until its end.
+    BlankLines,
+}
+
+/// A dense tensor of arbitrary rank. The elements are stored in row-major order, i.e. the last
+/// index runs fastest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tensor<T> {
+    shape: Vec<usize>,
+    data: Vec<T>,
+}
+
+impl<T> Tensor<T> {
+    /// Constructor for a tensor from row-major ordered data.
+    /// # Arguments
+    ///  * `shape` - Dimension of each index.
+    ///  * `data` - The elements, last index running fastest.
+    pub fn from_vec(shape: Vec<usize>, data: Vec<T>) -> Result<Self, ParseArrayTextError> {
+        let expected: usize = shape.iter().product();
+        if expected != data.len() {
+            return Err(ParseArrayTextError::ShapeMismatch {
+                expected,
+                found: data.len(),
+            });
+        }
+        Ok(Self { shape, data })
+    }
+
+    /// Constructor for a tensor filled with a single value.
+    /// # Arguments
+    ///  * `shape` - Dimension of each index.
+    ///  * `value` - The value of every element.
+    pub fn from_element(shape: Vec<usize>, value: T) -> Self
+    where
+        T: Clone,
+    {
+        let len = shape.iter().product();
+        Self {
+            shape,
+            data: vec![value; len],
+        }
+    }
+
+    /// The dimension of each index.
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// The rank, i.e. the number of indices.
+    pub fn rank(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// The elements in row-major order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    /// The elements in row-major order.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    /// Position of a multi index in the row-major data, `None` if it is out of bounds.
+    fn flat_index(&self, index: &[usize]) -> Option<usize> {
+        if index.len() != self.shape.len() {
+            return None;
+        }
+        let mut flat = 0;
+        for (i, n) in index.iter().zip(self.shape.iter()) {
+            if i >= n {
+                return None;
+            }
+            flat = flat * n + i;
+        }
+        Some(flat)
+    }
+
+    /// Returns a reference to an element, `None` if the index is out of bounds.
+    pub fn get(&self, index: &[usize]) -> Option<&T> {
+        self.flat_index(index).map(|i| &self.data[i])
+    }
+
+    /// Returns a mutable reference to an element, `None` if the index is out of bounds.
+    pub fn get_mut(&mut self, index: &[usize]) -> Option<&mut T> {
+        self.flat_index(index).map(|i| &mut self.data[i])
+    }
+
+    /// Returns the matrix spanned by the last two indices for fixed leading indices, e.g. the
+    /// matrix (ij|..) of a four index tensor.
+    /// # Arguments
+    ///  * `leading` - Values of all but the last two indices.
+    pub fn matrix(&self, leading: &[usize]) -> Option<DMatrix<T>>
+    where
+        T: Scalar,
+    {
+        let rank = self.rank();
+        if rank < 2 || leading.len() != rank - 2 {
+            return None;
+        }
+        let (nrows, ncols) = (self.shape[rank - 2], self.shape[rank - 1]);
+        let mut first = leading.to_vec();
+        first.extend([0, 0]);
+        let start = self.flat_index(&first)?;
+        Some(DMatrix::from_row_slice(
+            nrows,
+            ncols,
+            &self.data[start..start + nrows * ncols],
+        ))
+    }
+}
+
+impl<T> Index<&[usize]> for Tensor<T> {
+    type Output = T;
+    fn index(&self, index: &[usize]) -> &Self::Output {
+        self.get(index).expect("Tensor index out of bounds!")
+    }
+}
+
+impl<T> IndexMut<&[usize]> for Tensor<T> {
+    fn index_mut(&mut self, index: &[usize]) -> &mut Self::Output {
+        self.get_mut(index).expect("Tensor index out of bounds!")
+    }
+}
+
+/// Reads lines until `count` values are parsed. Fails if a line holds values beyond them.
+fn read_values<I, T>(
+    source: &mut I,
+    separator: &str,
+    count: usize,
+) -> Result<Vec<T>, ParseArrayTextError>
+where
+    I: BufRead,
+    T: FromStr,
+{
+    let mut values: Vec<T> = Vec::with_capacity(count);
+    let mut line = String::new();
+    while values.len() < count {
+        line.clear();
+        if source.read_line(&mut line)? == 0 {
+            break;
+        }
+        for ele in tokenize(&line, separator) {
+            values.push(parse_token(ele)?);
+        }
+    }
+    if values.len() != count {
+        return Err(ParseArrayTextError::ShapeMismatch {
+            expected: count,
+            found: values.len(),
+        });
+    }
+    Ok(values)
+}
+
+/// Function to parse several consecutive matrices of equal shape.
+/// # Arguments
+///  * `source` - mutable reference of the file buffer.
+///  * `separator` - Seperator of the datapoints.
+///  * `ncols` - Number of columns of each matrix.
+///  * `nrows` - Number of rows of each matrix.
+///  * `separation` - How the matrices are delimited.
+///  * `layout` - Order in which the elements of each matrix are stored.
+pub fn parse_text_into_matrices<I, T>(
+    source: &mut I,
+    separator: &str,
+    ncols: usize,
+    nrows: usize,
+    separation: BlockSeparation,
+    layout: MatrixLayout,
+) -> Result<Vec<DMatrix<T>>, ParseArrayTextError>
+where
+    I: BufRead,
+    T: FromStr + Scalar,
+{
+    let nelements = nrows * ncols;
+    match separation {
+        BlockSeparation::Count(nblocks) => {
+            let mut values =
+                read_values::<I, T>(source, separator, nblocks * nelements)?.into_iter();
+            let mut matrices = Vec::with_capacity(nblocks);
+            for _ in 0..nblocks {
+                let block: Vec<T> = values.by_ref().take(nelements).collect();
+                matrices.push(values_into_matrix(block, Dyn(nrows), Dyn(ncols), layout)?);
+            }
+            Ok(matrices)
+        }
+        BlockSeparation::BlankLines => {
+            let mut matrices = Vec::new();
+            let mut values: Vec<T> = Vec::with_capacity(nelements);
+            for line in source.lines() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    if !values.is_empty() {
+                        matrices.push(values_into_matrix(values, Dyn(nrows), Dyn(ncols), layout)?);
+                        values = Vec::with_capacity(nelements);
+                    }
+                    continue;
+                }
+                for ele in tokenize(&line, separator) {
+                    values.push(parse_token(ele)?);
+                }
+            }
+            if !values.is_empty() {
+                matrices.push(values_into_matrix(values, Dyn(nrows), Dyn(ncols), layout)?);
+            }
+            Ok(matrices)
+        }
+    }
+}
+
+/// Function to parse a dense tensor of arbitrary rank. Only the lines holding its elements are
+/// read.
+/// # Arguments
+///  * `source` - mutable reference of the file buffer.
+///  * `separator` - Seperator of the datapoints.
+///  * `shape` - Dimension of each index.
+///  * `layout` - `RowMajor` if the last index runs fastest in the file, `ColumnMajor` if the
+///    first one does.
+pub fn parse_text_into_tensor<I, T>(
+    source: &mut I,
+    separator: &str,
+    shape: &[usize],
+    layout: MatrixLayout,
+) -> Result<Tensor<T>, ParseArrayTextError>
+where
+    I: BufRead,
+    T: FromStr + Clone,
+{
+    let count: usize = shape.iter().product();
+    let values = read_values::<I, T>(source, separator, count)?;
+    let data = match layout {
+        MatrixLayout::RowMajor => values,
+        MatrixLayout::ColumnMajor => {
+            // Element k of the row-major data is found by reading its multi index backwards.
+            (0..count)
+                .map(|k| {
+                    let mut rest = k;
+                    let mut position = 0;
+                    let mut stride = 1;
+                    let mut multi_index = vec![0; shape.len()];
+                    for (m, n) in multi_index.iter_mut().zip(shape.iter()).rev() {
+                        *m = rest % n;
+                        rest /= n;
+                    }
+                    for (m, n) in multi_index.iter().zip(shape.iter()) {
+                        position += m * stride;
+                        stride *= n;
+                    }
+                    values[position].clone()
+                })
+                .collect()
+        }
+    };
+    Tensor::from_vec(shape.to_vec(), data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_matrices_by_count() {
+        let mut source = Cursor::new("1 2\n3 4\n5 6\n7 8\n9 10\n");
+        let parsed: Vec<DMatrix<i32>> = parse_text_into_matrices(
+            &mut source,
+            " ",
+            2,
+            2,
+            BlockSeparation::Count(2),
+            MatrixLayout::RowMajor,
+        )
+        .unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[1], DMatrix::from_row_slice(2, 2, &[5, 6, 7, 8]));
+        let mut rest = String::new();
+        source.read_line(&mut rest).unwrap();
+        assert_eq!(rest, "9 10\n");
+    }
+
+    #[test]
+    fn test_matrices_by_blank_lines() {
+        let text = "1 2\n3 4\n\n\n5 6\n7 8\n";
+        let parsed: Vec<DMatrix<i32>> = parse_text_into_matrices(
+            &mut Cursor::new(text),
+            " ",
+            2,
+            2,
+            BlockSeparation::BlankLines,
+            MatrixLayout::ColumnMajor,
+        )
+        .unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0], DMatrix::from_row_slice(2, 2, &[1, 3, 2, 4]));
+    }
+
+    #[test]
+    fn test_tensor_orders() {
+        let text = "0 1 2 3 4 5 6 7 8 9 10 11";
+        let row_major: Tensor<i32> = parse_text_into_tensor(
+            &mut Cursor::new(text),
+            " ",
+            &[2, 3, 2],
+            MatrixLayout::RowMajor,
+        )
+        .unwrap();
+        assert_eq!(row_major[&[1, 2, 0][..]], 10);
+        assert_eq!(
+            row_major.matrix(&[1]).unwrap(),
+            DMatrix::from_row_slice(3, 2, &[6, 7, 8, 9, 10, 11])
+        );
+        let col_major: Tensor<i32> = parse_text_into_tensor(
+            &mut Cursor::new(text),
+            " ",
+            &[2, 3, 2],
+            MatrixLayout::ColumnMajor,
+        )
+        .unwrap();
+        // Position in the file is i + 2 * j + 6 * k.
+        assert_eq!(col_major[&[1, 2, 0][..]], 5);
+        assert_eq!(col_major[&[0, 1, 1][..]], 8);
+        assert!(col_major.get(&[2, 0, 0]).is_none());
+    }
+}
@@ -0,0 +1,155 @@
+//! Writing of matrices as uniform text, which can be read back with
+//! [`parse_text_into_matrix`](super::parse_text_into_matrix).
+use nalgebra::{DMatrix, Scalar};
+use std::fmt::{Display, LowerExp};
+use std::io::Write;
+
+use super::MatrixLayout;
+
+/// Describes how the values of a matrix are printed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextFormat {
+    /// Printed between two values on the same line.
+    pub separator: String,
+    /// Minimum field width, values are right aligned.
+    pub width: usize,
+    /// Number of decimal places. Ignored for integers.
+    pub precision: usize,
+    /// Print floats as mantissa and exponent, e.g. `1.0000e-3`.
+    pub scientific: bool,
+    /// Number of values per line. `None` starts a new line after each row (or column).
+    pub values_per_line: Option<usize>,
+    /// Order in which the elements are written.
+    pub layout: MatrixLayout,
+}
+
+/// Implements the default for TextFormat.
+impl Default for TextFormat {
+    // Default is one row per line of white space separated values with ten decimal places.
+    fn default() -> Self {
+        Self {
+            separator: " ".to_string(),
+            width: 20,
+            precision: 10,
+            scientific: false,
+            values_per_line: None,
+            layout: MatrixLayout::RowMajor,
+        }
+    }
+}
+
+impl TextFormat {
+    /// Formats a single value according to width, precision and notation.
+    /// # Arguments
+    ///  * `value` - The value to format.
+    pub fn format_value<T>(&self, value: &T) -> String
+    where
+        T: Display + LowerExp,
+    {
+        if self.scientific {
+            format!("{:>w$.p$e}", value, w = self.width, p = self.precision)
+        } else {
+            format!("{:>w$.p$}", value, w = self.width, p = self.precision)
+        }
+    }
+
+    /// Writes a sequence of values, which is divided into lines of `values_per_line` values or,
+    /// if that is not set, into the given natural lines.
+    pub(crate) fn write_lines<'a, W, T, L>(&self, dest: &mut W, lines: L) -> std::io::Result<()>
+    where
+        W: Write,
+        T: Display + LowerExp + 'a,
+        L: Iterator<Item = Vec<&'a T>>,
+    {
+        let mut count = 0;
+        for natural_line in lines {
+            for value in natural_line {
+                if count > 0 {
+                    write!(dest, "{}", self.separator)?;
+                }
+                write!(dest, "{}", self.format_value(value))?;
+                count += 1;
+                if self.values_per_line.is_some_and(|n| count == n) {
+                    writeln!(dest)?;
+                    count = 0;
+                }
+            }
+            if self.values_per_line.is_none() && count > 0 {
+                writeln!(dest)?;
+                count = 0;
+            }
+        }
+        if count > 0 {
+            writeln!(dest)?;
+        }
+        Ok(())
+    }
+}
+
+/// Function to write a matrix as uniform text.
+/// # Arguments
+///  * `dest` - Where to write to.
+///  * `matrix` - The matrix to write.
+///  * `format` - How to print the values.
+pub fn write_matrix<W, T>(
+    dest: &mut W,
+    matrix: &DMatrix<T>,
+    format: &TextFormat,
+) -> std::io::Result<()>
+where
+    W: Write,
+    T: Scalar + Display + LowerExp,
+{
+    let (nrows, ncols) = matrix.shape();
+    match format.layout {
+        MatrixLayout::RowMajor => format.write_lines(
+            dest,
+            (0..nrows).map(|i| (0..ncols).map(|j| &matrix[(i, j)]).collect()),
+        ),
+        MatrixLayout::ColumnMajor => format.write_lines(
+            dest,
+            (0..ncols).map(|j| (0..nrows).map(|i| &matrix[(i, j)]).collect()),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array_text::parse_text_into_matrix;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_write_round_trip() {
+        let matrix = DMatrix::from_row_slice(2, 3, &[1.5, -2.0, 3.25e-5, 4.0, 5.0, -6.0e3]);
+        for layout in [MatrixLayout::RowMajor, MatrixLayout::ColumnMajor] {
+            for scientific in [false, true] {
+                let format = TextFormat {
+                    separator: ",".to_string(),
+                    width: 0,
+                    precision: 12,
+                    scientific,
+                    values_per_line: Some(4),
+                    layout,
+                };
+                let mut written: Vec<u8> = Vec::new();
+                write_matrix(&mut written, &matrix, &format).unwrap();
+                let parsed: DMatrix<f64> =
+                    parse_text_into_matrix(&mut Cursor::new(written), ",", 3, 2, layout).unwrap();
+                assert_eq!(parsed, matrix);
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_rows() {
+        let matrix = DMatrix::from_row_slice(2, 2, &[1, 2, 3, 4]);
+        let format = TextFormat {
+            width: 3,
+            ..Default::default()
+        };
+        let mut written: Vec<u8> = Vec::new();
+        write_matrix(&mut written, &matrix, &format).unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), "  1   2\n  3   4\n");
+    }
+}
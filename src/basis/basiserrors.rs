@@ -0,0 +1,57 @@
+use std::error;
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum ParseBasisError {
+    /// Raised when the underlying reader or writer fails.
+    Io(std::io::Error),
+    /// Raised when the file ends within a shell or a potential.
+    UnexpectedEof,
+    /// Raised when a line cannot be parsed.
+    InvalidLine(String),
+    /// Raised when the angular momentum of a shell is not known.
+    UnknownShell(String),
+    /// Raised when a function or potential type is not supported.
+    Unsupported(String),
+    /// Raised when a Basis Set Exchange JSON file cannot be read or written.
+    #[cfg(feature = "bse")]
+    Json(serde_json::Error),
+}
+
+impl Display for ParseBasisError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Could not read the basis set file: {}", e),
+            Self::UnexpectedEof => write!(f, "The basis set file ended unexpectedly."),
+            Self::InvalidLine(line) => write!(f, "Could not parse the line '{}'.", line),
+            Self::UnknownShell(label) => write!(f, "Unknown shell type '{}'.", label),
+            Self::Unsupported(kind) => write!(f, "'{}' is not supported.", kind),
+            #[cfg(feature = "bse")]
+            Self::Json(e) => write!(f, "Invalid Basis Set Exchange JSON: {}", e),
+        }
+    }
+}
+
+impl error::Error for ParseBasisError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            #[cfg(feature = "bse")]
+            Self::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseBasisError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+#[cfg(feature = "bse")]
+impl From<serde_json::Error> for ParseBasisError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
@@ -0,0 +1,366 @@
+//! Reading and writing of basis sets in the JSON schema of the
+//! [Basis Set Exchange](https://www.basissetexchange.org). Elements are keyed by their atomic
+//! number and all exponents and coefficients are given as strings.
+//! ```text
+//! {
+//!   "molssi_bse_schema": {"schema_type": "complete", "schema_version": "0.1"},
+//!   "name": "STO-3G",
+//!   "elements": {
+//!     "1": {
+//!       "references": [{"reference_description": "...", "reference_keys": ["hehre1969a"]}],
+//!       "electron_shells": [{"function_type": "gto", "region": "", "angular_momentum": [0],
+//!         "exponents": ["0.3425250914E+01", ...], "coefficients": [["0.1543289673E+00", ...]]}]
+//!     }
+//!   },
+//!   "references": {"hehre1969a": {"type": "article", "authors": [...], ...}}
+//! }
+//! ```
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+
+use crate::format_string::parse_fortran_real;
+use crate::xyz::element_label;
+use crate::xyz::xyzline::symbol::PSE_SYMBOLS;
+
+use super::basiserrors::ParseBasisError;
+use super::{
+    BasisSet, Ecp, EcpPotential, ElementBasis, ElementReference, FunctionType, Reference, Shell,
+};
+
+/// The only potential type of the schema, which is supported.
+const SCALAR_ECP: &str = "scalar_ecp";
+
+/// A number, which the schema stores as string.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum BseNumber {
+    Text(String),
+    Value(f64),
+}
+
+impl BseNumber {
+    fn value(&self) -> Result<f64, ParseBasisError> {
+        match self {
+            Self::Text(text) => parse_fortran_real::<f64>(text.trim())
+                .map_err(|_| ParseBasisError::InvalidLine(text.clone())),
+            Self::Value(value) => Ok(*value),
+        }
+    }
+}
+
+impl From<f64> for BseNumber {
+    fn from(value: f64) -> Self {
+        Self::Text(format!("{:E}", value))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BseSchema {
+    schema_type: String,
+    schema_version: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BseElementReference {
+    reference_description: String,
+    reference_keys: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BseShell {
+    function_type: String,
+    #[serde(default)]
+    region: String,
+    angular_momentum: Vec<usize>,
+    exponents: Vec<BseNumber>,
+    coefficients: Vec<Vec<BseNumber>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BsePotential {
+    ecp_type: String,
+    angular_momentum: Vec<usize>,
+    r_exponents: Vec<i32>,
+    gaussian_exponents: Vec<BseNumber>,
+    coefficients: Vec<Vec<BseNumber>>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BseElement {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    references: Vec<BseElementReference>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    electron_shells: Vec<BseShell>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ecp_electrons: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    ecp_potentials: Vec<BsePotential>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BseFile {
+    molssi_bse_schema: BseSchema,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    function_types: Vec<String>,
+    elements: BTreeMap<usize, BseElement>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    references: Map<String, Value>,
+}
+
+/// Returns the element symbol of an atomic number, e.g. `Na` for 11.
+fn element_symbol(z: usize) -> Result<String, ParseBasisError> {
+    let symbol = PSE_SYMBOLS
+        .get(z)
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| ParseBasisError::InvalidLine(format!("element {}", z)))?;
+    Ok(element_label(symbol))
+}
+
+/// Returns the atomic number of an element symbol.
+fn atomic_number(symbol: &str) -> Result<usize, ParseBasisError> {
+    PSE_SYMBOLS
+        .iter()
+        .skip(1)
+        .position(|s| s.eq_ignore_ascii_case(symbol))
+        .map(|p| p + 1)
+        .ok_or_else(|| ParseBasisError::InvalidLine(symbol.to_string()))
+}
+
+/// Converts the values of a list of numbers.
+fn values(numbers: &[BseNumber]) -> Result<Vec<f64>, ParseBasisError> {
+    numbers.iter().map(|n| n.value()).collect()
+}
+
+/// Converts a shell of the schema.
+fn shell_from_bse(shell: &BseShell) -> Result<Shell, ParseBasisError> {
+    let function_type = match shell.function_type.as_str() {
+        "gto" => FunctionType::Gto,
+        "gto_spherical" => FunctionType::Spherical,
+        "gto_cartesian" => FunctionType::Cartesian,
+        other => return Err(ParseBasisError::Unsupported(other.to_string())),
+    };
+    Ok(Shell {
+        function_type,
+        angular_momentum: shell.angular_momentum.clone(),
+        exponents: values(&shell.exponents)?,
+        coefficients: shell
+            .coefficients
+            .iter()
+            .map(|c| values(c))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Converts the potentials of the schema.
+fn ecp_from_bse(
+    core_electrons: usize,
+    potentials: &[BsePotential],
+) -> Result<Ecp, ParseBasisError> {
+    let potentials = potentials
+        .iter()
+        .map(|p| {
+            if p.ecp_type != SCALAR_ECP {
+                return Err(ParseBasisError::Unsupported(p.ecp_type.clone()));
+            }
+            let (&[angular_momentum], [coefficients]) =
+                (p.angular_momentum.as_slice(), p.coefficients.as_slice())
+            else {
+                return Err(ParseBasisError::InvalidLine(format!(
+                    "potential with angular momentum {:?}",
+                    p.angular_momentum
+                )));
+            };
+            Ok(EcpPotential {
+                angular_momentum,
+                r_exponents: p.r_exponents.clone(),
+                gaussian_exponents: values(&p.gaussian_exponents)?,
+                coefficients: values(coefficients)?,
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Ecp {
+        core_electrons,
+        potentials,
+    })
+}
+
+/// Converts a reference of the schema, whose fields other than `type` and `authors` are kept
+/// as text.
+fn reference_from_bse(key: &str, value: &Value) -> Reference {
+    let mut reference = Reference {
+        key: key.to_string(),
+        kind: String::new(),
+        authors: Vec::new(),
+        fields: Vec::new(),
+    };
+    let Some(object) = value.as_object() else {
+        return reference;
+    };
+    for (field, value) in object {
+        match (field.as_str(), value) {
+            ("type", Value::String(kind)) => reference.kind = kind.clone(),
+            ("authors", Value::Array(authors)) => {
+                reference.authors = authors
+                    .iter()
+                    .map(|a| a.as_str().map_or_else(|| a.to_string(), str::to_string))
+                    .collect()
+            }
+            (_, Value::String(text)) => reference.fields.push((field.clone(), text.clone())),
+            (_, other) => reference.fields.push((field.clone(), other.to_string())),
+        }
+    }
+    reference
+}
+
+/// Function to parse a basis set in the JSON schema of the Basis Set Exchange. Only scalar
+/// effective core potentials are supported.
+/// # Arguments
+///  * `source` - mutable reference of the file buffer.
+pub fn parse_bse_json<I: BufRead>(source: &mut I) -> Result<BasisSet, ParseBasisError> {
+    let file: BseFile = serde_json::from_reader(source)?;
+    let mut elements = Vec::with_capacity(file.elements.len());
+    for (z, element) in file.elements.iter() {
+        let ecp = match element.ecp_electrons {
+            Some(core_electrons) => Some(ecp_from_bse(core_electrons, &element.ecp_potentials)?),
+            None if element.ecp_potentials.is_empty() => None,
+            None => Some(ecp_from_bse(0, &element.ecp_potentials)?),
+        };
+        elements.push(ElementBasis {
+            symbol: element_symbol(*z)?,
+            shells: element
+                .electron_shells
+                .iter()
+                .map(shell_from_bse)
+                .collect::<Result<_, _>>()?,
+            ecp,
+            references: element
+                .references
+                .iter()
+                .map(|r| ElementReference {
+                    description: r.reference_description.clone(),
+                    keys: r.reference_keys.clone(),
+                })
+                .collect(),
+        });
+    }
+    Ok(BasisSet {
+        name: file.name,
+        description: file.description,
+        elements,
+        references: file
+            .references
+            .iter()
+            .map(|(key, value)| reference_from_bse(key, value))
+            .collect(),
+    })
+}
+
+/// Function to write a basis set in the JSON schema of the Basis Set Exchange.
+/// # Arguments
+///  * `dest` - Where to write to.
+///  * `basis` - The basis set to write.
+pub fn write_bse_json<W: Write>(dest: &mut W, basis: &BasisSet) -> Result<(), ParseBasisError> {
+    let mut function_types: Vec<String> = Vec::new();
+    let mut elements = BTreeMap::new();
+    for element in basis.elements.iter() {
+        let mut bse = BseElement {
+            references: element
+                .references
+                .iter()
+                .map(|r| BseElementReference {
+                    reference_description: r.description.clone(),
+                    reference_keys: r.keys.clone(),
+                })
+                .collect(),
+            ..Default::default()
+        };
+        for shell in element.shells.iter() {
+            let function_type = match shell.function_type {
+                FunctionType::Gto => "gto",
+                FunctionType::Spherical => "gto_spherical",
+                FunctionType::Cartesian => "gto_cartesian",
+            };
+            if !function_types.iter().any(|f| f == function_type) {
+                function_types.push(function_type.to_string());
+            }
+            bse.electron_shells.push(BseShell {
+                function_type: function_type.to_string(),
+                region: String::new(),
+                angular_momentum: shell.angular_momentum.clone(),
+                exponents: shell.exponents.iter().map(|&e| e.into()).collect(),
+                coefficients: shell
+                    .coefficients
+                    .iter()
+                    .map(|c| c.iter().map(|&v| v.into()).collect())
+                    .collect(),
+            });
+        }
+        if let Some(ecp) = element.ecp.as_ref() {
+            if !function_types.iter().any(|f| f == SCALAR_ECP) {
+                function_types.push(SCALAR_ECP.to_string());
+            }
+            bse.ecp_electrons = Some(ecp.core_electrons);
+            bse.ecp_potentials = ecp
+                .potentials
+                .iter()
+                .map(|p| BsePotential {
+                    ecp_type: SCALAR_ECP.to_string(),
+                    angular_momentum: vec![p.angular_momentum],
+                    r_exponents: p.r_exponents.clone(),
+                    gaussian_exponents: p.gaussian_exponents.iter().map(|&g| g.into()).collect(),
+                    coefficients: vec![p.coefficients.iter().map(|&c| c.into()).collect()],
+                })
+                .collect();
+        }
+        elements.insert(atomic_number(&element.symbol)?, bse);
+    }
+    let references = basis
+        .references
+        .iter()
+        .map(|r| {
+            let mut object = Map::new();
+            object.insert("type".to_string(), Value::String(r.kind.clone()));
+            object.insert(
+                "authors".to_string(),
+                Value::Array(r.authors.iter().cloned().map(Value::String).collect()),
+            );
+            for (field, text) in r.fields.iter() {
+                object.insert(field.clone(), Value::String(text.clone()));
+            }
+            (r.key.clone(), Value::Object(object))
+        })
+        .collect();
+    let file = BseFile {
+        molssi_bse_schema: BseSchema {
+            schema_type: "complete".to_string(),
+            schema_version: "0.1".to_string(),
+        },
+        name: basis.name.clone(),
+        description: basis.description.clone(),
+        function_types,
+        elements,
+        references,
+    };
+    serde_json::to_writer_pretty(&mut *dest, &file)?;
+    writeln!(dest)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_element_symbols() {
+        assert_eq!(element_symbol(11).unwrap(), "Na");
+        assert_eq!(atomic_number("NA").unwrap(), 11);
+        assert!(element_symbol(0).is_err());
+        assert!(atomic_number("X").is_err());
+    }
+}
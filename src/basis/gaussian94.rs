@@ -0,0 +1,257 @@
+//! Reading and writing of basis sets in the Gaussian94 format, which Psi4 also uses for its
+//! `.gbs` files. Each element block is terminated by `****`, every shell starts with its label,
+//! the number of primitives and a scale factor. Effective core potentials follow the basis sets.
+//! ```text
+//! spherical
+//! ****
+//! O     0
+//! S   3   1.00
+//!       0.1307093214D+03       0.1543289673D+00
+//! ...
+//! ****
+//!
+//! O     0
+//! O-ECP     1      2
+//! p potential
+//!   1
+//! 2      1.0000000000          0.0000000000
+//! s-p potential
+//! ...
+//! ```
+use std::io::{BufRead, Write};
+use std::iter::Peekable;
+
+use crate::format_string::format_fortran_real;
+
+use super::basiserrors::ParseBasisError;
+use super::{
+    angular_momenta, parse_ecp_term, parse_reals, shell_letter, BasisSet, Ecp, EcpPotential,
+    FunctionType, Shell,
+};
+
+/// Whether a line separates element blocks.
+fn is_separator(line: &str) -> bool {
+    line.starts_with("****")
+}
+
+/// Function to read the shells of an element up to the next `****`.
+fn parse_shells<L>(
+    lines: &mut Peekable<L>,
+    function_type: FunctionType,
+) -> Result<Vec<Shell>, ParseBasisError>
+where
+    L: Iterator<Item = String>,
+{
+    let mut shells = Vec::new();
+    while let Some(header) = lines.next_if(|l| !is_separator(l)) {
+        let invalid = || ParseBasisError::InvalidLine(header.clone());
+        let tokens: Vec<&str> = header.split_whitespace().collect();
+        if tokens.len() < 2 {
+            return Err(invalid());
+        }
+        let angular_momentum = angular_momenta(tokens[0])?;
+        let nprimitives = tokens[1].parse::<usize>().map_err(|_| invalid())?;
+        let scale = match tokens.get(2) {
+            Some(t) => parse_reals(t)?[0],
+            None => 1.0,
+        };
+        let mut exponents = Vec::with_capacity(nprimitives);
+        let mut coefficients: Vec<Vec<f64>> = Vec::new();
+        for _ in 0..nprimitives {
+            let line = lines.next().ok_or(ParseBasisError::UnexpectedEof)?;
+            let values = parse_reals(&line)?;
+            if values.len() < 2
+                || (angular_momentum.len() > 1 && values.len() != angular_momentum.len() + 1)
+            {
+                return Err(ParseBasisError::InvalidLine(line));
+            }
+            if coefficients.is_empty() {
+                coefficients = vec![Vec::with_capacity(nprimitives); values.len() - 1];
+            } else if coefficients.len() != values.len() - 1 {
+                return Err(ParseBasisError::InvalidLine(line));
+            }
+            exponents.push(if scale == 0.0 {
+                values[0]
+            } else {
+                values[0] * scale * scale
+            });
+            for (c, v) in coefficients.iter_mut().zip(&values[1..]) {
+                c.push(*v);
+            }
+        }
+        shells.push(Shell {
+            function_type,
+            angular_momentum,
+            exponents,
+            coefficients,
+        });
+    }
+    Ok(shells)
+}
+
+/// Function to read an effective core potential after its `ELEMENT-ECP lmax ncore` line.
+/// Potentials without terms are dropped.
+fn parse_ecp<L>(lines: &mut Peekable<L>, header: &str) -> Result<Ecp, ParseBasisError>
+where
+    L: Iterator<Item = String>,
+{
+    let invalid = || ParseBasisError::InvalidLine(header.to_string());
+    let tokens: Vec<&str> = header.split_whitespace().collect();
+    if tokens.len() != 3 {
+        return Err(invalid());
+    }
+    let lmax = tokens[1].parse::<usize>().map_err(|_| invalid())?;
+    let core_electrons = tokens[2].parse::<usize>().map_err(|_| invalid())?;
+    let mut potentials = Vec::with_capacity(lmax + 1);
+    for angular_momentum in std::iter::once(lmax).chain(0..lmax) {
+        lines.next().ok_or(ParseBasisError::UnexpectedEof)?;
+        let count_line = lines.next().ok_or(ParseBasisError::UnexpectedEof)?;
+        let nterms = count_line
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| ParseBasisError::InvalidLine(count_line.clone()))?;
+        let mut potential = EcpPotential {
+            angular_momentum,
+            r_exponents: Vec::with_capacity(nterms),
+            gaussian_exponents: Vec::with_capacity(nterms),
+            coefficients: Vec::with_capacity(nterms),
+        };
+        for _ in 0..nterms {
+            let line = lines.next().ok_or(ParseBasisError::UnexpectedEof)?;
+            let (r, g, c) = parse_ecp_term(&line)?;
+            potential.r_exponents.push(r);
+            potential.gaussian_exponents.push(g);
+            potential.coefficients.push(c);
+        }
+        if nterms > 0 {
+            potentials.push(potential);
+        }
+    }
+    Ok(Ecp {
+        core_electrons,
+        potentials,
+    })
+}
+
+/// Function to parse a basis set in the Gaussian94 format. A leading `spherical` or `cartesian`
+/// line as in Psi4 `.gbs` files sets the function type of all shells. Comments start with `!`.
+/// # Arguments
+///  * `source` - mutable reference of the file buffer.
+pub fn parse_gaussian94<I: BufRead>(source: &mut I) -> Result<BasisSet, ParseBasisError> {
+    let lines = source.lines().collect::<Result<Vec<String>, _>>()?;
+    let mut lines = lines
+        .into_iter()
+        .map(|l| l.split('!').next().unwrap_or_default().trim().to_string())
+        .filter(|l| !l.is_empty())
+        .peekable();
+    let mut basis = BasisSet::default();
+    let mut function_type = FunctionType::Gto;
+    while let Some(line) = lines.next() {
+        match line.to_lowercase().as_str() {
+            "spherical" => function_type = FunctionType::Spherical,
+            "cartesian" => function_type = FunctionType::Cartesian,
+            _ if is_separator(&line) => (),
+            _ => {
+                let symbol = line
+                    .split_whitespace()
+                    .next()
+                    .ok_or_else(|| ParseBasisError::InvalidLine(line.clone()))?;
+                let element = basis.element_mut(symbol);
+                match lines.next_if(|l| {
+                    l.split_whitespace()
+                        .next()
+                        .is_some_and(|t| t.to_uppercase().ends_with("-ECP"))
+                }) {
+                    Some(header) => element.ecp = Some(parse_ecp(&mut lines, &header)?),
+                    None => element
+                        .shells
+                        .extend(parse_shells(&mut lines, function_type)?),
+                }
+            }
+        }
+    }
+    Ok(basis)
+}
+
+/// Function to write a basis set in the Gaussian94 format. General contractions are split into
+/// one shell per contraction.
+/// # Arguments
+///  * `dest` - Where to write to.
+///  * `basis` - The basis set to write.
+pub fn write_gaussian94<W: Write>(dest: &mut W, basis: &BasisSet) -> Result<(), ParseBasisError> {
+    writeln!(dest, "****")?;
+    for element in basis.elements.iter().filter(|e| !e.shells.is_empty()) {
+        writeln!(dest, "{:<5} 0", element.symbol)?;
+        for shell in element.shells.iter().flat_map(|s| s.segmented()) {
+            writeln!(
+                dest,
+                "{:<3} {:3}   1.00",
+                shell.label(),
+                shell.nprimitives()
+            )?;
+            for (p, exponent) in shell.exponents.iter().enumerate() {
+                write!(dest, "{}", format_fortran_real(*exponent, 22, 14, 'D'))?;
+                for c in shell.coefficients.iter() {
+                    write!(dest, "{}", format_fortran_real(c[p], 22, 14, 'D'))?;
+                }
+                writeln!(dest)?;
+            }
+        }
+        writeln!(dest, "****")?;
+    }
+    let ecps: Vec<_> = basis
+        .elements
+        .iter()
+        .filter_map(|e| e.ecp.as_ref().map(|ecp| (&e.symbol, ecp)))
+        .collect();
+    if !ecps.is_empty() {
+        writeln!(dest)?;
+    }
+    for (symbol, ecp) in ecps {
+        let lmax = ecp.lmax();
+        writeln!(dest, "{:<5} 0", symbol)?;
+        writeln!(dest, "{}-ECP {:5} {:5}", symbol, lmax, ecp.core_electrons)?;
+        for l in std::iter::once(lmax).chain(0..lmax) {
+            if l == lmax {
+                writeln!(dest, "{} potential", shell_letter(l))?;
+            } else {
+                writeln!(dest, "{}-{} potential", shell_letter(l), shell_letter(lmax))?;
+            }
+            let potential = ecp.potentials.iter().find(|p| p.angular_momentum == l);
+            let nterms = potential.map_or(0, |p| p.coefficients.len());
+            writeln!(dest, "{:3}", nterms)?;
+            for p in potential.iter() {
+                for t in 0..nterms {
+                    writeln!(
+                        dest,
+                        "{} {} {}",
+                        p.r_exponents[t],
+                        format_fortran_real(p.gaussian_exponents[t], 22, 14, 'D'),
+                        format_fortran_real(p.coefficients[t], 22, 14, 'D')
+                    )?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Function to write a basis set as Psi4 `.gbs` file, i.e. in the Gaussian94 format preceded by
+/// the function type of the first shell.
+/// # Arguments
+///  * `dest` - Where to write to.
+///  * `basis` - The basis set to write.
+pub fn write_gbs<W: Write>(dest: &mut W, basis: &BasisSet) -> Result<(), ParseBasisError> {
+    let function_type = basis
+        .elements
+        .iter()
+        .flat_map(|e| e.shells.iter())
+        .map(|s| s.function_type)
+        .next()
+        .unwrap_or_default();
+    match function_type {
+        FunctionType::Cartesian => writeln!(dest, "cartesian")?,
+        _ => writeln!(dest, "spherical")?,
+    }
+    write_gaussian94(dest, basis)
+}
@@ -0,0 +1,248 @@
+//! This module provides a common representation of Gaussian basis sets and effective core
+//! potentials together with readers and writers for
+//! * the Gaussian94 format, which Psi4 also uses for its `.gbs` files,
+//! * NWChem `BASIS` and `ECP` blocks,
+//! * the JSON schema of the Basis Set Exchange, with the `bse` feature.
+//!
+//! Shells follow the conventions of the Basis Set Exchange: a shell with a single angular
+//! momentum may hold several general contractions, while a shell with several angular momenta,
+//! e.g. an SP shell, holds one contraction per angular momentum.
+use crate::format_string::parse_fortran_real;
+
+use self::basiserrors::ParseBasisError;
+pub mod basiserrors;
+#[cfg(feature = "bse")]
+pub mod bse;
+pub mod gaussian94;
+pub mod nwchem;
+
+/// Letters of the angular momenta, starting with s.
+const SHELL_LETTERS: [char; 8] = ['s', 'p', 'd', 'f', 'g', 'h', 'i', 'k'];
+
+/// Whether the functions of a shell are spherical or cartesian.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FunctionType {
+    /// Not specified by the file.
+    #[default]
+    Gto,
+    Spherical,
+    Cartesian,
+}
+
+/// A contracted shell of Gaussian functions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shell {
+    pub function_type: FunctionType,
+    /// A single angular momentum or one per contraction, e.g. `[0, 1]` for SP shells.
+    pub angular_momentum: Vec<usize>,
+    pub exponents: Vec<f64>,
+    /// The contraction coefficients, one vector over the primitives per contraction.
+    pub coefficients: Vec<Vec<f64>>,
+}
+
+impl Shell {
+    /// Number of primitives.
+    pub fn nprimitives(&self) -> usize {
+        self.exponents.len()
+    }
+
+    /// Whether all contractions share the exponents but differ in angular momentum.
+    pub fn is_fused(&self) -> bool {
+        self.angular_momentum.len() > 1
+    }
+
+    /// The label of the shell, e.g. `S` or `SP`.
+    pub fn label(&self) -> String {
+        self.angular_momentum
+            .iter()
+            .map(|&l| shell_letter(l))
+            .collect::<String>()
+            .to_uppercase()
+    }
+
+    /// Splits general contractions into shells with a single contraction each. Fused shells are
+    /// kept.
+    pub fn segmented(&self) -> Vec<Shell> {
+        if self.is_fused() || self.coefficients.len() < 2 {
+            return vec![self.clone()];
+        }
+        self.coefficients
+            .iter()
+            .map(|c| Shell {
+                coefficients: vec![c.clone()],
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+/// A term of an effective core potential, i.e. `coefficient * r^(r_exponent - 2) *
+/// exp(-gaussian_exponent * r^2)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EcpPotential {
+    /// The angular momentum of the projector. The local part has the largest one.
+    pub angular_momentum: usize,
+    pub r_exponents: Vec<i32>,
+    pub gaussian_exponents: Vec<f64>,
+    pub coefficients: Vec<f64>,
+}
+
+/// An effective core potential.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ecp {
+    /// Number of core electrons replaced by the potential.
+    pub core_electrons: usize,
+    pub potentials: Vec<EcpPotential>,
+}
+
+impl Ecp {
+    /// The angular momentum of the local part.
+    pub fn lmax(&self) -> usize {
+        self.potentials
+            .iter()
+            .map(|p| p.angular_momentum)
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// References of an element together with a description of what they cover.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElementReference {
+    pub description: String,
+    /// Keys into the references of the basis set.
+    pub keys: Vec<String>,
+}
+
+/// A literature reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub key: String,
+    /// The kind of the publication, e.g. `article`.
+    pub kind: String,
+    pub authors: Vec<String>,
+    /// Further fields like `title`, `journal`, `year` or `doi`.
+    pub fields: Vec<(String, String)>,
+}
+
+/// The basis set and effective core potential of an element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElementBasis {
+    /// The element symbol as given in the file.
+    pub symbol: String,
+    pub shells: Vec<Shell>,
+    pub ecp: Option<Ecp>,
+    pub references: Vec<ElementReference>,
+}
+
+/// A basis set for several elements.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BasisSet {
+    pub name: String,
+    pub description: String,
+    pub elements: Vec<ElementBasis>,
+    pub references: Vec<Reference>,
+}
+
+impl BasisSet {
+    /// Returns the basis of an element, ignoring case.
+    /// # Arguments
+    ///  * `symbol` - The element symbol.
+    pub fn element(&self, symbol: &str) -> Option<&ElementBasis> {
+        self.elements
+            .iter()
+            .find(|e| e.symbol.eq_ignore_ascii_case(symbol))
+    }
+
+    /// Returns the basis of an element, which is added if it is not present yet.
+    /// # Arguments
+    ///  * `symbol` - The element symbol.
+    pub fn element_mut(&mut self, symbol: &str) -> &mut ElementBasis {
+        match self
+            .elements
+            .iter()
+            .position(|e| e.symbol.eq_ignore_ascii_case(symbol))
+        {
+            Some(position) => &mut self.elements[position],
+            None => {
+                self.elements.push(ElementBasis {
+                    symbol: symbol.to_string(),
+                    shells: Vec::new(),
+                    ecp: None,
+                    references: Vec::new(),
+                });
+                self.elements
+                    .last_mut()
+                    .expect("An element was just added.")
+            }
+        }
+    }
+}
+
+/// Returns the letter of an angular momentum.
+fn shell_letter(l: usize) -> char {
+    SHELL_LETTERS.get(l).copied().unwrap_or('?')
+}
+
+/// Function to convert a shell label like `S`, `SP` or `L` into angular momenta.
+/// # Arguments
+///  * `label` - The shell label.
+fn angular_momenta(label: &str) -> Result<Vec<usize>, ParseBasisError> {
+    if label.eq_ignore_ascii_case("l") {
+        return Ok(vec![0, 1]);
+    }
+    label
+        .to_lowercase()
+        .chars()
+        .map(|c| SHELL_LETTERS.iter().position(|&s| s == c))
+        .collect::<Option<Vec<usize>>>()
+        .filter(|l| !l.is_empty())
+        .ok_or_else(|| ParseBasisError::UnknownShell(label.to_string()))
+}
+
+/// Function to parse all tokens of a line as reals.
+/// # Arguments
+///  * `line` - The line to parse.
+fn parse_reals(line: &str) -> Result<Vec<f64>, ParseBasisError> {
+    line.split_whitespace()
+        .map(parse_fortran_real::<f64>)
+        .collect::<Result<Vec<f64>, _>>()
+        .map_err(|_| ParseBasisError::InvalidLine(line.to_string()))
+}
+
+/// Function to parse an ECP term given as `r_exponent gaussian_exponent coefficient`.
+/// # Arguments
+///  * `line` - The line to parse.
+fn parse_ecp_term(line: &str) -> Result<(i32, f64, f64), ParseBasisError> {
+    let invalid = || ParseBasisError::InvalidLine(line.to_string());
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    if tokens.len() != 3 {
+        return Err(invalid());
+    }
+    Ok((
+        tokens[0].parse::<i32>().map_err(|_| invalid())?,
+        parse_fortran_real::<f64>(tokens[1]).map_err(|_| invalid())?,
+        parse_fortran_real::<f64>(tokens[2]).map_err(|_| invalid())?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_angular_momenta() {
+        assert_eq!(angular_momenta("SP").unwrap(), vec![0, 1]);
+        assert_eq!(angular_momenta("L").unwrap(), vec![0, 1]);
+        assert_eq!(angular_momenta("d").unwrap(), vec![2]);
+        assert!(angular_momenta("X").is_err());
+        let shell = Shell {
+            function_type: FunctionType::Gto,
+            angular_momentum: vec![0],
+            exponents: vec![1.0, 0.1],
+            coefficients: vec![vec![1.0, 0.0], vec![0.0, 1.0]],
+        };
+        assert_eq!(shell.label(), "S");
+        assert_eq!(shell.segmented().len(), 2);
+    }
+}
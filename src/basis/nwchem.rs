@@ -0,0 +1,253 @@
+//! Reading and writing of NWChem `BASIS` and `ECP` blocks. Each shell starts with the element
+//! symbol and the shell label followed by one line per primitive with the exponent and the
+//! coefficients of all contractions. Potentials list the power of r, the exponent and the
+//! coefficient of each term, `ul` denoting the local part.
+//! ```text
+//! BASIS "ao basis" SPHERICAL PRINT
+//! O    S
+//!       1.307093214E+02        1.543289673E-01
+//! ...
+//! END
+//! ECP
+//! O nelec 2
+//! O ul
+//! 2      1.0000000              0.0000000
+//! O S
+//! ...
+//! END
+//! ```
+use std::io::{BufRead, Write};
+
+use crate::format_string::{format_fortran_real, parse_fortran_real};
+
+use super::basiserrors::ParseBasisError;
+use super::{
+    angular_momenta, parse_ecp_term, parse_reals, shell_letter, BasisSet, Ecp, EcpPotential,
+    FunctionType, Shell,
+};
+
+/// The block of the file being read.
+enum Block {
+    None,
+    Basis(FunctionType),
+    Ecp,
+}
+
+/// Function to parse the `BASIS` and `ECP` blocks of a NWChem input or library file. Comments
+/// start with `#`.
+/// # Arguments
+///  * `source` - mutable reference of the file buffer.
+pub fn parse_nwchem<I: BufRead>(source: &mut I) -> Result<BasisSet, ParseBasisError> {
+    let mut basis = BasisSet::default();
+    let mut block = Block::None;
+    // Symbol of the element the following numeric lines belong to.
+    let mut current: Option<String> = None;
+    for line in source.lines() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let keyword = tokens[0].to_uppercase();
+        match block {
+            Block::None => {
+                if keyword == "BASIS" {
+                    let function_type = tokens
+                        .iter()
+                        .find_map(|t| match t.to_uppercase().as_str() {
+                            "SPHERICAL" => Some(FunctionType::Spherical),
+                            "CARTESIAN" => Some(FunctionType::Cartesian),
+                            _ => None,
+                        })
+                        .unwrap_or_default();
+                    block = Block::Basis(function_type);
+                } else if keyword == "ECP" {
+                    block = Block::Ecp;
+                }
+                continue;
+            }
+            _ if keyword == "END" => {
+                if matches!(block, Block::Ecp) {
+                    assign_local_potentials(&mut basis);
+                }
+                block = Block::None;
+                current = None;
+                continue;
+            }
+            _ => (),
+        }
+        let numeric = parse_fortran_real::<f64>(tokens[0]).is_ok();
+        match (&block, numeric) {
+            (Block::Basis(function_type), false) => {
+                if tokens.len() != 2 {
+                    return Err(ParseBasisError::InvalidLine(line.to_string()));
+                }
+                basis.element_mut(tokens[0]).shells.push(Shell {
+                    function_type: *function_type,
+                    angular_momentum: angular_momenta(tokens[1])?,
+                    exponents: Vec::new(),
+                    coefficients: Vec::new(),
+                });
+                current = Some(tokens[0].to_string());
+            }
+            (Block::Basis(_), true) => {
+                let shell = current
+                    .as_ref()
+                    .and_then(|s| basis.element_mut(s).shells.last_mut())
+                    .ok_or_else(|| ParseBasisError::InvalidLine(line.to_string()))?;
+                let values = parse_reals(line)?;
+                if shell.coefficients.is_empty() {
+                    shell.coefficients = vec![Vec::new(); values.len().saturating_sub(1)];
+                }
+                if values.len() < 2 || values.len() != shell.coefficients.len() + 1 {
+                    return Err(ParseBasisError::InvalidLine(line.to_string()));
+                }
+                shell.exponents.push(values[0]);
+                for (c, v) in shell.coefficients.iter_mut().zip(&values[1..]) {
+                    c.push(*v);
+                }
+            }
+            (Block::Ecp, false) => {
+                let invalid = || ParseBasisError::InvalidLine(line.to_string());
+                let element = basis.element_mut(tokens[0]);
+                let ecp = element.ecp.get_or_insert_with(|| Ecp {
+                    core_electrons: 0,
+                    potentials: Vec::new(),
+                });
+                match tokens.get(1).map(|t| t.to_lowercase()).as_deref() {
+                    Some("nelec") => {
+                        ecp.core_electrons = tokens
+                            .get(2)
+                            .and_then(|t| t.parse::<usize>().ok())
+                            .ok_or_else(invalid)?;
+                    }
+                    Some("ul") => ecp.potentials.push(EcpPotential {
+                        angular_momentum: usize::MAX,
+                        r_exponents: Vec::new(),
+                        gaussian_exponents: Vec::new(),
+                        coefficients: Vec::new(),
+                    }),
+                    Some(label) => {
+                        let l = angular_momenta(label)?;
+                        if l.len() != 1 {
+                            return Err(invalid());
+                        }
+                        ecp.potentials.push(EcpPotential {
+                            angular_momentum: l[0],
+                            r_exponents: Vec::new(),
+                            gaussian_exponents: Vec::new(),
+                            coefficients: Vec::new(),
+                        });
+                    }
+                    None => return Err(invalid()),
+                }
+                current = Some(tokens[0].to_string());
+            }
+            (Block::Ecp, true) => {
+                let potential = current
+                    .as_ref()
+                    .and_then(|s| basis.element_mut(s).ecp.as_mut())
+                    .and_then(|ecp| ecp.potentials.last_mut())
+                    .ok_or_else(|| ParseBasisError::InvalidLine(line.to_string()))?;
+                let (r, g, c) = parse_ecp_term(line)?;
+                potential.r_exponents.push(r);
+                potential.gaussian_exponents.push(g);
+                potential.coefficients.push(c);
+            }
+            (Block::None, _) => unreachable!("Lines outside of blocks are skipped above."),
+        }
+    }
+    assign_local_potentials(&mut basis);
+    Ok(basis)
+}
+
+/// Gives the local `ul` potentials the angular momentum above the largest semi-local one.
+fn assign_local_potentials(basis: &mut BasisSet) {
+    for ecp in basis.elements.iter_mut().filter_map(|e| e.ecp.as_mut()) {
+        let lmax = ecp
+            .potentials
+            .iter()
+            .filter(|p| p.angular_momentum != usize::MAX)
+            .map(|p| p.angular_momentum + 1)
+            .max()
+            .unwrap_or_default();
+        for p in ecp
+            .potentials
+            .iter_mut()
+            .filter(|p| p.angular_momentum == usize::MAX)
+        {
+            p.angular_momentum = lmax;
+        }
+    }
+}
+
+/// Function to write a basis set as NWChem `BASIS` block, followed by an `ECP` block if any
+/// element has a potential.
+/// # Arguments
+///  * `dest` - Where to write to.
+///  * `basis` - The basis set to write.
+pub fn write_nwchem<W: Write>(dest: &mut W, basis: &BasisSet) -> Result<(), ParseBasisError> {
+    let function_type = basis
+        .elements
+        .iter()
+        .flat_map(|e| e.shells.iter())
+        .map(|s| s.function_type)
+        .next()
+        .unwrap_or_default();
+    match function_type {
+        FunctionType::Spherical => writeln!(dest, "BASIS \"ao basis\" SPHERICAL PRINT")?,
+        FunctionType::Cartesian => writeln!(dest, "BASIS \"ao basis\" CARTESIAN PRINT")?,
+        FunctionType::Gto => writeln!(dest, "BASIS \"ao basis\" PRINT")?,
+    }
+    for element in basis.elements.iter() {
+        for shell in element.shells.iter() {
+            writeln!(dest, "{:<4} {}", element.symbol, shell.label())?;
+            for (p, exponent) in shell.exponents.iter().enumerate() {
+                write!(dest, "{}", format_fortran_real(*exponent, 22, 14, 'E'))?;
+                for c in shell.coefficients.iter() {
+                    write!(dest, "{}", format_fortran_real(c[p], 22, 14, 'E'))?;
+                }
+                writeln!(dest)?;
+            }
+        }
+    }
+    writeln!(dest, "END")?;
+    if basis.elements.iter().all(|e| e.ecp.is_none()) {
+        return Ok(());
+    }
+    writeln!(dest, "ECP")?;
+    for element in basis.elements.iter() {
+        let Some(ecp) = element.ecp.as_ref() else {
+            continue;
+        };
+        let lmax = ecp.lmax();
+        writeln!(dest, "{:<4} nelec {}", element.symbol, ecp.core_electrons)?;
+        for l in std::iter::once(lmax).chain(0..lmax) {
+            let Some(potential) = ecp.potentials.iter().find(|p| p.angular_momentum == l) else {
+                continue;
+            };
+            if l == lmax {
+                writeln!(dest, "{:<4} ul", element.symbol)?;
+            } else {
+                writeln!(
+                    dest,
+                    "{:<4} {}",
+                    element.symbol,
+                    shell_letter(l).to_ascii_uppercase()
+                )?;
+            }
+            for t in 0..potential.coefficients.len() {
+                writeln!(
+                    dest,
+                    "{} {} {}",
+                    potential.r_exponents[t],
+                    format_fortran_real(potential.gaussian_exponents[t], 22, 14, 'E'),
+                    format_fortran_real(potential.coefficients[t], 22, 14, 'E')
+                )?;
+            }
+        }
+    }
+    writeln!(dest, "END")?;
+    Ok(())
+}
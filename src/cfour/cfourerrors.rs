@@ -0,0 +1,54 @@
+use std::error;
+use std::fmt::Display;
+
+use crate::array_text::arraytexterrors::ParseArrayTextError;
+
+#[derive(Debug)]
+pub enum ParseCfourError {
+    /// Raised when the underlying reader or writer fails.
+    Io(std::io::Error),
+    /// Raised when the file ends before all announced data is read.
+    UnexpectedEof,
+    /// Raised when a line cannot be parsed.
+    InvalidLine(String),
+    /// Raised when the numeric data of a file is malformed.
+    ArrayError(ParseArrayTextError),
+    /// Raised when a matrix to write is not a 3N x 3N Hessian.
+    InvalidHessian { nrows: usize, ncols: usize },
+}
+
+impl Display for ParseCfourError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Could not read the CFOUR file: {}", e),
+            Self::UnexpectedEof => write!(f, "The CFOUR file ended unexpectedly."),
+            Self::InvalidLine(line) => write!(f, "Could not parse the line '{}'.", line),
+            Self::ArrayError(e) => write!(f, "Could not read the numeric data: {}", e),
+            Self::InvalidHessian { nrows, ncols } => {
+                write!(f, "A {}x{} matrix is not a 3N x 3N Hessian.", nrows, ncols)
+            }
+        }
+    }
+}
+
+impl error::Error for ParseCfourError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::ArrayError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseCfourError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<ParseArrayTextError> for ParseCfourError {
+    fn from(value: ParseArrayTextError) -> Self {
+        Self::ArrayError(value)
+    }
+}
@@ -0,0 +1,74 @@
+//! Reading and writing of the cartesian force constants in `FCMFINAL` and `FCM`. A line with the
+//! number of atoms is followed by the 3N x 3N Hessian in hartree/bohr^2, three values per line.
+//! ```text
+//!     3    9
+//!         0.6103524650       -0.0000000000        0.0000000000
+//! ```
+use nalgebra::DMatrix;
+use std::io::{BufRead, Write};
+
+use crate::array_text::writer::{write_matrix, TextFormat};
+use crate::array_text::{parse_text_into_matrix, MatrixLayout};
+
+use super::cfourerrors::ParseCfourError;
+
+/// Function to parse the force constants of an `FCMFINAL` or `FCM` file.
+/// # Arguments
+///  * `source` - mutable reference of the file buffer.
+pub fn parse_fcm<I: BufRead + std::fmt::Debug>(
+    source: &mut I,
+) -> Result<DMatrix<f64>, ParseCfourError> {
+    let mut header = String::new();
+    if source.read_line(&mut header)? == 0 {
+        return Err(ParseCfourError::UnexpectedEof);
+    }
+    let natoms = header
+        .split_whitespace()
+        .next()
+        .and_then(|t| t.parse::<usize>().ok())
+        .ok_or_else(|| ParseCfourError::InvalidLine(header.trim_end().to_string()))?;
+    Ok(parse_text_into_matrix(
+        source,
+        " ",
+        3 * natoms,
+        3 * natoms,
+        MatrixLayout::RowMajor,
+    )?)
+}
+
+/// Function to write force constants in the format of `FCMFINAL`.
+/// # Arguments
+///  * `dest` - Where to write to.
+///  * `hessian` - The 3N x 3N cartesian force constants.
+pub fn write_fcm<W: Write>(dest: &mut W, hessian: &DMatrix<f64>) -> Result<(), ParseCfourError> {
+    let (nrows, ncols) = hessian.shape();
+    if nrows != ncols || !nrows.is_multiple_of(3) {
+        return Err(ParseCfourError::InvalidHessian { nrows, ncols });
+    }
+    let natoms = nrows / 3;
+    writeln!(dest, "{:5}{:5}", natoms, 3 * natoms)?;
+    let format = TextFormat {
+        separator: String::new(),
+        values_per_line: Some(3),
+        ..Default::default()
+    };
+    write_matrix(dest, hessian, &format)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_invalid_hessian() {
+        let mut written: Vec<u8> = Vec::new();
+        for (nrows, ncols) in [(6, 3), (4, 4)] {
+            assert!(matches!(
+                write_fcm(&mut written, &DMatrix::zeros(nrows, ncols)),
+                Err(ParseCfourError::InvalidHessian { .. })
+            ));
+        }
+        assert!(written.is_empty());
+    }
+}
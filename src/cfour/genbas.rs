@@ -0,0 +1,145 @@
+//! Reading and writing of the `GENBAS` basis set library. Each entry starts with its name and a
+//! comment, followed by the number of shells, their angular momenta, numbers of contracted and
+//! primitive functions and for each shell the exponents and the contraction coefficients with one
+//! row per primitive.
+//! ```text
+//! H:STO-3G
+//! STO-3G basis for hydrogen
+//!
+//!   1
+//!     0
+//!     1
+//!     3
+//!
+//!     3.4252509100    0.6239137300    0.1688554000
+//!
+//!     0.1543289700
+//!     0.5353281400
+//!     0.4446345400
+//! ```
+use nalgebra::DMatrix;
+use std::io::{BufRead, Write};
+
+use super::cfourerrors::ParseCfourError;
+use super::read_reals;
+
+/// Number of exponents per line.
+const EXPONENTS_PER_LINE: usize = 5;
+
+/// A shell of a `GENBAS` entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenbasShell {
+    pub angular_momentum: usize,
+    /// Exponents of the primitives.
+    pub exponents: Vec<f64>,
+    /// Contraction coefficients with one row per primitive and one column per contraction.
+    pub coefficients: DMatrix<f64>,
+}
+
+/// An entry of a `GENBAS` file, i.e. the basis set of one element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenbasEntry {
+    /// The name, element and basis set separated by a colon, e.g. `H:PVDZ`.
+    pub name: String,
+    /// The comment line.
+    pub comment: String,
+    pub shells: Vec<GenbasShell>,
+}
+
+impl GenbasEntry {
+    /// The element symbol of the entry name.
+    pub fn element(&self) -> &str {
+        self.name.split(':').next().unwrap_or_default()
+    }
+
+    /// The basis set of the entry name.
+    pub fn basis(&self) -> &str {
+        self.name.split_once(':').map_or("", |(_, b)| b)
+    }
+}
+
+/// Converts reals read from the file into counts.
+fn as_counts(values: Vec<f64>) -> Vec<usize> {
+    values.into_iter().map(|v| v.round() as usize).collect()
+}
+
+/// Function to parse all entries of a `GENBAS` file.
+/// # Arguments
+///  * `source` - mutable reference of the file buffer.
+pub fn parse_genbas<I: BufRead>(source: &mut I) -> Result<Vec<GenbasEntry>, ParseCfourError> {
+    let mut lines = source.lines();
+    let mut entries = Vec::new();
+    while let Some(name) = lines.next() {
+        let name = name?;
+        if name.trim().is_empty() {
+            continue;
+        }
+        let comment = lines.next().ok_or(ParseCfourError::UnexpectedEof)??;
+        let nshells = as_counts(read_reals(&mut lines, 1)?)[0];
+        let angular_momenta = as_counts(read_reals(&mut lines, nshells)?);
+        let ncontracted = as_counts(read_reals(&mut lines, nshells)?);
+        let nprimitives = as_counts(read_reals(&mut lines, nshells)?);
+        let mut shells = Vec::with_capacity(nshells);
+        for s in 0..nshells {
+            let exponents = read_reals(&mut lines, nprimitives[s])?;
+            let coefficients = read_reals(&mut lines, nprimitives[s] * ncontracted[s])?;
+            shells.push(GenbasShell {
+                angular_momentum: angular_momenta[s],
+                exponents,
+                coefficients: DMatrix::from_row_slice(
+                    nprimitives[s],
+                    ncontracted[s],
+                    &coefficients,
+                ),
+            });
+        }
+        entries.push(GenbasEntry {
+            name: name.trim().to_string(),
+            comment: comment.trim().to_string(),
+            shells,
+        });
+    }
+    Ok(entries)
+}
+
+/// Function to write entries in the format of `GENBAS`.
+/// # Arguments
+///  * `dest` - Where to write to.
+///  * `entries` - The basis sets to write.
+pub fn write_genbas<W: Write>(
+    dest: &mut W,
+    entries: &[GenbasEntry],
+) -> Result<(), ParseCfourError> {
+    for entry in entries {
+        writeln!(dest, "{}\n{}\n", entry.name, entry.comment)?;
+        writeln!(dest, "{:3}", entry.shells.len())?;
+        for count in [
+            |s: &GenbasShell| s.angular_momentum,
+            |s: &GenbasShell| s.coefficients.ncols(),
+            |s: &GenbasShell| s.exponents.len(),
+        ] {
+            for shell in entry.shells.iter() {
+                write!(dest, "{:5}", count(shell))?;
+            }
+            writeln!(dest)?;
+        }
+        writeln!(dest)?;
+        for shell in entry.shells.iter() {
+            for line in shell.exponents.chunks(EXPONENTS_PER_LINE) {
+                for e in line {
+                    write!(dest, "{:18.10}", e)?;
+                }
+                writeln!(dest)?;
+            }
+            writeln!(dest)?;
+            for row in shell.coefficients.row_iter() {
+                for c in row.iter() {
+                    write!(dest, "{:16.10}", c)?;
+                }
+                writeln!(dest)?;
+            }
+            writeln!(dest)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,100 @@
+//! Reading and writing of `GRD` files. A line with the number of atoms and the energy is followed
+//! by a line per atom with its charge and position and a line per atom with its charge and the
+//! gradient, all in atomic units.
+//! ```text
+//!     3      -76.3339402591
+//!         8.0000000000        0.0000000000        0.0000000000       -0.1243020000
+//!         ...
+//!         8.0000000000        0.0000000000        0.0000000000        0.0115238304
+//! ```
+use nalgebra::{DMatrix, Point3};
+use std::io::{BufRead, Write};
+
+use crate::format_string::parse_fortran_real;
+use crate::xyz::xyzline::numeric::XYZLineNumeric;
+use crate::xyz::{Xyz, XyzLine};
+
+use super::cfourerrors::ParseCfourError;
+use super::read_reals;
+
+/// Represents a `GRD` file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CfourGradient {
+    /// The total energy in hartree.
+    pub energy: f64,
+    /// Nuclear charges and positions in bohr.
+    pub atoms: Vec<XYZLineNumeric<f64>>,
+    /// The gradient in hartree/bohr with one row per atom.
+    pub gradient: DMatrix<f64>,
+}
+
+impl CfourGradient {
+    /// Constructor for a CfourGradient struct.
+    /// # Arguments:
+    ///  * `file_handle` - A handle to a `BufReader`, i.e. the `GRD` file.
+    pub fn new<I: BufRead>(file_handle: &mut I) -> Result<Self, ParseCfourError> {
+        let mut lines = file_handle.lines();
+        let header = lines.next().ok_or(ParseCfourError::UnexpectedEof)??;
+        let invalid = || ParseCfourError::InvalidLine(header.clone());
+        let mut tokens = header.split_whitespace();
+        let natoms = tokens
+            .next()
+            .and_then(|t| t.parse::<usize>().ok())
+            .ok_or_else(invalid)?;
+        let energy = tokens
+            .next()
+            .and_then(|t| parse_fortran_real::<f64>(t).ok())
+            .ok_or_else(invalid)?;
+        let positions = read_reals(&mut lines, 4 * natoms)?;
+        let gradients = read_reals(&mut lines, 4 * natoms)?;
+        let atoms = positions
+            .chunks(4)
+            .map(|p| XYZLineNumeric {
+                z_value: p[0].round() as usize,
+                xyz: Point3::new(p[1], p[2], p[3]),
+            })
+            .collect();
+        let gradient = DMatrix::from_fn(natoms, 3, |i, k| gradients[4 * i + k + 1]);
+        Ok(Self {
+            energy,
+            atoms,
+            gradient,
+        })
+    }
+
+    /// Returns the geometry in bohr.
+    pub fn geometry(&self) -> Xyz<f64> {
+        Xyz {
+            number_of_atoms: self.atoms.len(),
+            distances_in: "bohr".to_string(),
+            info_line: String::new(),
+            lines: self
+                .atoms
+                .iter()
+                .map(|a| XyzLine::Numeric(a.clone()))
+                .collect(),
+        }
+    }
+
+    /// Writes the `GRD` file.
+    /// # Arguments
+    ///  * `dest` - Where to write to.
+    pub fn write<W: Write>(&self, dest: &mut W) -> Result<(), ParseCfourError> {
+        writeln!(dest, "{:5}{:20.10}", self.atoms.len(), self.energy)?;
+        for a in self.atoms.iter() {
+            writeln!(
+                dest,
+                "{:20.10}{:20.10}{:20.10}{:20.10}",
+                a.z_value as f64, a.xyz.x, a.xyz.y, a.xyz.z
+            )?;
+        }
+        for (a, g) in self.atoms.iter().zip(self.gradient.row_iter()) {
+            writeln!(
+                dest,
+                "{:20.10}{:20.10}{:20.10}{:20.10}",
+                a.z_value as f64, g[0], g[1], g[2]
+            )?;
+        }
+        Ok(())
+    }
+}
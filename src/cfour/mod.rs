@@ -0,0 +1,59 @@
+//! This module provides readers and writers for the input and auxiliary files of CFOUR:
+//! * `ZMAT`, the input file with title, geometry and `*CFOUR(...)` keywords,
+//! * `GENBAS`, the basis set library,
+//! * `FCMFINAL` and `FCM`, the cartesian force constants,
+//! * `GRD`, energy and cartesian gradient,
+//! * `OLDMOS`, the MO coefficients used as restart guess.
+use std::io::BufRead;
+
+use crate::format_string::parse_fortran_real;
+
+use self::cfourerrors::ParseCfourError;
+pub mod cfourerrors;
+pub mod fcm;
+pub mod genbas;
+pub mod grd;
+pub mod oldmos;
+pub mod zmat;
+
+/// Reads lines until `count` reals are found. Blank lines are skipped, but a line must not hold
+/// more values than needed.
+/// # Arguments
+///  * `lines` - Iterator over the lines of the file.
+///  * `count` - Number of values to read.
+fn read_reals<L>(lines: &mut L, count: usize) -> Result<Vec<f64>, ParseCfourError>
+where
+    L: Iterator<Item = std::io::Result<String>>,
+{
+    let mut values = Vec::with_capacity(count);
+    while values.len() < count {
+        let line = lines.next().ok_or(ParseCfourError::UnexpectedEof)??;
+        for token in line.split_whitespace() {
+            values.push(
+                parse_fortran_real::<f64>(token)
+                    .map_err(|_| ParseCfourError::InvalidLine(line.clone()))?,
+            );
+        }
+        if values.len() > count {
+            return Err(ParseCfourError::InvalidLine(line));
+        }
+    }
+    Ok(values)
+}
+
+/// Reads all reals of the remaining lines.
+/// # Arguments
+///  * `source` - mutable reference of the file buffer.
+fn read_all_reals<I: BufRead>(source: &mut I) -> Result<Vec<f64>, ParseCfourError> {
+    let mut values = Vec::new();
+    for line in source.lines() {
+        let line = line?;
+        for token in line.split_whitespace() {
+            values.push(
+                parse_fortran_real::<f64>(token)
+                    .map_err(|_| ParseCfourError::InvalidLine(line.clone()))?,
+            );
+        }
+    }
+    Ok(values)
+}
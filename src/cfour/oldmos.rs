@@ -0,0 +1,92 @@
+//! Reading and writing of `OLDMOS` files. The square MO coefficient matrix is written in blocks of
+//! four orbitals, each block holding one line per basis function. Open shell files hold the beta
+//! orbitals after the alpha ones.
+//! ```text
+//!   0.99421444012345678901  -0.23376005012345678901   0.00000000000000000000 ...
+//! ```
+use nalgebra::DMatrix;
+use std::io::{BufRead, Write};
+
+use crate::array_text::arraytexterrors::ParseArrayTextError;
+
+use super::cfourerrors::ParseCfourError;
+use super::read_all_reals;
+
+/// Number of orbitals per block.
+const BLOCK_WIDTH: usize = 4;
+
+/// Function to parse the MO coefficients of an `OLDMOS` file into one matrix per spin with one
+/// column per orbital.
+/// # Arguments
+///  * `source` - mutable reference of the file buffer.
+///  * `nbasis` - Number of basis functions.
+pub fn parse_oldmos<I: BufRead>(
+    source: &mut I,
+    nbasis: usize,
+) -> Result<Vec<DMatrix<f64>>, ParseCfourError> {
+    let values = read_all_reals(source)?;
+    let per_spin = nbasis * nbasis;
+    if per_spin == 0 || values.is_empty() || !values.len().is_multiple_of(per_spin) {
+        return Err(ParseArrayTextError::ShapeMismatch {
+            expected: per_spin,
+            found: values.len(),
+        }
+        .into());
+    }
+    let mut matrices = Vec::with_capacity(values.len() / per_spin);
+    for spin in values.chunks(per_spin) {
+        let mut matrix = DMatrix::zeros(nbasis, nbasis);
+        let mut spin = spin.iter();
+        for first in (0..nbasis).step_by(BLOCK_WIDTH) {
+            let last = (first + BLOCK_WIDTH).min(nbasis);
+            for i in 0..nbasis {
+                for j in first..last {
+                    matrix[(i, j)] = *spin.next().expect("The length is checked above.");
+                }
+            }
+        }
+        matrices.push(matrix);
+    }
+    Ok(matrices)
+}
+
+/// Function to write MO coefficients in the format of `OLDMOS`.
+/// # Arguments
+///  * `dest` - Where to write to.
+///  * `coefficients` - One square matrix per spin with one column per orbital.
+pub fn write_oldmos<W: Write>(
+    dest: &mut W,
+    coefficients: &[DMatrix<f64>],
+) -> Result<(), ParseCfourError> {
+    for matrix in coefficients {
+        for first in (0..matrix.ncols()).step_by(BLOCK_WIDTH) {
+            let last = (first + BLOCK_WIDTH).min(matrix.ncols());
+            for i in 0..matrix.nrows() {
+                for j in first..last {
+                    write!(dest, "{:30.20}", matrix[(i, j)])?;
+                }
+                writeln!(dest)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_oldmos_round_trip() {
+        let alpha = DMatrix::from_fn(6, 6, |i, j| (i * 6 + j) as f64 / 8.0);
+        let beta = -alpha.clone();
+        let mut written: Vec<u8> = Vec::new();
+        write_oldmos(&mut written, &[alpha.clone(), beta.clone()]).unwrap();
+        let text = String::from_utf8(written.clone()).unwrap();
+        assert_eq!(text.lines().count(), 24);
+        assert_eq!(text.lines().nth(6).unwrap().split_whitespace().count(), 2);
+        let parsed = parse_oldmos(&mut Cursor::new(written), 6).unwrap();
+        assert_eq!(parsed, vec![alpha, beta]);
+    }
+}
@@ -0,0 +1,315 @@
+//! Reading and writing of `ZMAT` input files. The title line is followed by the geometry, either
+//! as Z-matrix or in cartesian coordinates, an optional block of variable definitions and the
+//! keyword namelist.
+//! ```text
+//! water
+//! O
+//! H 1 R
+//! H 1 R 2 A
+//!
+//! R=0.958
+//! A=104.5
+//!
+//! *CFOUR(CALC=CCSD(T),BASIS=PVDZ
+//! COORD=INTERNAL)
+//!
+//! ```
+use nalgebra::Point3;
+use std::io::{BufRead, Write};
+
+use crate::format_string::parse_fortran_real;
+use crate::xyz::xyzline::symbol::XYZLineSymbol;
+
+use super::cfourerrors::ParseCfourError;
+
+/// A line of a Z-matrix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZMatrixLine {
+    pub symbol: String,
+    /// Pairs of the referenced atom (1-based) and the distance, angle or dihedral, which is
+    /// either a number or the name of a variable.
+    pub references: Vec<(usize, String)>,
+}
+
+/// The geometry of a `ZMAT` file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ZmatGeometry {
+    ZMatrix(Vec<ZMatrixLine>),
+    Cartesian(Vec<XYZLineSymbol<f64>>),
+}
+
+/// Represents a `ZMAT` file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Zmat {
+    pub title: String,
+    pub geometry: ZmatGeometry,
+    /// Variable definitions of the Z-matrix, e.g. `("R", "0.958")`.
+    pub variables: Vec<(String, String)>,
+    /// The name of the namelist, usually `CFOUR`.
+    pub namelist: String,
+    /// Keywords and values in input order.
+    pub keywords: Vec<(String, String)>,
+    /// Lines after the namelist, e.g. basis set or isotope definitions.
+    pub extra: Vec<String>,
+}
+
+/// Tries to read a cartesian geometry line.
+fn cartesian_line(line: &str) -> Option<XYZLineSymbol<f64>> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    if tokens.len() != 4 {
+        return None;
+    }
+    let mut xyz = [0.0; 3];
+    for (value, token) in xyz.iter_mut().zip(&tokens[1..]) {
+        *value = parse_fortran_real::<f64>(token).ok()?;
+    }
+    Some(XYZLineSymbol {
+        symbol: tokens[0].to_lowercase(),
+        xyz: Point3::from(xyz),
+    })
+}
+
+/// Reads a Z-matrix line.
+fn zmatrix_line(line: &str) -> Result<ZMatrixLine, ParseCfourError> {
+    let mut tokens = line.split_whitespace();
+    let symbol = tokens
+        .next()
+        .ok_or_else(|| ParseCfourError::InvalidLine(line.to_string()))?
+        .to_string();
+    let rest: Vec<&str> = tokens.collect();
+    if !rest.len().is_multiple_of(2) || rest.len() > 6 {
+        return Err(ParseCfourError::InvalidLine(line.to_string()));
+    }
+    let references = rest
+        .chunks(2)
+        .map(|pair| {
+            pair[0]
+                .parse::<usize>()
+                .map(|atom| (atom, pair[1].to_string()))
+                .map_err(|_| ParseCfourError::InvalidLine(line.to_string()))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(ZMatrixLine { symbol, references })
+}
+
+/// Splits the content of a namelist at commas outside of parentheses.
+fn split_keywords(content: &str) -> Result<Vec<(String, String)>, ParseCfourError> {
+    let mut entries = Vec::new();
+    let mut depth = 0usize;
+    let mut current = String::new();
+    for c in content.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ => (),
+        }
+        if c == ',' && depth == 0 {
+            entries.push(std::mem::take(&mut current));
+        } else {
+            current.push(c);
+        }
+    }
+    entries.push(current);
+    entries
+        .into_iter()
+        .map(|e| e.trim().to_string())
+        .filter(|e| !e.is_empty())
+        .map(|e| match e.split_once('=') {
+            Some((k, v)) => Ok((k.trim().to_string(), v.trim().to_string())),
+            None => Err(ParseCfourError::InvalidLine(e)),
+        })
+        .collect()
+}
+
+impl Zmat {
+    /// Constructor for a Zmat struct.
+    /// # Arguments:
+    ///  * `file_handle` - A handle to a `BufReader`, i.e. the `ZMAT` file.
+    ///
+    /// # Examples
+    /// ```no_run
+    ///  use std::fs::File;
+    ///  use std::io::BufReader;
+    ///  use qc_file_parsers::cfour::zmat::Zmat;
+    ///
+    ///  let mut reader = BufReader::new(File::open("ZMAT").unwrap());
+    ///  let zmat = Zmat::new(&mut reader).unwrap();
+    ///  println!("{:?}", zmat.keyword("calc"));
+    /// ```
+    pub fn new<I: BufRead>(file_handle: &mut I) -> Result<Self, ParseCfourError> {
+        let lines = file_handle.lines().collect::<Result<Vec<String>, _>>()?;
+        let mut lines = lines.into_iter().peekable();
+        let title = lines.next().ok_or(ParseCfourError::UnexpectedEof)?;
+        let mut geometry_lines = Vec::new();
+        for line in lines.by_ref() {
+            if line.trim().is_empty() {
+                break;
+            }
+            geometry_lines.push(line);
+        }
+        if geometry_lines.is_empty() {
+            return Err(ParseCfourError::UnexpectedEof);
+        }
+        let cartesian: Option<Vec<_>> = geometry_lines.iter().map(|l| cartesian_line(l)).collect();
+        let geometry = match cartesian {
+            Some(atoms) => ZmatGeometry::Cartesian(atoms),
+            None => ZmatGeometry::ZMatrix(
+                geometry_lines
+                    .iter()
+                    .map(|l| zmatrix_line(l))
+                    .collect::<Result<_, _>>()?,
+            ),
+        };
+
+        let mut variables = Vec::new();
+        while let Some(line) = lines.next_if(|l| !l.trim_start().starts_with('*')) {
+            if line.trim().is_empty() {
+                if !variables.is_empty() {
+                    break;
+                }
+                continue;
+            }
+            let (name, value) = line
+                .split_once('=')
+                .ok_or_else(|| ParseCfourError::InvalidLine(line.clone()))?;
+            variables.push((name.trim().to_string(), value.trim().to_string()));
+        }
+        while lines.next_if(|l| l.trim().is_empty()).is_some() {}
+
+        let first = lines.next().ok_or(ParseCfourError::UnexpectedEof)?;
+        let (namelist, rest) = first
+            .trim()
+            .strip_prefix('*')
+            .and_then(|l| l.split_once('('))
+            .ok_or_else(|| ParseCfourError::InvalidLine(first.clone()))?;
+        let namelist = namelist.trim().to_string();
+        let mut content = String::new();
+        let mut depth = 1usize;
+        let mut pending = Some(rest.to_string());
+        'namelist: loop {
+            let line = match pending.take() {
+                Some(line) => line,
+                None => lines.next().ok_or(ParseCfourError::UnexpectedEof)?,
+            };
+            if !content.is_empty() {
+                content.push(',');
+            }
+            for c in line.trim().chars() {
+                match c {
+                    '(' => depth += 1,
+                    ')' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            break 'namelist;
+                        }
+                    }
+                    _ => (),
+                }
+                content.push(c);
+            }
+        }
+        let keywords = split_keywords(&content)?;
+        let mut extra: Vec<String> = lines.collect();
+        while extra.last().is_some_and(|l| l.trim().is_empty()) {
+            extra.pop();
+        }
+        Ok(Self {
+            title,
+            geometry,
+            variables,
+            namelist,
+            keywords,
+            extra,
+        })
+    }
+
+    /// Returns the value of a keyword, ignoring case.
+    /// # Arguments
+    ///  * `name` - Name of the keyword.
+    pub fn keyword(&self, name: &str) -> Option<&str> {
+        self.keywords
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Writes the `ZMAT` file with one keyword per line.
+    /// # Arguments
+    ///  * `dest` - Where to write to.
+    pub fn write<W: Write>(&self, dest: &mut W) -> Result<(), ParseCfourError> {
+        writeln!(dest, "{}", self.title)?;
+        match &self.geometry {
+            ZmatGeometry::ZMatrix(lines) => {
+                for line in lines {
+                    write!(dest, "{}", line.symbol)?;
+                    for (atom, value) in line.references.iter() {
+                        write!(dest, " {} {}", atom, value)?;
+                    }
+                    writeln!(dest)?;
+                }
+            }
+            ZmatGeometry::Cartesian(atoms) => {
+                for a in atoms {
+                    writeln!(
+                        dest,
+                        "{:<3}{:16.10}{:16.10}{:16.10}",
+                        a.symbol.to_uppercase(),
+                        a.xyz.x,
+                        a.xyz.y,
+                        a.xyz.z
+                    )?;
+                }
+            }
+        }
+        writeln!(dest)?;
+        if !self.variables.is_empty() {
+            for (name, value) in self.variables.iter() {
+                writeln!(dest, "{}={}", name, value)?;
+            }
+            writeln!(dest)?;
+        }
+        let keywords: Vec<String> = self
+            .keywords
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect();
+        writeln!(dest, "*{}({})", self.namelist, keywords.join("\n"))?;
+        for line in self.extra.iter() {
+            writeln!(dest, "{}", line)?;
+        }
+        writeln!(dest)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_split_keywords() {
+        let keywords = split_keywords("CALC=CCSD(T),BASIS=PVDZ,,MEMORY=2,MEM_UNIT=GB").unwrap();
+        assert_eq!(keywords.len(), 4);
+        assert_eq!(keywords[0], ("CALC".to_string(), "CCSD(T)".to_string()));
+        assert!(split_keywords("CALC").is_err());
+    }
+
+    #[test]
+    fn test_cartesian_zmat() {
+        let text = "h2\nH 0.0 0.0 0.0\nH 0.0 0.0 0.74\n\n*CFOUR(CALC=SCF\nCOORD=CARTESIAN\nUNITS=ANGSTROM)\n\n";
+        let zmat = Zmat::new(&mut Cursor::new(text)).unwrap();
+        match &zmat.geometry {
+            ZmatGeometry::Cartesian(atoms) => {
+                assert_eq!(atoms.len(), 2);
+                assert_eq!(atoms[1].symbol, "h");
+            }
+            ZmatGeometry::ZMatrix(_) => panic!("expected cartesian geometry"),
+        }
+        assert!(zmat.variables.is_empty());
+        assert_eq!(zmat.keyword("units"), Some("ANGSTROM"));
+        let mut written: Vec<u8> = Vec::new();
+        zmat.write(&mut written).unwrap();
+        assert_eq!(Zmat::new(&mut Cursor::new(written)).unwrap(), zmat);
+    }
+}
@@ -0,0 +1,46 @@
+use std::error;
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum ParseCifError {
+    /// Raised when the underlying reader fails.
+    Io(std::io::Error),
+    /// Raised when a quoted string or a text field is not terminated.
+    UnterminatedValue(String),
+    /// Raised when the tokens do not follow the STAR syntax, e.g. a value without tag.
+    InvalidSyntax(String),
+    /// Raised when a tag required for the crystal structure is missing.
+    MissingTag(String),
+    /// Raised when a value cannot be interpreted, e.g. a symmetry operator.
+    InvalidValue(String),
+    /// Raised when the cell parameters do not describe a cell, e.g. a zero length.
+    InvalidCell,
+}
+
+impl Display for ParseCifError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Could not read the CIF file: {}", e),
+            Self::UnterminatedValue(v) => write!(f, "The value '{}' is not terminated.", v),
+            Self::InvalidSyntax(t) => write!(f, "Unexpected token '{}'.", t),
+            Self::MissingTag(tag) => write!(f, "The tag '{}' is missing.", tag),
+            Self::InvalidValue(v) => write!(f, "Could not interpret the value '{}'.", v),
+            Self::InvalidCell => write!(f, "The cell parameters do not describe a cell."),
+        }
+    }
+}
+
+impl error::Error for ParseCifError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseCifError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
@@ -0,0 +1,428 @@
+//! The crystal structure of a CIF data block, i.e. the cell, the atoms of the asymmetric unit
+//! and the symmetry operators, which expand the asymmetric unit into the unit cell or into a
+//! cluster of arbitrary size.
+use nalgebra::{Matrix3, Point3, Vector3};
+
+use crate::common::{cartesian_to_fractional, fractional_to_cartesian};
+use crate::xyz::xyzline::symbol::{XYZLineSymbol, PSE_SYMBOLS};
+use crate::xyz::{element_label, Xyz, XyzLine};
+
+use super::ciferrors::ParseCifError;
+use super::{parse_cif_number, CifBlock};
+
+/// Sites closer than this distance in angstrom are considered the same.
+const SITE_TOLERANCE: f64 = 1e-2;
+/// Tags of the symmetry operators, in the order of the older and the newer dictionary.
+const OPERATOR_TAGS: [&str; 2] = [
+    "_symmetry_equiv_pos_as_xyz",
+    "_space_group_symop_operation_xyz",
+];
+
+/// The cell parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CifCell {
+    /// Lengths a, b and c in angstrom.
+    pub lengths: [f64; 3],
+    /// Angles alpha, beta and gamma in degrees.
+    pub angles: [f64; 3],
+}
+
+impl CifCell {
+    /// The lattice vectors as rows in angstrom, a along x and b in the xy plane.
+    pub fn matrix(&self) -> Matrix3<f64> {
+        let [a, b, c] = self.lengths;
+        // Right angles are common, their cosines are set to exactly 0.
+        let [cos_alpha, cos_beta, cos_gamma] = self.angles.map(|angle| {
+            if angle == 90.0 {
+                0.0
+            } else {
+                angle.to_radians().cos()
+            }
+        });
+        let sin_gamma = self.angles[2].to_radians().sin();
+        let cx = c * cos_beta;
+        let cy = c * (cos_alpha - cos_beta * cos_gamma) / sin_gamma;
+        let cz = (c * c - cx * cx - cy * cy).sqrt();
+        Matrix3::new(a, 0.0, 0.0, b * cos_gamma, b * sin_gamma, 0.0, cx, cy, cz)
+    }
+
+    /// Whether the parameters describe a cell, i.e. positive lengths and angles enclosing a
+    /// volume.
+    pub fn is_valid(&self) -> bool {
+        let matrix = self.matrix();
+        self.lengths.iter().all(|l| *l > 0.0)
+            && matrix.iter().all(|v| v.is_finite())
+            && matrix.determinant().abs() > 0.0
+    }
+}
+
+/// A symmetry operator mapping fractional coordinates `r` onto `rotation * r + translation`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SymmetryOperator {
+    pub rotation: Matrix3<f64>,
+    pub translation: Vector3<f64>,
+}
+
+impl SymmetryOperator {
+    /// Function to parse an operator like `-x+1/2, y, z+0.25`.
+    /// # Arguments
+    ///  * `operator` - The operator in the notation of `_symmetry_equiv_pos_as_xyz`.
+    pub fn parse(operator: &str) -> Result<Self, ParseCifError> {
+        let invalid = || ParseCifError::InvalidValue(operator.to_string());
+        let components: Vec<&str> = operator.split(',').collect();
+        if components.len() != 3 {
+            return Err(invalid());
+        }
+        let mut rotation = Matrix3::zeros();
+        let mut translation = Vector3::zeros();
+        for (row, component) in components.iter().enumerate() {
+            let component: String = component
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect::<String>()
+                .to_lowercase();
+            // Split into signed terms like `-x` or `+1/2`.
+            let mut terms: Vec<String> = Vec::new();
+            for c in component.chars() {
+                match terms.last_mut() {
+                    Some(term) if c != '+' && c != '-' => term.push(c),
+                    _ => terms.push(c.to_string()),
+                }
+            }
+            if terms.is_empty() {
+                return Err(invalid());
+            }
+            for term in terms {
+                let (sign, body) = match term.strip_prefix('-') {
+                    Some(b) => (-1.0, b),
+                    None => (1.0, term.strip_prefix('+').unwrap_or(&term)),
+                };
+                match body {
+                    "x" => rotation[(row, 0)] += sign,
+                    "y" => rotation[(row, 1)] += sign,
+                    "z" => rotation[(row, 2)] += sign,
+                    _ => {
+                        let value = match body.split_once('/') {
+                            Some((n, d)) => {
+                                n.parse::<f64>().map_err(|_| invalid())?
+                                    / d.parse::<f64>().map_err(|_| invalid())?
+                            }
+                            None => body.parse::<f64>().map_err(|_| invalid())?,
+                        };
+                        translation[row] += sign * value;
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            rotation,
+            translation,
+        })
+    }
+
+    /// The identity.
+    pub fn identity() -> Self {
+        Self {
+            rotation: Matrix3::identity(),
+            translation: Vector3::zeros(),
+        }
+    }
+
+    /// Applies the operator to fractional coordinates.
+    /// # Arguments
+    ///  * `fractional` - The fractional coordinates.
+    pub fn apply(&self, fractional: &Point3<f64>) -> Point3<f64> {
+        Point3::from(self.rotation * fractional.coords + self.translation)
+    }
+}
+
+/// An atom of the asymmetric unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CifSite {
+    pub label: String,
+    /// The element symbol of the type symbol or the label.
+    pub symbol: String,
+    pub fractional: Point3<f64>,
+    pub occupancy: f64,
+}
+
+/// The crystal structure of a data block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Crystal {
+    pub name: String,
+    pub cell: CifCell,
+    pub sites: Vec<CifSite>,
+    /// The symmetry operators, only the identity if none are given.
+    pub operators: Vec<SymmetryOperator>,
+}
+
+/// Returns the element symbol at the start of a type symbol or label, e.g. `Fe` of `Fe3+`. The
+/// second letter only belongs to the symbol if it is lowercase and forms an element with the
+/// first one, so `CA1` and `OW1` are carbon and oxygen sites.
+fn element_symbol(text: &str) -> String {
+    let letters: String = text
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .take(2)
+        .collect();
+    let is_element = |symbol: &str| PSE_SYMBOLS.contains(&symbol.to_lowercase().as_str());
+    if letters.len() == 2
+        && letters.ends_with(|c: char| c.is_ascii_lowercase())
+        && is_element(&letters)
+    {
+        element_label(&letters)
+    } else {
+        element_label(&letters[..letters.len().min(1)])
+    }
+}
+
+impl Crystal {
+    /// Constructor for a Crystal struct from a data block.
+    /// # Arguments:
+    ///  * `block` - The data block.
+    pub fn from_block(block: &CifBlock) -> Result<Self, ParseCifError> {
+        let number = |tag: &str| {
+            block
+                .number(tag)
+                .ok_or_else(|| ParseCifError::MissingTag(tag.to_string()))
+        };
+        let cell = CifCell {
+            lengths: [
+                number("_cell_length_a")?,
+                number("_cell_length_b")?,
+                number("_cell_length_c")?,
+            ],
+            angles: [
+                number("_cell_angle_alpha")?,
+                number("_cell_angle_beta")?,
+                number("_cell_angle_gamma")?,
+            ],
+        };
+        if !cell.is_valid() {
+            return Err(ParseCifError::InvalidCell);
+        }
+        let sites_loop = block
+            .find_loop("_atom_site_fract_x")
+            .ok_or_else(|| ParseCifError::MissingTag("_atom_site_fract_x".to_string()))?;
+        let column = |tag: &str| {
+            sites_loop
+                .column(tag)
+                .ok_or_else(|| ParseCifError::MissingTag(tag.to_string()))
+        };
+        let labels = column("_atom_site_label")?;
+        let coordinates = [
+            column("_atom_site_fract_x")?,
+            column("_atom_site_fract_y")?,
+            column("_atom_site_fract_z")?,
+        ];
+        let types = sites_loop.column("_atom_site_type_symbol");
+        let occupancies = sites_loop.column("_atom_site_occupancy");
+        let value = |v: &str| {
+            parse_cif_number(v)
+                .map(|(n, _)| n)
+                .ok_or_else(|| ParseCifError::InvalidValue(v.to_string()))
+        };
+        let mut sites = Vec::with_capacity(labels.len());
+        for (i, label) in labels.iter().enumerate() {
+            let symbol = match types.as_ref().map(|t| t[i]) {
+                Some(t) if t != "?" && t != "." => element_symbol(t),
+                _ => element_symbol(label),
+            };
+            sites.push(CifSite {
+                label: label.to_string(),
+                symbol,
+                fractional: Point3::new(
+                    value(coordinates[0][i])?,
+                    value(coordinates[1][i])?,
+                    value(coordinates[2][i])?,
+                ),
+                occupancy: match occupancies.as_ref().map(|o| o[i]) {
+                    Some(o) if o != "?" && o != "." => value(o)?,
+                    _ => 1.0,
+                },
+            });
+        }
+        let operators = match OPERATOR_TAGS
+            .iter()
+            .find_map(|tag| block.find_loop(tag).and_then(|l| l.column(tag)))
+        {
+            Some(column) => column
+                .into_iter()
+                .map(SymmetryOperator::parse)
+                .collect::<Result<Vec<_>, _>>()?,
+            None => vec![SymmetryOperator::identity()],
+        };
+        Ok(Self {
+            name: block.name.clone(),
+            cell,
+            sites,
+            operators,
+        })
+    }
+
+    /// Returns all sites of the unit cell, with fractional coordinates in [0, 1). Images of a
+    /// site closer than 0.01 angstrom to a previous image of the same site are dropped, while
+    /// different sites sharing a position, e.g. of mixed occupancy, are all kept.
+    pub fn unit_cell_sites(&self) -> Vec<CifSite> {
+        let matrix = self.cell.matrix();
+        let mut sites: Vec<CifSite> = Vec::new();
+        for site in self.sites.iter() {
+            let mut images: Vec<Point3<f64>> = Vec::with_capacity(self.operators.len());
+            for operator in self.operators.iter() {
+                let image = operator.apply(&site.fractional).map(|c| c - c.floor());
+                let duplicate = images.iter().any(|previous| {
+                    let difference = (image - previous).map(|d| d - d.round());
+                    (matrix.transpose() * difference).norm() < SITE_TOLERANCE
+                });
+                if !duplicate {
+                    images.push(image);
+                }
+            }
+            sites.extend(images.into_iter().map(|fractional| CifSite {
+                fractional,
+                ..site.clone()
+            }));
+        }
+        sites
+    }
+
+    /// Returns the geometry of the unit cell in angstrom.
+    pub fn unit_cell(&self) -> Xyz<f64> {
+        let matrix = self.cell.matrix();
+        let lines: Vec<XyzLine<f64>> = self
+            .unit_cell_sites()
+            .into_iter()
+            .map(|s| {
+                XyzLine::Symbolic(XYZLineSymbol {
+                    symbol: s.symbol.to_lowercase(),
+                    xyz: fractional_to_cartesian(&matrix, &s.fractional),
+                })
+            })
+            .collect();
+        Xyz {
+            number_of_atoms: lines.len(),
+            distances_in: "ang".to_string(),
+            info_line: self.name.clone(),
+            lines,
+        }
+    }
+
+    /// Returns the atoms within a sphere, which may extend over several unit cells. Fails if the
+    /// cell parameters do not describe a cell.
+    /// # Arguments
+    ///  * `center` - The Cartesian coordinates of the center in angstrom.
+    ///  * `radius` - The radius in angstrom.
+    pub fn cluster(&self, center: &Point3<f64>, radius: f64) -> Result<Xyz<f64>, ParseCifError> {
+        if !self.cell.is_valid() {
+            return Err(ParseCifError::InvalidCell);
+        }
+        let matrix = self.cell.matrix();
+        let volume = matrix.determinant().abs();
+        let fractional_center =
+            cartesian_to_fractional(&matrix, center).ok_or(ParseCifError::InvalidCell)?;
+        // The sphere spans radius / width cells along each axis, the width being the distance
+        // between opposite faces of the cell.
+        let rows: Vec<Vector3<f64>> = matrix.row_iter().map(|r| r.transpose()).collect();
+        let mut ranges = [(0, 0); 3];
+        for (axis, range) in ranges.iter_mut().enumerate() {
+            let width = volume / rows[(axis + 1) % 3].cross(&rows[(axis + 2) % 3]).norm();
+            let extent = radius / width;
+            *range = (
+                (fractional_center[axis] - extent).floor() as i64 - 1,
+                (fractional_center[axis] + extent).ceil() as i64 + 1,
+            );
+        }
+        let sites = self.unit_cell_sites();
+        let mut lines: Vec<XyzLine<f64>> = Vec::new();
+        for i in ranges[0].0..=ranges[0].1 {
+            for j in ranges[1].0..=ranges[1].1 {
+                for k in ranges[2].0..=ranges[2].1 {
+                    let shift = Vector3::new(i as f64, j as f64, k as f64);
+                    for site in sites.iter() {
+                        let xyz = fractional_to_cartesian(&matrix, &(site.fractional + shift));
+                        if (xyz - center).norm() <= radius {
+                            lines.push(XyzLine::Symbolic(XYZLineSymbol {
+                                symbol: site.symbol.to_lowercase(),
+                                xyz,
+                            }));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(Xyz {
+            number_of_atoms: lines.len(),
+            distances_in: "ang".to_string(),
+            info_line: format!("{} cluster of radius {}", self.name, radius),
+            lines,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operators_and_cell() {
+        let operator = SymmetryOperator::parse("-x+1/2, Y-z, 0.25+z").unwrap();
+        assert_eq!(
+            operator.apply(&Point3::new(0.1, 0.2, 0.3)),
+            Point3::new(0.4, 0.2 - 0.3, 0.55)
+        );
+        assert!(SymmetryOperator::parse("x,y").is_err());
+        assert!(SymmetryOperator::parse("x,y,w").is_err());
+        let hexagonal = CifCell {
+            lengths: [3.0, 3.0, 5.0],
+            angles: [90.0, 90.0, 120.0],
+        };
+        let matrix = hexagonal.matrix();
+        assert!((matrix.determinant() - 9.0 * 3.0_f64.sqrt() / 2.0 * 5.0).abs() < 1e-10);
+        assert!((matrix.row(1).norm() - 3.0).abs() < 1e-12);
+        assert!(hexagonal.is_valid());
+        let flat = CifCell {
+            lengths: [3.0, 3.0, 5.0],
+            angles: [90.0, 150.0, 30.0],
+        };
+        assert!(!flat.is_valid());
+        assert!(!CifCell {
+            lengths: [0.0, 3.0, 5.0],
+            angles: [90.0, 90.0, 90.0],
+        }
+        .is_valid());
+        assert_eq!(element_symbol("Fe3+"), "Fe");
+        assert_eq!(element_symbol("O12"), "O");
+        assert_eq!(element_symbol("Ca1"), "Ca");
+        assert_eq!(element_symbol("CA1"), "C");
+        assert_eq!(element_symbol("OW1"), "O");
+        assert_eq!(element_symbol("HA"), "H");
+        assert_eq!(element_symbol("Hx2"), "H");
+    }
+
+    #[test]
+    fn test_mixed_occupancy() {
+        let site = |label: &str, symbol: &str| CifSite {
+            label: label.to_string(),
+            symbol: symbol.to_string(),
+            fractional: Point3::new(0.25, 0.25, 0.25),
+            occupancy: 0.5,
+        };
+        let crystal = Crystal {
+            name: "FeNi".to_string(),
+            cell: CifCell {
+                lengths: [3.5, 3.5, 3.5],
+                angles: [90.0, 90.0, 90.0],
+            },
+            sites: vec![site("Fe1", "Fe"), site("Ni1", "Ni")],
+            operators: vec![
+                SymmetryOperator::identity(),
+                SymmetryOperator::parse("-x+1/2, -y+1/2, -z+1/2").unwrap(),
+                SymmetryOperator::parse("-x, -y, -z").unwrap(),
+            ],
+        };
+        let sites = crystal.unit_cell_sites();
+        let labels: Vec<&str> = sites.iter().map(|s| s.label.as_str()).collect();
+        assert_eq!(labels, vec!["Fe1", "Fe1", "Ni1", "Ni1"]);
+        assert_eq!(sites[1].fractional, Point3::new(0.75, 0.75, 0.75));
+    }
+}
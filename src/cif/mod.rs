@@ -0,0 +1,346 @@
+//! This module provides a reader for crystallographic information files, see [`crystal`] for the
+//! crystal structure and its expansion by symmetry. CIF files follow the STAR syntax: data blocks
+//! hold tagged values and loops, values may be quoted or given as text fields between lines
+//! starting with a semicolon.
+//! ```text
+//! data_NaCl
+//! _symmetry_space_group_name_H-M   'F m -3 m'
+//! _cell_length_a                   5.6402(3)
+//! loop_
+//! _atom_site_label
+//! _atom_site_fract_x
+//! ...
+//! Na1  0.0  0.0  0.0
+//! ```
+//! Tags are compared ignoring case, with the dots of DDLm tags like `_cell.length_a` treated
+//! as underscores.
+use std::io::BufRead;
+
+use self::ciferrors::ParseCifError;
+pub mod ciferrors;
+pub mod crystal;
+
+/// A token of the STAR syntax.
+#[derive(Debug, PartialEq)]
+struct Token {
+    text: String,
+    /// Whether the token was quoted or a text field, i.e. cannot be a keyword or a tag.
+    quoted: bool,
+}
+
+/// A loop of a data block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CifLoop {
+    pub tags: Vec<String>,
+    /// The values, one row per packet.
+    pub rows: Vec<Vec<String>>,
+}
+
+impl CifLoop {
+    /// Returns the values of a column.
+    /// # Arguments
+    ///  * `tag` - The tag of the column.
+    pub fn column(&self, tag: &str) -> Option<Vec<&str>> {
+        let index = self.tags.iter().position(|t| same_tag(t, tag))?;
+        Some(self.rows.iter().map(|r| r[index].as_str()).collect())
+    }
+}
+
+/// A data block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CifBlock {
+    pub name: String,
+    /// Tags and values outside of loops.
+    pub items: Vec<(String, String)>,
+    pub loops: Vec<CifLoop>,
+}
+
+impl CifBlock {
+    /// Returns the value of a tag outside of loops.
+    /// # Arguments
+    ///  * `tag` - The tag.
+    pub fn value(&self, tag: &str) -> Option<&str> {
+        self.items
+            .iter()
+            .find(|(t, _)| same_tag(t, tag))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Returns the number of a tag outside of loops, without its uncertainty.
+    /// # Arguments
+    ///  * `tag` - The tag.
+    pub fn number(&self, tag: &str) -> Option<f64> {
+        self.value(tag).and_then(parse_cif_number).map(|(v, _)| v)
+    }
+
+    /// Returns the loop holding a tag.
+    /// # Arguments
+    ///  * `tag` - The tag of a column of the loop.
+    pub fn find_loop(&self, tag: &str) -> Option<&CifLoop> {
+        self.loops
+            .iter()
+            .find(|l| l.tags.iter().any(|t| same_tag(t, tag)))
+    }
+}
+
+/// Represents a CIF file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cif {
+    pub blocks: Vec<CifBlock>,
+}
+
+impl Cif {
+    /// Constructor for a Cif struct. Save frames are not supported, their markers are skipped.
+    /// # Arguments:
+    ///  * `file_handle` - A handle to a `BufReader`, i.e. the CIF file.
+    ///
+    ///  # Example:
+    ///
+    /// ```no_run
+    ///  use std::fs::File;
+    ///  use std::io::BufReader;
+    ///  use qc_file_parsers::cif::Cif;
+    ///  use qc_file_parsers::cif::crystal::Crystal;
+    ///  fn main() -> std::io::Result<()> {
+    ///     let f = File::open("structure.cif")?;
+    ///     let mut b = BufReader::new(f);
+    ///     let cif = Cif::new(&mut b).unwrap();
+    ///     let crystal = Crystal::from_block(&cif.blocks[0]).unwrap();
+    ///     let unit_cell = crystal.unit_cell();
+    ///     Ok(())
+    ///  }
+    ///  ```
+    pub fn new<I: BufRead>(file_handle: &mut I) -> Result<Self, ParseCifError> {
+        let lines = file_handle.lines().collect::<Result<Vec<String>, _>>()?;
+        let mut blocks: Vec<CifBlock> = Vec::new();
+        // Tags and values of the loop being read.
+        let mut current: Option<(Vec<String>, Vec<String>)> = None;
+        let mut tokens = tokenize(&lines)?.into_iter();
+        while let Some(token) = tokens.next() {
+            let keyword = if token.quoted {
+                String::new()
+            } else {
+                token.text.to_lowercase()
+            };
+            if keyword.starts_with("data_") {
+                close_loop(&mut blocks, &mut current)?;
+                blocks.push(CifBlock {
+                    name: token.text[5..].to_string(),
+                    ..Default::default()
+                });
+                continue;
+            }
+            if keyword == "loop_" {
+                close_loop(&mut blocks, &mut current)?;
+                current = Some((Vec::new(), Vec::new()));
+                continue;
+            }
+            if keyword.starts_with("save_") {
+                continue;
+            }
+            if blocks.is_empty() {
+                return Err(ParseCifError::InvalidSyntax(token.text));
+            }
+            if keyword.starts_with('_') {
+                if let Some((tags, values)) = current.as_mut() {
+                    if values.is_empty() {
+                        tags.push(token.text);
+                        continue;
+                    }
+                }
+                close_loop(&mut blocks, &mut current)?;
+                let value = match tokens.next() {
+                    Some(v) if v.quoted || !v.text.starts_with('_') => v.text,
+                    _ => return Err(ParseCifError::InvalidSyntax(token.text)),
+                };
+                blocks
+                    .last_mut()
+                    .expect("A block is present.")
+                    .items
+                    .push((token.text, value));
+                continue;
+            }
+            match current.as_mut() {
+                Some((tags, values)) if !tags.is_empty() => values.push(token.text),
+                _ => return Err(ParseCifError::InvalidSyntax(token.text)),
+            }
+        }
+        close_loop(&mut blocks, &mut current)?;
+        Ok(Self { blocks })
+    }
+
+    /// Returns a data block by its name, ignoring case.
+    /// # Arguments
+    ///  * `name` - The name of the block without `data_`.
+    pub fn block(&self, name: &str) -> Option<&CifBlock> {
+        self.blocks
+            .iter()
+            .find(|b| b.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Whether two tags are the same, ignoring case and treating dots as underscores.
+fn same_tag(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.chars().zip(b.chars()).all(|(x, y)| {
+            let normalize = |c: char| {
+                if c == '.' {
+                    '_'
+                } else {
+                    c.to_ascii_lowercase()
+                }
+            };
+            normalize(x) == normalize(y)
+        })
+}
+
+/// Function to add the loop being read to the last block.
+fn close_loop(
+    blocks: &mut [CifBlock],
+    current: &mut Option<(Vec<String>, Vec<String>)>,
+) -> Result<(), ParseCifError> {
+    let Some((tags, values)) = current.take() else {
+        return Ok(());
+    };
+    let block = blocks
+        .last_mut()
+        .ok_or_else(|| ParseCifError::InvalidSyntax("loop_".to_string()))?;
+    if tags.is_empty() || !values.len().is_multiple_of(tags.len()) {
+        return Err(ParseCifError::InvalidSyntax(
+            tags.first().cloned().unwrap_or_else(|| "loop_".to_string()),
+        ));
+    }
+    let rows = values.chunks(tags.len()).map(|r| r.to_vec()).collect();
+    block.loops.push(CifLoop { tags, rows });
+    Ok(())
+}
+
+/// Function to split the lines into tokens, dropping comments.
+fn tokenize(lines: &[String]) -> Result<Vec<Token>, ParseCifError> {
+    let mut tokens = Vec::new();
+    // Lines of the text field being read.
+    let mut text_field: Option<Vec<&str>> = None;
+    for line in lines.iter() {
+        if let Some(field) = text_field.as_mut() {
+            if line.starts_with(';') {
+                tokens.push(Token {
+                    text: field.join("\n"),
+                    quoted: true,
+                });
+                text_field = None;
+            } else {
+                field.push(line);
+            }
+            continue;
+        }
+        if let Some(first) = line.strip_prefix(';') {
+            text_field = Some(if first.trim().is_empty() {
+                Vec::new()
+            } else {
+                vec![first]
+            });
+            continue;
+        }
+        let mut rest = line.trim_start();
+        while !rest.is_empty() {
+            if rest.starts_with('#') {
+                break;
+            }
+            let quote = rest.chars().next().filter(|c| *c == '\'' || *c == '"');
+            let (token, remainder) = match quote {
+                // A quote only closes the string if it is followed by white space.
+                Some(q) => {
+                    let end = rest[1..]
+                        .char_indices()
+                        .find(|(i, c)| {
+                            *c == q
+                                && rest[1 + i + 1..]
+                                    .chars()
+                                    .next()
+                                    .is_none_or(|n| n.is_whitespace())
+                        })
+                        .map(|(i, _)| i + 1)
+                        .ok_or_else(|| ParseCifError::UnterminatedValue(rest.to_string()))?;
+                    (
+                        Token {
+                            text: rest[1..end].to_string(),
+                            quoted: true,
+                        },
+                        &rest[end + 1..],
+                    )
+                }
+                None => {
+                    let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                    (
+                        Token {
+                            text: rest[..end].to_string(),
+                            quoted: false,
+                        },
+                        &rest[end..],
+                    )
+                }
+            };
+            tokens.push(token);
+            rest = remainder.trim_start();
+        }
+    }
+    if let Some(field) = text_field {
+        return Err(ParseCifError::UnterminatedValue(field.join("\n")));
+    }
+    Ok(tokens)
+}
+
+/// Function to parse a number with an optional standard uncertainty in parentheses, e.g.
+/// `1.234(5)`, which is returned on the scale of the number, i.e. 0.005.
+/// # Arguments
+///  * `value` - The value to parse.
+pub fn parse_cif_number(value: &str) -> Option<(f64, Option<f64>)> {
+    let (number, uncertainty) = match value.split_once('(') {
+        Some((n, u)) => (n, Some(u.strip_suffix(')')?)),
+        None => (value, None),
+    };
+    let parsed = number.parse::<f64>().ok()?;
+    let Some(uncertainty) = uncertainty else {
+        return Some((parsed, None));
+    };
+    let digits = uncertainty.parse::<f64>().ok()?;
+    let mantissa = number.split(['e', 'E']).next().unwrap_or_default();
+    let decimals = mantissa.split_once('.').map_or(0, |(_, d)| d.len()) as i32;
+    let exponent = number
+        .split_once(['e', 'E'])
+        .and_then(|(_, e)| e.parse::<i32>().ok())
+        .unwrap_or(0);
+    let scale = exponent - decimals;
+    Some((
+        parsed,
+        Some(if scale < 0 {
+            digits / 10_f64.powi(-scale)
+        } else {
+            digits * 10_f64.powi(scale)
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbers_and_tokens() {
+        assert_eq!(parse_cif_number("5.6402(3)"), Some((5.6402, Some(3e-4))));
+        assert_eq!(parse_cif_number("90"), Some((90.0, None)));
+        assert_eq!(parse_cif_number("120(2)"), Some((120.0, Some(2.0))));
+        assert_eq!(parse_cif_number("?"), None);
+        let lines = vec![
+            "_name 'O'Neil' # comment".to_string(),
+            ";".to_string(),
+            "text".to_string(),
+            ";".to_string(),
+        ];
+        let tokens = tokenize(&lines).unwrap();
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(tokens[1].text, "O'Neil");
+        assert_eq!(tokens[2].text, "text");
+        assert!(same_tag("_cell.length_a", "_CELL_LENGTH_A"));
+    }
+}
@@ -0,0 +1,47 @@
+//! Types and functions shared by the parsers of several file formats.
+use nalgebra::{Matrix3, Point3};
+
+/// Selects alpha or beta orbitals.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Spin {
+    #[default]
+    Alpha,
+    Beta,
+}
+
+/// Function to convert fractional into Cartesian coordinates.
+/// # Arguments
+///  * `cell` - The lattice vectors as rows.
+///  * `fractional` - The fractional coordinates.
+pub fn fractional_to_cartesian(cell: &Matrix3<f64>, fractional: &Point3<f64>) -> Point3<f64> {
+    Point3::from(cell.transpose() * fractional.coords)
+}
+
+/// Function to convert Cartesian into fractional coordinates, `None` for a singular cell.
+/// # Arguments
+///  * `cell` - The lattice vectors as rows.
+///  * `cartesian` - The Cartesian coordinates.
+pub fn cartesian_to_fractional(
+    cell: &Matrix3<f64>,
+    cartesian: &Point3<f64>,
+) -> Option<Point3<f64>> {
+    cell.transpose()
+        .try_inverse()
+        .map(|inverse| Point3::from(inverse * cartesian.coords))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fractional_coordinates() {
+        let cell = Matrix3::new(0.0, 0.5, 0.5, 0.5, 0.0, 0.5, 0.5, 0.5, 0.0) * 5.43;
+        let fractional = Point3::new(0.25, 0.25, 0.25);
+        let cartesian = fractional_to_cartesian(&cell, &fractional);
+        assert!((cartesian - Point3::new(1.3575, 1.3575, 1.3575)).norm() < 1e-12);
+        let back = cartesian_to_fractional(&cell, &cartesian).unwrap();
+        assert!((back - fractional).norm() < 1e-12);
+        assert_eq!(cartesian_to_fractional(&Matrix3::zeros(), &cartesian), None);
+    }
+}
@@ -0,0 +1,50 @@
+use std::error;
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum ParseCubeError {
+    /// Raised when the underlying reader or writer fails.
+    Io(std::io::Error),
+    /// Raised when the file ends before the header is complete.
+    UnexpectedEof,
+    /// Raised when a header line cannot be parsed.
+    InvalidLine(String),
+    /// Raised when the number of volumetric values does not match the grid.
+    ShapeMismatch { expected: usize, found: usize },
+    /// Raised when two cubes on different grids are combined.
+    GridMismatch,
+    /// Raised when a cube without a three-dimensional grid is written.
+    MissingGrid,
+}
+
+impl Display for ParseCubeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Could not read the cube file: {}", e),
+            Self::UnexpectedEof => write!(f, "The cube file ended within its header."),
+            Self::InvalidLine(line) => write!(f, "Could not parse the header line '{}'.", line),
+            Self::ShapeMismatch { expected, found } => write!(
+                f,
+                "Expected {} volumetric values, but found {}.",
+                expected, found
+            ),
+            Self::GridMismatch => write!(f, "The cubes are not defined on the same grid."),
+            Self::MissingGrid => write!(f, "The cube has no three-dimensional grid."),
+        }
+    }
+}
+
+impl error::Error for ParseCubeError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseCubeError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
@@ -0,0 +1,381 @@
+//! This module provides a reader and writer for Gaussian cube files, which hold volumetric data
+//! like densities, electrostatic potentials or orbitals on a regular grid.
+//! ```text
+//! comment line 1
+//! comment line 2
+//!     3    0.000000    0.000000    0.000000          <- number of atoms, origin
+//!    40    0.250000    0.000000    0.000000          <- points and step along the first axis
+//!    40    0.000000    0.250000    0.000000
+//!    40    0.000000    0.000000    0.250000
+//!     8    8.000000    0.000000    0.000000    0.221904   <- Z, charge, x, y, z
+//!  ...
+//!  0.12345E-02  0.23456E-02 ...                      <- six values per line
+//! ```
+//! A negative number of atoms marks files with several orbitals. The atom lines are then
+//! followed by a line with the number of orbitals and their indices and each grid point holds
+//! one value per orbital. A negative number of points along an axis marks lengths in angstrom.
+use nalgebra::{Matrix3, Point3, Vector3};
+use std::io::{BufRead, Write};
+
+use crate::array_text::tensor::Tensor;
+use crate::format_string::{format_fortran_real, parse_fortran_real};
+use crate::xyz::xyzline::numeric::XYZLineNumeric;
+use crate::xyz::{Xyz, XyzLine};
+
+use self::cubeerrors::ParseCubeError;
+pub mod cubeerrors;
+
+/// An atom of a cube file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CubeAtom {
+    /// Atomic number and position.
+    pub atom: XYZLineNumeric<f64>,
+    /// The nuclear charge, which may differ from the atomic number for pseudopotentials.
+    pub charge: f64,
+}
+
+/// Represents a cube file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cube {
+    /// The first comment line.
+    pub title: String,
+    /// The second comment line.
+    pub comment: String,
+    /// The length unit of origin, axes and atoms, 'bohr' === `bohr` or 'angstroem' === `ang`.
+    pub distances_in: String,
+    /// Position of the first grid point.
+    pub origin: Point3<f64>,
+    /// The step vectors of the three grid axes as rows.
+    pub axes: Matrix3<f64>,
+    /// The atoms of the molecule.
+    pub atoms: Vec<CubeAtom>,
+    /// Indices of the orbitals of multi-orbital files, empty otherwise.
+    pub orbitals: Vec<i32>,
+    /// One grid of shape [n1, n2, n3] per orbital, or a single one.
+    pub grids: Vec<Tensor<f64>>,
+}
+
+/// Parses the leading integer and the following reals of a header line.
+fn parse_header_line(line: &str, nreals: usize) -> Result<(i64, Vec<f64>), ParseCubeError> {
+    let invalid = || ParseCubeError::InvalidLine(line.to_string());
+    let mut tokens = line.split_whitespace();
+    let count = tokens
+        .next()
+        .ok_or_else(invalid)?
+        .parse::<i64>()
+        .map_err(|_| invalid())?;
+    let reals = tokens
+        .take(nreals)
+        .map(|t| parse_fortran_real::<f64>(t).map_err(|_| invalid()))
+        .collect::<Result<Vec<f64>, _>>()?;
+    if reals.len() != nreals {
+        return Err(invalid());
+    }
+    Ok((count, reals))
+}
+
+impl Cube {
+    /// Constructor for a Cube struct.
+    /// # Arguments:
+    ///  * `file_handle` - A handle to a `BufReader`, i.e. the cube file.
+    ///
+    ///  # Example:
+    ///
+    /// ```no_run
+    ///  use std::fs::File;
+    ///  use std::io::BufReader;
+    ///  use qc_file_parsers::cube::Cube;
+    ///  fn main() -> std::io::Result<()> {
+    ///     let f = File::open("density.cube")?;
+    ///     let mut b = BufReader::new(f);
+    ///     let cube = Cube::new(&mut b).unwrap();
+    ///     let electrons = cube.integrate()[0];
+    ///     Ok(())
+    ///  }
+    ///  ```
+    pub fn new<I: BufRead>(file_handle: &mut I) -> Result<Self, ParseCubeError> {
+        let mut lines = file_handle.lines();
+        let mut next_line = || -> Result<String, ParseCubeError> {
+            Ok(lines.next().ok_or(ParseCubeError::UnexpectedEof)??)
+        };
+        let title = next_line()?.trim().to_string();
+        let comment = next_line()?.trim().to_string();
+        let (natoms, origin) = parse_header_line(&next_line()?, 3)?;
+        let mut shape = [0; 3];
+        let mut axes = Matrix3::zeros();
+        let mut distances_in = "bohr".to_string();
+        for (i, n) in shape.iter_mut().enumerate() {
+            let (points, step) = parse_header_line(&next_line()?, 3)?;
+            if points < 0 {
+                distances_in = "ang".to_string();
+            }
+            *n = points.unsigned_abs() as usize;
+            axes.set_row(i, &Vector3::new(step[0], step[1], step[2]).transpose());
+        }
+        let mut atoms = Vec::with_capacity(natoms.unsigned_abs() as usize);
+        for _ in 0..natoms.unsigned_abs() {
+            let line = next_line()?;
+            let (z_value, values) = parse_header_line(&line, 4)?;
+            atoms.push(CubeAtom {
+                atom: XYZLineNumeric {
+                    z_value: usize::try_from(z_value)
+                        .map_err(|_| ParseCubeError::InvalidLine(line.clone()))?,
+                    xyz: Point3::new(values[1], values[2], values[3]),
+                },
+                charge: values[0],
+            });
+        }
+        let mut orbitals: Vec<i32> = Vec::new();
+        let mut values: Vec<f64> = Vec::new();
+        if natoms < 0 {
+            // The orbital line may be continued, so all integers up to the count are taken.
+            let mut norbitals: Option<usize> = None;
+            while norbitals.is_none_or(|n| orbitals.len() < n) {
+                let line = next_line()?;
+                for token in line.split_whitespace() {
+                    let index = token
+                        .parse::<i32>()
+                        .map_err(|_| ParseCubeError::InvalidLine(line.clone()))?;
+                    match norbitals {
+                        None => norbitals = Some(index.max(0) as usize),
+                        Some(_) => orbitals.push(index),
+                    }
+                }
+            }
+        }
+        for line in lines {
+            let line = line?;
+            for token in line.split_whitespace() {
+                values.push(
+                    parse_fortran_real::<f64>(token)
+                        .map_err(|_| ParseCubeError::InvalidLine(line.clone()))?,
+                );
+            }
+        }
+        let nvalues = orbitals.len().max(1);
+        let npoints: usize = shape.iter().product();
+        if values.len() != npoints * nvalues {
+            return Err(ParseCubeError::ShapeMismatch {
+                expected: npoints * nvalues,
+                found: values.len(),
+            });
+        }
+        // The values of all orbitals at one grid point follow each other.
+        let grids = (0..nvalues)
+            .map(|m| {
+                let data = values.iter().skip(m).step_by(nvalues).copied().collect();
+                Tensor::from_vec(shape.to_vec(), data).expect("Shape is checked above.")
+            })
+            .collect();
+        Ok(Self {
+            title,
+            comment,
+            distances_in,
+            origin: Point3::new(origin[0], origin[1], origin[2]),
+            axes,
+            atoms,
+            orbitals,
+            grids,
+        })
+    }
+
+    /// Number of grid points along the three axes, `None` if there is no three-dimensional grid.
+    pub fn shape(&self) -> Option<[usize; 3]> {
+        match self.grids.first()?.shape() {
+            &[n1, n2, n3] => Some([n1, n2, n3]),
+            _ => None,
+        }
+    }
+
+    /// The volume of a single voxel.
+    pub fn voxel_volume(&self) -> f64 {
+        self.axes.determinant().abs()
+    }
+
+    /// Position of the grid point (i, j, k).
+    pub fn point(&self, i: usize, j: usize, k: usize) -> Point3<f64> {
+        self.origin
+            + self.axes.row(0).transpose() * i as f64
+            + self.axes.row(1).transpose() * j as f64
+            + self.axes.row(2).transpose() * k as f64
+    }
+
+    /// Integrates each grid, e.g. to obtain the number of electrons from a density.
+    pub fn integrate(&self) -> Vec<f64> {
+        let volume = self.voxel_volume();
+        self.grids
+            .iter()
+            .map(|g| g.as_slice().iter().sum::<f64>() * volume)
+            .collect()
+    }
+
+    /// Combines the values of two cubes on the same grid point by point. The atoms and comments
+    /// are taken from `self`.
+    /// # Arguments
+    ///  * `other` - The second cube.
+    ///  * `op` - Function of the two values at a grid point.
+    pub fn combine<F>(&self, other: &Self, op: F) -> Result<Self, ParseCubeError>
+    where
+        F: Fn(f64, f64) -> f64,
+    {
+        if self.origin != other.origin
+            || self.axes != other.axes
+            || self.grids.len() != other.grids.len()
+            || self.shape() != other.shape()
+        {
+            return Err(ParseCubeError::GridMismatch);
+        }
+        let mut combined = self.clone();
+        for (grid, other_grid) in combined.grids.iter_mut().zip(other.grids.iter()) {
+            for (v, o) in grid.as_mut_slice().iter_mut().zip(other_grid.as_slice()) {
+                *v = op(*v, *o);
+            }
+        }
+        Ok(combined)
+    }
+
+    /// Returns the difference `self - other`, e.g. a difference density.
+    /// # Arguments
+    ///  * `other` - The cube to subtract.
+    pub fn difference(&self, other: &Self) -> Result<Self, ParseCubeError> {
+        self.combine(other, |a, b| a - b)
+    }
+
+    /// Returns the atoms as xyz structure.
+    pub fn to_xyz(&self) -> Xyz<f64> {
+        Xyz {
+            number_of_atoms: self.atoms.len(),
+            distances_in: self.distances_in.clone(),
+            info_line: self.title.clone(),
+            lines: self
+                .atoms
+                .iter()
+                .map(|a| XyzLine::Numeric(a.atom.clone()))
+                .collect(),
+        }
+    }
+
+    /// Writes the cube file in the format used by Gaussian.
+    /// # Arguments
+    ///  * `dest` - Where to write to.
+    pub fn write<W: Write>(&self, dest: &mut W) -> Result<(), ParseCubeError> {
+        let [n1, n2, n3] = self.shape().ok_or(ParseCubeError::MissingGrid)?;
+        writeln!(dest, "{}", self.title)?;
+        writeln!(dest, "{}", self.comment)?;
+        let natoms = self.atoms.len() as i64;
+        writeln!(
+            dest,
+            "{:5}{:12.6}{:12.6}{:12.6}",
+            if self.orbitals.is_empty() {
+                natoms
+            } else {
+                -natoms
+            },
+            self.origin.x,
+            self.origin.y,
+            self.origin.z
+        )?;
+        let unit_sign = if self.distances_in == "ang" { -1 } else { 1 };
+        for (n, axis) in [n1, n2, n3].iter().zip(self.axes.row_iter()) {
+            writeln!(
+                dest,
+                "{:5}{:12.6}{:12.6}{:12.6}",
+                unit_sign * *n as i64,
+                axis[0],
+                axis[1],
+                axis[2]
+            )?;
+        }
+        for a in self.atoms.iter() {
+            writeln!(
+                dest,
+                "{:5}{:12.6}{:12.6}{:12.6}{:12.6}",
+                a.atom.z_value, a.charge, a.atom.xyz.x, a.atom.xyz.y, a.atom.xyz.z
+            )?;
+        }
+        if !self.orbitals.is_empty() {
+            write!(dest, "{:5}", self.orbitals.len())?;
+            for o in self.orbitals.iter() {
+                write!(dest, "{:5}", o)?;
+            }
+            writeln!(dest)?;
+        }
+        // Each run along the third axis starts on a new line.
+        let nvalues = self.grids.len();
+        for i in 0..n1 {
+            for j in 0..n2 {
+                let mut count = 0;
+                for k in 0..n3 {
+                    for grid in self.grids.iter() {
+                        write!(
+                            dest,
+                            "{}",
+                            format_fortran_real(grid[&[i, j, k][..]], 13, 5, 'E')
+                        )?;
+                        count += 1;
+                        if count % 6 == 0 {
+                            writeln!(dest)?;
+                        }
+                    }
+                }
+                if !(n3 * nvalues).is_multiple_of(6) {
+                    writeln!(dest)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_multi_orbital() {
+        let text = " MO cube
+ two orbitals
+   -1    0.000000    0.000000    0.000000
+    2    1.000000    0.000000    0.000000
+    1    0.000000    1.000000    0.000000
+    2    0.000000    0.000000    0.500000
+    1    1.000000    0.000000    0.000000    0.000000
+    2    5    6
+  0.10000E+00 -0.10000E+00  0.20000E+00 -0.20000E+00
+  0.30000E+00 -0.30000E+00  0.40000E+00 -0.40000E+00
+";
+        let cube = Cube::new(&mut Cursor::new(text)).unwrap();
+        assert_eq!(cube.orbitals, vec![5, 6]);
+        assert_eq!(cube.shape(), Some([2, 1, 2]));
+        assert_eq!(cube.grids[1][&[1, 0, 0][..]], -0.3);
+        assert_eq!(cube.point(1, 0, 1), Point3::new(1.0, 0.0, 0.5));
+        let integrals = cube.integrate();
+        assert!((integrals[0] - 0.5).abs() < 1e-12);
+        assert!((integrals[1] + 0.5).abs() < 1e-12);
+        let mut written: Vec<u8> = Vec::new();
+        cube.write(&mut written).unwrap();
+        assert_eq!(Cube::new(&mut Cursor::new(written)).unwrap(), cube);
+    }
+
+    #[test]
+    fn test_missing_grid() {
+        let text = " density
+ one point
+    1    0.000000    0.000000    0.000000
+    1    1.000000    0.000000    0.000000
+    1    0.000000    1.000000    0.000000
+    1    0.000000    0.000000    1.000000
+    1    1.000000    0.000000    0.000000    0.000000
+  0.10000E+00
+";
+        let mut cube = Cube::new(&mut Cursor::new(text)).unwrap();
+        assert_eq!(cube.shape(), Some([1, 1, 1]));
+        cube.grids.clear();
+        assert_eq!(cube.shape(), None);
+        assert!(matches!(
+            cube.write(&mut Vec::new()),
+            Err(ParseCubeError::MissingGrid)
+        ));
+    }
+}
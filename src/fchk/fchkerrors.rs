@@ -0,0 +1,71 @@
+use std::error;
+use std::fmt::Display;
+
+use crate::array_text::arraytexterrors::ParseArrayTextError;
+use crate::format_string::frmtstngerror::ParseFortranFormattedError;
+
+#[derive(Debug)]
+pub enum ParseFchkError {
+    /// Raised when the underlying reader fails.
+    Io(std::io::Error),
+    /// Raised when the file ends before the header or a section is complete.
+    UnexpectedEof,
+    /// Raised when a line is not a valid section header.
+    InvalidHeader(String),
+    /// Raised when the values of a section cannot be parsed.
+    FormatError(ParseFortranFormattedError),
+    /// Raised when a requested section is not in the file.
+    MissingSection(String),
+    /// Raised when a section does not hold the requested type or shape of data.
+    WrongType(String),
+    /// Raised when an integer of a section is out of range, e.g. a negative count.
+    InvalidValue(String),
+    /// Raised when an array cannot be turned into a matrix.
+    ShapeError(ParseArrayTextError),
+}
+
+impl Display for ParseFchkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Could not read the fchk file: {}", e),
+            Self::UnexpectedEof => write!(f, "The fchk file ended unexpectedly."),
+            Self::InvalidHeader(line) => write!(f, "'{}' is not a valid section header.", line),
+            Self::FormatError(e) => write!(f, "Could not parse the section values: {}", e),
+            Self::MissingSection(name) => write!(f, "There is no section '{}'.", name),
+            Self::WrongType(name) => {
+                write!(f, "Section '{}' does not hold the requested data.", name)
+            }
+            Self::InvalidValue(name) => write!(f, "Section '{}' holds a value out of range.", name),
+            Self::ShapeError(e) => write!(f, "Could not build the matrix: {}", e),
+        }
+    }
+}
+
+impl error::Error for ParseFchkError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::FormatError(e) => Some(e),
+            Self::ShapeError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseFchkError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<ParseFortranFormattedError> for ParseFchkError {
+    fn from(value: ParseFortranFormattedError) -> Self {
+        Self::FormatError(value)
+    }
+}
+
+impl From<ParseArrayTextError> for ParseFchkError {
+    fn from(value: ParseArrayTextError) -> Self {
+        Self::ShapeError(value)
+    }
+}
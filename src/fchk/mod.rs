@@ -0,0 +1,522 @@
+//! This module provides a parser for Gaussian formatted checkpoint files (fchk). After a title
+//! line and a line holding job type, method and basis set, the file consists of typed sections
+//! ```text
+//! Number of atoms                            I                3
+//! Current cartesian coordinates              R   N=           9
+//!   0.00000000E+00  0.00000000E+00  2.21904480E-01  0.00000000E+00  1.43996597E+00
+//!  -8.87617920E-01  0.00000000E+00 -1.43996597E+00 -8.87617920E-01
+//! ```
+//! Arrays are written with the Fortran formats `6I12`, `5E16.8`, `5A12`, `9A8` and `72L1`.
+use nalgebra::{DMatrix, Point3};
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::array_text::packed::{unpack_triangular, MatrixSymmetry, TriangularPacking};
+use crate::format_string::{
+    parse_fortran_formatted_line, parse_fortran_real, FortranFormat, ParsedValue,
+};
+use crate::xyz::xyzline::numeric::XYZLineNumeric;
+use crate::xyz::xyzline::symbol::PSE_SYMBOLS;
+use crate::xyz::{Xyz, XyzLine};
+
+pub use crate::common::Spin;
+
+use self::fchkerrors::ParseFchkError;
+pub mod fchkerrors;
+
+/// The value of a single section.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FchkValue {
+    Integer(i32),
+    Real(f64),
+    Character(String),
+    Logical(bool),
+    IntegerArray(Vec<i32>),
+    RealArray(Vec<f64>),
+    /// Character arrays (types `C` and `H`) are joined into a single string.
+    CharacterArray(String),
+    LogicalArray(Vec<bool>),
+}
+
+/// A contracted shell of the basis set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FchkShell {
+    /// Gaussian shell type: 0 = s, 1 = p, -1 = sp, 2 = cartesian d, -2 = spherical d, ...
+    pub shell_type: i32,
+    /// Zero based index of the atom the shell is centered on.
+    pub atom: usize,
+    /// Center of the shell in bohr.
+    pub center: Point3<f64>,
+    /// Exponents of the primitives.
+    pub exponents: Vec<f64>,
+    /// Contraction coefficients of the primitives.
+    pub coefficients: Vec<f64>,
+    /// The p contraction coefficients of sp shells.
+    pub sp_coefficients: Option<Vec<f64>>,
+}
+
+/// Represents a formatted checkpoint file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fchk {
+    /// The title of the job.
+    pub title: String,
+    /// Job type, e.g. `SP` or `FOpt`.
+    pub job_type: String,
+    /// Method, e.g. `RB3LYP`.
+    pub method: String,
+    /// Basis set, e.g. `6-31G(d)`.
+    pub basis: String,
+    /// All sections by name.
+    pub sections: HashMap<String, FchkValue>,
+}
+
+/// Function to convert an integer of a section to `usize`, checking that it lies in `range`.
+/// # Arguments
+///  * `value` - The integer read from the file.
+///  * `range` - The allowed values.
+///  * `name` - Name of the section, reported on failure.
+fn to_usize(value: i32, range: Range<usize>, name: &str) -> Result<usize, ParseFchkError> {
+    usize::try_from(value)
+        .ok()
+        .filter(|v| range.contains(v))
+        .ok_or_else(|| ParseFchkError::InvalidValue(name.to_string()))
+}
+
+/// Parses the header line of a section into name, type and either the number of elements of
+/// an array or the value of a scalar.
+fn parse_header(line: &str) -> Result<(String, char, Result<usize, String>), ParseFchkError> {
+    let invalid = || ParseFchkError::InvalidHeader(line.to_string());
+    let name = line.get(..40).ok_or_else(invalid)?.trim().to_string();
+    let kind = line
+        .get(43..44)
+        .and_then(|k| k.chars().next())
+        .ok_or_else(invalid)?;
+    let rest = line.get(44..).ok_or_else(invalid)?.trim();
+    match rest.strip_prefix("N=") {
+        Some(count) => Ok((
+            name,
+            kind,
+            Ok(count.trim().parse::<usize>().map_err(|_| invalid())?),
+        )),
+        None => Ok((name, kind, Err(rest.to_string()))),
+    }
+}
+
+/// Reads the lines of a character array section. The fields are joined without trimming, as
+/// words may be split between them.
+fn parse_character_array<L>(
+    lines: &mut L,
+    kind: char,
+    count: usize,
+) -> Result<String, ParseFchkError>
+where
+    L: Iterator<Item = std::io::Result<String>>,
+{
+    let (per_line, width) = if kind == 'H' { (9, 8) } else { (5, 12) };
+    let mut text = String::with_capacity(count * width);
+    for _ in 0..count.div_ceil(per_line) {
+        let line = lines.next().ok_or(ParseFchkError::UnexpectedEof)??;
+        text.push_str(&format!("{:<w$}", line, w = per_line * width));
+    }
+    Ok(text.trim().to_string())
+}
+
+/// Reads the lines of an array section until `count` values are found.
+fn parse_array<L>(
+    lines: &mut L,
+    kind: char,
+    count: usize,
+) -> Result<Vec<ParsedValue>, ParseFchkError>
+where
+    L: Iterator<Item = std::io::Result<String>>,
+{
+    let format = match kind {
+        'I' => "6i12",
+        'R' => "5e16.8",
+        'L' => "72l1",
+        _ => return Err(ParseFchkError::InvalidHeader(kind.to_string())),
+    };
+    let ff = [FortranFormat::from_str(format)?];
+    let mut values: Vec<ParsedValue> = Vec::with_capacity(count);
+    while values.len() < count {
+        let line = lines.next().ok_or(ParseFchkError::UnexpectedEof)??;
+        for parsed in parse_fortran_formatted_line(&line, &ff)? {
+            values.extend(parsed);
+        }
+    }
+    Ok(values)
+}
+
+impl Fchk {
+    /// Constructor for an Fchk struct.
+    /// # Arguments:
+    ///  * `file_handle` - A handle to a `BufReader`, i.e. the fchk file.
+    ///
+    ///  # Example:
+    ///
+    /// ```no_run
+    ///  use std::fs::File;
+    ///  use std::io::BufReader;
+    ///  use qc_file_parsers::fchk::Fchk;
+    ///  fn main() -> std::io::Result<()> {
+    ///     let f = File::open("test_file.fchk")?;
+    ///     let mut b = BufReader::new(f);
+    ///     let fchk = Fchk::new(&mut b).unwrap();
+    ///     let energy = fchk.real("Total Energy").unwrap();
+    ///     Ok(())
+    ///  }
+    ///  ```
+    pub fn new<I: BufRead>(file_handle: &mut I) -> Result<Self, ParseFchkError> {
+        let mut lines = file_handle.lines();
+        let title = lines
+            .next()
+            .ok_or(ParseFchkError::UnexpectedEof)??
+            .trim()
+            .to_string();
+        let job_line = lines.next().ok_or(ParseFchkError::UnexpectedEof)??;
+        let field = |range: std::ops::Range<usize>| {
+            job_line
+                .get(range.start..range.end.min(job_line.len()))
+                .unwrap_or("")
+                .trim()
+                .to_string()
+        };
+        let (job_type, method, basis) = (field(0..10), field(10..40), field(40..job_line.len()));
+        let mut sections: HashMap<String, FchkValue> = HashMap::new();
+        while let Some(line) = lines.next() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (name, kind, size) = parse_header(&line)?;
+            let wrong = || ParseFchkError::WrongType(name.clone());
+            let value = match size {
+                Err(scalar) => match kind {
+                    'I' => FchkValue::Integer(scalar.parse::<i32>().map_err(|_| wrong())?),
+                    'R' => {
+                        FchkValue::Real(parse_fortran_real::<f64>(&scalar).map_err(|_| wrong())?)
+                    }
+                    'C' | 'H' => FchkValue::Character(scalar),
+                    'L' => FchkValue::Logical(scalar.starts_with('T')),
+                    _ => return Err(ParseFchkError::InvalidHeader(line)),
+                },
+                Ok(count) if kind == 'C' || kind == 'H' => {
+                    FchkValue::CharacterArray(parse_character_array(&mut lines, kind, count)?)
+                }
+                Ok(count) => {
+                    let values = parse_array(&mut lines, kind, count)?;
+                    match kind {
+                        'I' => FchkValue::IntegerArray(
+                            values
+                                .into_iter()
+                                .map(|v| match v {
+                                    ParsedValue::In(i) => Ok(i),
+                                    _ => Err(wrong()),
+                                })
+                                .collect::<Result<_, _>>()?,
+                        ),
+                        'R' => FchkValue::RealArray(
+                            values
+                                .into_iter()
+                                .map(|v| match v {
+                                    ParsedValue::Fl(r) => Ok(r),
+                                    _ => Err(wrong()),
+                                })
+                                .collect::<Result<_, _>>()?,
+                        ),
+                        _ => FchkValue::LogicalArray(
+                            values
+                                .into_iter()
+                                .map(|v| match v {
+                                    ParsedValue::Lo(l) => Ok(l),
+                                    _ => Err(wrong()),
+                                })
+                                .collect::<Result<_, _>>()?,
+                        ),
+                    }
+                }
+            };
+            sections.insert(name, value);
+        }
+        Ok(Self {
+            title,
+            job_type,
+            method,
+            basis,
+            sections,
+        })
+    }
+
+    /// Returns the value of a section.
+    /// # Arguments
+    ///  * `name` - Name of the section, e.g. `Total Energy`.
+    pub fn get(&self, name: &str) -> Result<&FchkValue, ParseFchkError> {
+        self.sections
+            .get(name)
+            .ok_or_else(|| ParseFchkError::MissingSection(name.to_string()))
+    }
+
+    /// Returns the value of an integer scalar section.
+    pub fn integer(&self, name: &str) -> Result<i32, ParseFchkError> {
+        match self.get(name)? {
+            FchkValue::Integer(i) => Ok(*i),
+            _ => Err(ParseFchkError::WrongType(name.to_string())),
+        }
+    }
+
+    /// Returns the value of a real scalar section.
+    pub fn real(&self, name: &str) -> Result<f64, ParseFchkError> {
+        match self.get(name)? {
+            FchkValue::Real(r) => Ok(*r),
+            _ => Err(ParseFchkError::WrongType(name.to_string())),
+        }
+    }
+
+    /// Returns the values of an integer array section.
+    pub fn integer_array(&self, name: &str) -> Result<&[i32], ParseFchkError> {
+        match self.get(name)? {
+            FchkValue::IntegerArray(a) => Ok(a),
+            _ => Err(ParseFchkError::WrongType(name.to_string())),
+        }
+    }
+
+    /// Returns the values of a real array section.
+    pub fn real_array(&self, name: &str) -> Result<&[f64], ParseFchkError> {
+        match self.get(name)? {
+            FchkValue::RealArray(a) => Ok(a),
+            _ => Err(ParseFchkError::WrongType(name.to_string())),
+        }
+    }
+
+    /// Returns a packed lower triangular real array section as full symmetric matrix.
+    /// # Arguments
+    ///  * `name` - Name of the section, e.g. `Total SCF Density`.
+    pub fn packed_matrix(&self, name: &str) -> Result<DMatrix<f64>, ParseFchkError> {
+        Ok(unpack_triangular(
+            self.real_array(name)?.to_vec(),
+            TriangularPacking::Lower,
+            true,
+            MatrixSymmetry::Symmetric,
+        )?)
+    }
+
+    /// Returns the current geometry in bohr.
+    pub fn geometry(&self) -> Result<Xyz<f64>, ParseFchkError> {
+        let atomic_numbers = self.integer_array("Atomic numbers")?;
+        let coordinates = self.real_array("Current cartesian coordinates")?;
+        if coordinates.len() != 3 * atomic_numbers.len() {
+            return Err(ParseFchkError::WrongType(
+                "Current cartesian coordinates".to_string(),
+            ));
+        }
+        let lines = atomic_numbers
+            .iter()
+            .zip(coordinates.chunks_exact(3))
+            .map(|(z, xyz)| {
+                Ok(XyzLine::Numeric(XYZLineNumeric {
+                    z_value: to_usize(*z, 0..PSE_SYMBOLS.len(), "Atomic numbers")?,
+                    xyz: Point3::new(xyz[0], xyz[1], xyz[2]),
+                }))
+            })
+            .collect::<Result<Vec<_>, ParseFchkError>>()?;
+        Ok(Xyz {
+            number_of_atoms: atomic_numbers.len(),
+            distances_in: "bohr".to_string(),
+            info_line: self.title.clone(),
+            lines,
+        })
+    }
+
+    /// Returns the contracted shells of the basis set.
+    pub fn basis_shells(&self) -> Result<Vec<FchkShell>, ParseFchkError> {
+        let shell_types = self.integer_array("Shell types")?;
+        let primitives = self.integer_array("Number of primitives per shell")?;
+        let atoms = self.integer_array("Shell to atom map")?;
+        let exponents = self.real_array("Primitive exponents")?;
+        let coefficients = self.real_array("Contraction coefficients")?;
+        let sp_coefficients = self.real_array("P(S=P) Contraction coefficients").ok();
+        let centers = self.real_array("Coordinates of each shell")?;
+        let primitives = primitives
+            .iter()
+            .map(|n| to_usize(*n, 1..usize::MAX, "Number of primitives per shell"))
+            .collect::<Result<Vec<usize>, _>>()?;
+        let nprimitives: usize = primitives.iter().sum();
+        if primitives.len() != shell_types.len()
+            || atoms.len() != shell_types.len()
+            || centers.len() != 3 * shell_types.len()
+            || exponents.len() != nprimitives
+            || coefficients.len() != nprimitives
+        {
+            return Err(ParseFchkError::WrongType("Shell types".to_string()));
+        }
+        let mut start = 0;
+        let mut shells = Vec::with_capacity(shell_types.len());
+        for (k, shell_type) in shell_types.iter().enumerate() {
+            let end = start + primitives[k];
+            shells.push(FchkShell {
+                shell_type: *shell_type,
+                atom: to_usize(atoms[k], 1..usize::MAX, "Shell to atom map")? - 1,
+                center: Point3::new(centers[3 * k], centers[3 * k + 1], centers[3 * k + 2]),
+                exponents: exponents[start..end].to_vec(),
+                coefficients: coefficients[start..end].to_vec(),
+                sp_coefficients: match sp_coefficients {
+                    Some(sp) if *shell_type == -1 => sp.get(start..end).map(|c| c.to_vec()),
+                    _ => None,
+                },
+            });
+            start = end;
+        }
+        Ok(shells)
+    }
+
+    /// Returns the MO coefficients with one column per orbital.
+    /// # Arguments
+    ///  * `spin` - Alpha or beta orbitals.
+    pub fn mo_coefficients(&self, spin: Spin) -> Result<DMatrix<f64>, ParseFchkError> {
+        let name = match spin {
+            Spin::Alpha => "Alpha MO coefficients",
+            Spin::Beta => "Beta MO coefficients",
+        };
+        let coefficients = self.real_array(name)?;
+        let nbasis = to_usize(
+            self.integer("Number of basis functions")?,
+            1..usize::MAX,
+            "Number of basis functions",
+        )?;
+        if coefficients.len() % nbasis != 0 {
+            return Err(ParseFchkError::WrongType(name.to_string()));
+        }
+        Ok(DMatrix::from_column_slice(
+            nbasis,
+            coefficients.len() / nbasis,
+            coefficients,
+        ))
+    }
+
+    /// Returns the orbital energies.
+    /// # Arguments
+    ///  * `spin` - Alpha or beta orbitals.
+    pub fn mo_energies(&self, spin: Spin) -> Result<&[f64], ParseFchkError> {
+        match spin {
+            Spin::Alpha => self.real_array("Alpha Orbital Energies"),
+            Spin::Beta => self.real_array("Beta Orbital Energies"),
+        }
+    }
+
+    /// Returns the total SCF density matrix.
+    pub fn total_scf_density(&self) -> Result<DMatrix<f64>, ParseFchkError> {
+        self.packed_matrix("Total SCF Density")
+    }
+
+    /// Returns the SCF spin density matrix of open shell calculations.
+    pub fn spin_scf_density(&self) -> Result<DMatrix<f64>, ParseFchkError> {
+        self.packed_matrix("Spin SCF Density")
+    }
+
+    /// Returns the cartesian gradient in hartree/bohr with one row per atom.
+    pub fn gradient(&self) -> Result<DMatrix<f64>, ParseFchkError> {
+        let gradient = self.real_array("Cartesian Gradient")?;
+        if gradient.len() % 3 != 0 {
+            return Err(ParseFchkError::WrongType("Cartesian Gradient".to_string()));
+        }
+        Ok(DMatrix::from_row_slice(gradient.len() / 3, 3, gradient))
+    }
+
+    /// Returns the cartesian force constants, i.e. the Hessian in hartree/bohr^2.
+    pub fn force_constants(&self) -> Result<DMatrix<f64>, ParseFchkError> {
+        self.packed_matrix("Cartesian Force Constants")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header() {
+        let (name, kind, size) =
+            parse_header("Number of atoms                            I                3").unwrap();
+        assert_eq!(
+            (name.as_str(), kind, size),
+            ("Number of atoms", 'I', Err("3".to_string()))
+        );
+        let (name, kind, size) =
+            parse_header("Alpha MO coefficients                      R   N=          49").unwrap();
+        assert_eq!(
+            (name.as_str(), kind, size),
+            ("Alpha MO coefficients", 'R', Ok(49))
+        );
+        assert!(parse_header("too short").is_err());
+    }
+
+    #[test]
+    fn test_out_of_range_integers() {
+        let mut fchk = Fchk {
+            title: String::new(),
+            job_type: String::new(),
+            method: String::new(),
+            basis: String::new(),
+            sections: HashMap::from([
+                (
+                    "Atomic numbers".to_string(),
+                    FchkValue::IntegerArray(vec![-1]),
+                ),
+                (
+                    "Current cartesian coordinates".to_string(),
+                    FchkValue::RealArray(vec![0.0; 3]),
+                ),
+                (
+                    "Number of basis functions".to_string(),
+                    FchkValue::Integer(-2),
+                ),
+                (
+                    "Alpha MO coefficients".to_string(),
+                    FchkValue::RealArray(vec![1.0]),
+                ),
+                ("Shell types".to_string(), FchkValue::IntegerArray(vec![0])),
+                (
+                    "Number of primitives per shell".to_string(),
+                    FchkValue::IntegerArray(vec![1]),
+                ),
+                (
+                    "Shell to atom map".to_string(),
+                    FchkValue::IntegerArray(vec![0]),
+                ),
+                (
+                    "Primitive exponents".to_string(),
+                    FchkValue::RealArray(vec![1.0]),
+                ),
+                (
+                    "Contraction coefficients".to_string(),
+                    FchkValue::RealArray(vec![1.0]),
+                ),
+                (
+                    "Coordinates of each shell".to_string(),
+                    FchkValue::RealArray(vec![0.0; 3]),
+                ),
+            ]),
+        };
+        assert!(matches!(
+            fchk.geometry(),
+            Err(ParseFchkError::InvalidValue(_))
+        ));
+        assert!(matches!(
+            fchk.mo_coefficients(Spin::Alpha),
+            Err(ParseFchkError::InvalidValue(_))
+        ));
+        assert!(matches!(
+            fchk.basis_shells(),
+            Err(ParseFchkError::InvalidValue(_))
+        ));
+        fchk.sections.insert(
+            "Number of primitives per shell".to_string(),
+            FchkValue::IntegerArray(vec![-1]),
+        );
+        assert!(matches!(
+            fchk.basis_shells(),
+            Err(ParseFchkError::InvalidValue(_))
+        ));
+    }
+}
@@ -0,0 +1,42 @@
+use std::error;
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum ParseFcidumpError {
+    /// Raised when the underlying reader or writer fails.
+    Io(std::io::Error),
+    /// Raised when the `&FCI` namelist is missing, incomplete or malformed.
+    InvalidHeader(String),
+    /// Raised when an integral line cannot be parsed.
+    InvalidLine(String),
+    /// Raised when an orbital index exceeds `NORB`.
+    IndexOutOfRange(String),
+}
+
+impl Display for ParseFcidumpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Could not read the FCIDUMP file: {}", e),
+            Self::InvalidHeader(msg) => write!(f, "Invalid FCIDUMP header: {}", msg),
+            Self::InvalidLine(line) => write!(f, "Could not parse the line '{}'.", line),
+            Self::IndexOutOfRange(line) => {
+                write!(f, "The orbital index in '{}' exceeds NORB.", line)
+            }
+        }
+    }
+}
+
+impl error::Error for ParseFcidumpError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseFcidumpError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
@@ -0,0 +1,547 @@
+//! This module provides a reader and a writer for FCIDUMP files, the integral interchange format
+//! between SCF programs and CI or DMRG solvers. A Fortran namelist is followed by one integral per
+//! line, the kind of the integral being given by the non-zero indices:
+//! ```text
+//!  &FCI NORB=  2,NELEC= 2,MS2= 0,
+//!   ORBSYM=1,5,
+//!   ISYM=1,
+//!  &END
+//!   0.6757101549    1    1    1    1     two-electron integral (ij|kl)
+//!  -1.2563390730    1    1    0    0     one-electron integral h_ij
+//!   0.7137539936    0    0    0    0     core energy
+//! ```
+//! Unrestricted files set `IUHF=1` and list the (aa|aa), (bb|bb) and (aa|bb) integrals followed by
+//! the alpha and beta one-electron integrals, each section terminated by a line with zero value
+//! and indices. Complex integrals are written as `(re,im)`. Orbital energies, i.e. lines with a
+//! single non-zero index, are skipped.
+use nalgebra::{DMatrix, Scalar};
+use num::{Complex, Zero};
+use std::io::{BufRead, Lines, Write};
+
+use crate::array_text::tensor::Tensor;
+use crate::format_string::parse_fortran_real;
+
+use self::fcidumperrors::ParseFcidumpError;
+pub mod fcidumperrors;
+
+/// The value type of the integrals, i.e. `f64` or `Complex<f64>`.
+pub trait IntegralValue: Scalar + Copy + Zero {
+    /// Whether the orbitals are real, so that (ij|kl) = (ji|kl).
+    const REAL: bool;
+    /// Parses the value tokens of an integral line.
+    fn parse(tokens: &[&str]) -> Option<Self>;
+    /// Formats the value for an integral line.
+    fn format(&self) -> String;
+    fn conjugate(&self) -> Self;
+    fn magnitude(&self) -> f64;
+}
+
+impl IntegralValue for f64 {
+    const REAL: bool = true;
+
+    fn parse(tokens: &[&str]) -> Option<Self> {
+        match tokens {
+            [value] => parse_fortran_real::<f64>(value).ok(),
+            _ => None,
+        }
+    }
+
+    fn format(&self) -> String {
+        format!("{:24.16E}", self)
+    }
+
+    fn conjugate(&self) -> Self {
+        *self
+    }
+
+    fn magnitude(&self) -> f64 {
+        self.abs()
+    }
+}
+
+impl IntegralValue for Complex<f64> {
+    const REAL: bool = false;
+
+    fn parse(tokens: &[&str]) -> Option<Self> {
+        let (re, im) = match tokens {
+            [tuple] => tuple
+                .strip_prefix('(')
+                .and_then(|t| t.strip_suffix(')'))
+                .and_then(|t| t.split_once(','))?,
+            [re, im] => (*re, *im),
+            _ => return None,
+        };
+        Some(Complex::new(
+            parse_fortran_real::<f64>(re.trim()).ok()?,
+            parse_fortran_real::<f64>(im.trim()).ok()?,
+        ))
+    }
+
+    fn format(&self) -> String {
+        format!("({:.16E},{:.16E})", self.re, self.im)
+    }
+
+    fn conjugate(&self) -> Self {
+        self.conj()
+    }
+
+    fn magnitude(&self) -> f64 {
+        self.norm()
+    }
+}
+
+/// The `&FCI` namelist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FcidumpHeader {
+    /// Number of orbitals.
+    pub norb: usize,
+    /// Number of electrons.
+    pub nelec: usize,
+    /// Twice the spin projection.
+    pub ms2: i64,
+    /// Irreducible representation of each orbital, starting at 1.
+    pub orbsym: Vec<usize>,
+    /// Irreducible representation of the wave function.
+    pub isym: usize,
+    /// Whether the integrals are given per spin.
+    pub uhf: bool,
+    /// Further entries of the namelist with their values.
+    pub extra: Vec<(String, Vec<String>)>,
+}
+
+impl FcidumpHeader {
+    /// Reads the namelist up to `&END` or `/`.
+    fn parse<I: BufRead>(lines: &mut Lines<I>) -> Result<Self, ParseFcidumpError> {
+        let mut entries: Vec<(String, Vec<String>)> = Vec::new();
+        'namelist: loop {
+            let line = lines.next().ok_or_else(|| {
+                ParseFcidumpError::InvalidHeader("The namelist is not terminated.".to_string())
+            })??;
+            // Entries may be written as `NORB = 4`, so the blanks around `=` are dropped.
+            let normalized = line
+                .replace(',', " ")
+                .split('=')
+                .map(str::trim)
+                .collect::<Vec<_>>()
+                .join("=");
+            for token in normalized.split_whitespace() {
+                let upper = token.to_uppercase();
+                if upper == "&END" || upper == "/" || upper == "$END" {
+                    break 'namelist;
+                } else if upper.starts_with('&') || upper.starts_with('$') {
+                    continue;
+                } else if let Some((key, value)) = token.split_once('=') {
+                    let mut values = Vec::new();
+                    if !value.is_empty() {
+                        values.push(value.to_string());
+                    }
+                    entries.push((key.trim().to_uppercase(), values));
+                } else if let Some((_, values)) = entries.last_mut() {
+                    values.push(token.to_string());
+                } else {
+                    return Err(ParseFcidumpError::InvalidHeader(line));
+                }
+            }
+        }
+        let mut take = |key: &str| {
+            entries
+                .iter()
+                .position(|(k, _)| k == key)
+                .map(|p| entries.remove(p).1)
+        };
+        fn number<T: std::str::FromStr>(
+            key: &str,
+            values: Option<Vec<String>>,
+        ) -> Result<Option<T>, ParseFcidumpError> {
+            match values.as_deref() {
+                None => Ok(None),
+                Some([value]) => value
+                    .parse::<T>()
+                    .map(Some)
+                    .map_err(|_| ParseFcidumpError::InvalidHeader(format!("{}={}", key, value))),
+                Some(values) => Err(ParseFcidumpError::InvalidHeader(format!(
+                    "{}={}",
+                    key,
+                    values.join(",")
+                ))),
+            }
+        }
+        let missing = |key: &str| ParseFcidumpError::InvalidHeader(format!("{} is missing.", key));
+        let norb = number::<usize>("NORB", take("NORB"))?.ok_or_else(|| missing("NORB"))?;
+        let nelec = number::<usize>("NELEC", take("NELEC"))?.ok_or_else(|| missing("NELEC"))?;
+        let ms2 = number::<i64>("MS2", take("MS2"))?.unwrap_or(0);
+        let isym = number::<usize>("ISYM", take("ISYM"))?.unwrap_or(1);
+        let orbsym = match take("ORBSYM") {
+            Some(values) => values
+                .iter()
+                .map(|v| v.parse::<usize>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| ParseFcidumpError::InvalidHeader(values.join(",")))?,
+            None => vec![1; norb],
+        };
+        if orbsym.len() != norb {
+            return Err(ParseFcidumpError::InvalidHeader(format!(
+                "ORBSYM has {} entries for {} orbitals.",
+                orbsym.len(),
+                norb
+            )));
+        }
+        let uhf = take("IUHF")
+            .or_else(|| take("UHF"))
+            .and_then(|v| v.first().map(|v| v.trim_matches('.').to_uppercase()))
+            .is_some_and(|v| v == "1" || v.starts_with('T'));
+        Ok(Self {
+            norb,
+            nelec,
+            ms2,
+            orbsym,
+            isym,
+            uhf,
+            extra: entries,
+        })
+    }
+
+    /// Writes the namelist.
+    fn write<W: Write>(&self, dest: &mut W) -> Result<(), ParseFcidumpError> {
+        writeln!(
+            dest,
+            " &FCI NORB={:4},NELEC={:3},MS2={:2},",
+            self.norb, self.nelec, self.ms2
+        )?;
+        let orbsym: Vec<String> = self.orbsym.iter().map(|s| s.to_string()).collect();
+        writeln!(dest, "  ORBSYM={},", orbsym.join(","))?;
+        writeln!(dest, "  ISYM={},", self.isym)?;
+        if self.uhf {
+            writeln!(dest, "  IUHF=1,")?;
+        }
+        for (key, values) in self.extra.iter() {
+            writeln!(dest, "  {}={},", key, values.join(","))?;
+        }
+        writeln!(dest, " &END")?;
+        Ok(())
+    }
+}
+
+/// Indices sharing the value of (ij|kl) and whether the value has to be conjugated.
+/// # Arguments
+///  * `index` - The indices i, j, k and l.
+///  * `real` - Whether the orbitals are real.
+///  * `pair_symmetric` - Whether (ij|kl) = (kl|ij).
+fn symmetry_partners(
+    index: [usize; 4],
+    real: bool,
+    pair_symmetric: bool,
+) -> Vec<([usize; 4], bool)> {
+    let [i, j, k, l] = index;
+    let mut partners = vec![([i, j, k, l], false), ([j, i, l, k], true)];
+    if pair_symmetric {
+        partners.extend([([k, l, i, j], false), ([l, k, j, i], true)]);
+    }
+    if real {
+        partners.extend([([j, i, k, l], false), ([i, j, l, k], false)]);
+        if pair_symmetric {
+            partners.extend([([l, k, i, j], false), ([k, l, j, i], false)]);
+        }
+    }
+    partners
+}
+
+/// How the two-electron integrals are stored.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntegralStorage<T> {
+    /// All norb^4 elements (ij|kl) with the permutational symmetry unfolded.
+    Unfolded(Tensor<T>),
+    /// The integrals as listed in the file with zero based indices.
+    Packed(Vec<([usize; 4], T)>),
+}
+
+/// A block of two-electron integrals in chemists' notation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TwoElectronIntegrals<T> {
+    /// Whether (ij|kl) = (kl|ij), which does not hold for the alpha-beta block of UHF.
+    pub pair_symmetric: bool,
+    pub storage: IntegralStorage<T>,
+}
+
+impl<T: IntegralValue> TwoElectronIntegrals<T> {
+    /// Returns the integral (ij|kl) with zero based indices. Packed integrals are searched
+    /// linearly.
+    /// # Arguments
+    ///  * `index` - The indices i, j, k and l.
+    pub fn get(&self, index: [usize; 4]) -> T {
+        match &self.storage {
+            IntegralStorage::Unfolded(tensor) => tensor[&index[..]],
+            IntegralStorage::Packed(integrals) => {
+                let partners = symmetry_partners(index, T::REAL, self.pair_symmetric);
+                integrals
+                    .iter()
+                    .find_map(|(stored, value)| {
+                        partners.iter().find(|(p, _)| p == stored).map(|(_, conj)| {
+                            if *conj {
+                                value.conjugate()
+                            } else {
+                                *value
+                            }
+                        })
+                    })
+                    .unwrap_or_else(T::zero)
+            }
+        }
+    }
+
+    /// Returns the integrals with the permutational symmetry unfolded.
+    /// # Arguments
+    ///  * `norb` - Number of orbitals.
+    pub fn unfolded(&self, norb: usize) -> Tensor<T> {
+        match &self.storage {
+            IntegralStorage::Unfolded(tensor) => tensor.clone(),
+            IntegralStorage::Packed(integrals) => {
+                let mut tensor = Tensor::from_element(vec![norb; 4], T::zero());
+                for (index, value) in integrals.iter() {
+                    for (p, conj) in symmetry_partners(*index, T::REAL, self.pair_symmetric) {
+                        tensor[&p[..]] = if conj { value.conjugate() } else { *value };
+                    }
+                }
+                tensor
+            }
+        }
+    }
+
+    /// Returns one representative of every set of symmetry equivalent integrals.
+    /// # Arguments
+    ///  * `norb` - Number of orbitals.
+    fn unique(&self, norb: usize) -> Vec<([usize; 4], T)> {
+        match &self.storage {
+            IntegralStorage::Packed(integrals) => integrals.clone(),
+            IntegralStorage::Unfolded(tensor) => {
+                let mut unique = Vec::new();
+                for (flat, value) in tensor.as_slice().iter().enumerate() {
+                    let index = [
+                        flat / (norb * norb * norb),
+                        flat / (norb * norb) % norb,
+                        flat / norb % norb,
+                        flat % norb,
+                    ];
+                    let partners = symmetry_partners(index, T::REAL, self.pair_symmetric);
+                    if partners.iter().all(|(p, _)| *p <= index) {
+                        unique.push((index, *value));
+                    }
+                }
+                unique
+            }
+        }
+    }
+}
+
+/// Represents a FCIDUMP file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fcidump<T> {
+    pub header: FcidumpHeader,
+    pub core_energy: T,
+    /// The one-electron integrals, for UHF alpha followed by beta.
+    pub one_electron: Vec<DMatrix<T>>,
+    /// The two-electron integrals, for UHF (aa|aa), (bb|bb) and (aa|bb).
+    pub two_electron: Vec<TwoElectronIntegrals<T>>,
+}
+
+impl<T: IntegralValue> Fcidump<T> {
+    /// Constructor for a Fcidump struct.
+    /// # Arguments:
+    ///  * `file_handle` - A handle to a `BufReader`, i.e. the FCIDUMP file.
+    ///  * `unfold` - Whether to unfold the two-electron integrals into norb^4 tensors or to keep
+    ///    them as listed in the file.
+    ///
+    ///  # Example:
+    ///
+    /// ```no_run
+    ///  use std::fs::File;
+    ///  use std::io::BufReader;
+    ///  use qc_file_parsers::fcidump::Fcidump;
+    ///  fn main() -> std::io::Result<()> {
+    ///     let f = File::open("FCIDUMP")?;
+    ///     let mut b = BufReader::new(f);
+    ///     let fcidump: Fcidump<f64> = Fcidump::new(&mut b, true).unwrap();
+    ///     let h = &fcidump.one_electron[0];
+    ///     Ok(())
+    ///  }
+    ///  ```
+    pub fn new<I: BufRead>(file_handle: &mut I, unfold: bool) -> Result<Self, ParseFcidumpError> {
+        let mut lines = file_handle.lines();
+        let header = FcidumpHeader::parse(&mut lines)?;
+        let norb = header.norb;
+        let nspin = if header.uhf { 2 } else { 1 };
+        let mut one_electron = vec![DMatrix::zeros(norb, norb); nspin];
+        let nblocks = if header.uhf { 3 } else { 1 };
+        let mut packed: Vec<Vec<([usize; 4], T)>> = vec![Vec::new(); nblocks];
+        let mut core_energy = T::zero();
+        let mut section: usize = 0;
+        for line in lines {
+            let line = line?;
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            if tokens.is_empty() {
+                continue;
+            }
+            if tokens.len() < 5 {
+                return Err(ParseFcidumpError::InvalidLine(line));
+            }
+            let (value_tokens, index_tokens) = tokens.split_at(tokens.len() - 4);
+            let value = T::parse(value_tokens)
+                .ok_or_else(|| ParseFcidumpError::InvalidLine(line.clone()))?;
+            let mut index = [0; 4];
+            for (i, token) in index.iter_mut().zip(index_tokens) {
+                *i = token
+                    .parse::<usize>()
+                    .map_err(|_| ParseFcidumpError::InvalidLine(line.clone()))?;
+            }
+            if index.iter().any(|&i| i > norb) {
+                return Err(ParseFcidumpError::IndexOutOfRange(line));
+            }
+            let [i, j, k, l] = index;
+            match (i, j, k, l) {
+                (0, 0, 0, 0) if header.uhf && section < 5 => section += 1,
+                (0, 0, 0, 0) => core_energy = value,
+                (_, 0, 0, 0) => (),
+                (_, _, 0, 0) => {
+                    let h = &mut one_electron[section.saturating_sub(3).min(nspin - 1)];
+                    h[(i - 1, j - 1)] = value;
+                    h[(j - 1, i - 1)] = value.conjugate();
+                }
+                _ if i == 0 || j == 0 || k == 0 || l == 0 => {
+                    return Err(ParseFcidumpError::InvalidLine(line))
+                }
+                _ => packed[section.min(nblocks - 1)].push(([i - 1, j - 1, k - 1, l - 1], value)),
+            }
+        }
+        let two_electron = packed
+            .into_iter()
+            .enumerate()
+            .map(|(block, integrals)| {
+                let integrals = TwoElectronIntegrals {
+                    pair_symmetric: block != 2,
+                    storage: IntegralStorage::Packed(integrals),
+                };
+                if unfold {
+                    TwoElectronIntegrals {
+                        storage: IntegralStorage::Unfolded(integrals.unfolded(norb)),
+                        ..integrals
+                    }
+                } else {
+                    integrals
+                }
+            })
+            .collect();
+        Ok(Self {
+            header,
+            core_energy,
+            one_electron,
+            two_electron,
+        })
+    }
+
+    /// Writes the FCIDUMP file. Of unfolded two-electron integrals only one of each set of
+    /// symmetry equivalent elements is written.
+    /// # Arguments
+    ///  * `dest` - Where to write to.
+    ///  * `threshold` - Integrals with a smaller magnitude are dropped.
+    pub fn write<W: Write>(&self, dest: &mut W, threshold: f64) -> Result<(), ParseFcidumpError> {
+        let norb = self.header.norb;
+        let write_line = |dest: &mut W, value: T, index: [usize; 4]| -> std::io::Result<()> {
+            writeln!(
+                dest,
+                "{} {:4} {:4} {:4} {:4}",
+                value.format(),
+                index[0],
+                index[1],
+                index[2],
+                index[3]
+            )
+        };
+        self.header.write(dest)?;
+        let mut sections = 0;
+        for block in self.two_electron.iter() {
+            for (index, value) in block.unique(norb) {
+                if value.magnitude() >= threshold {
+                    write_line(dest, value, index.map(|i| i + 1))?;
+                }
+            }
+            if self.header.uhf {
+                write_line(dest, T::zero(), [0; 4])?;
+                sections += 1;
+            }
+        }
+        for h in self.one_electron.iter() {
+            for i in 0..norb {
+                for j in 0..=i {
+                    if h[(i, j)].magnitude() >= threshold {
+                        write_line(dest, h[(i, j)], [i + 1, j + 1, 0, 0])?;
+                    }
+                }
+            }
+            if self.header.uhf && sections < 5 {
+                write_line(dest, T::zero(), [0; 4])?;
+                sections += 1;
+            }
+        }
+        write_line(dest, self.core_energy, [0; 4])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symmetry_partners() {
+        assert_eq!(symmetry_partners([0, 1, 2, 3], true, true).len(), 8);
+        let complex = symmetry_partners([0, 1, 2, 3], false, true);
+        assert_eq!(complex.len(), 4);
+        assert!(complex.contains(&([1, 0, 3, 2], true)));
+        assert!(!symmetry_partners([0, 1, 2, 3], true, false).contains(&([2, 3, 0, 1], false)));
+    }
+
+    #[test]
+    fn test_header_with_spaces() {
+        let text = " &FCI NORB = 3, NELEC= 2, MS2 =0,\n  ORBSYM = 1, 1, 2,\n  ISYM = 1\n /\n";
+        let header = FcidumpHeader::parse(&mut text.as_bytes().lines()).unwrap();
+        assert_eq!(header.norb, 3);
+        assert_eq!(header.nelec, 2);
+        assert_eq!(header.ms2, 0);
+        assert_eq!(header.orbsym, vec![1, 1, 2]);
+        assert_eq!(header.isym, 1);
+        assert!(header.extra.is_empty());
+    }
+
+    #[test]
+    fn test_uhf_sections() {
+        let text = "&FCI NORB=1,NELEC=1,MS2=1,ORBSYM=1,ISYM=1,IUHF=1 /\n\
+            0.5 1 1 1 1\n0.0 0 0 0 0\n0.6 1 1 1 1\n0.0 0 0 0 0\n0.7 1 1 1 1\n0.0 0 0 0 0\n\
+            -1.0 1 1 0 0\n0.0 0 0 0 0\n-1.1 1 1 0 0\n0.0 0 0 0 0\n0.25 0 0 0 0\n";
+        let fcidump: Fcidump<f64> = Fcidump::new(&mut text.as_bytes(), false).unwrap();
+        assert!(fcidump.header.uhf);
+        assert_eq!(fcidump.two_electron[2].get([0, 0, 0, 0]), 0.7);
+        assert!(!fcidump.two_electron[2].pair_symmetric);
+        assert_eq!(fcidump.one_electron[1][(0, 0)], -1.1);
+        assert_eq!(fcidump.core_energy, 0.25);
+        let mut written: Vec<u8> = Vec::new();
+        fcidump.write(&mut written, 0.0).unwrap();
+        let reread: Fcidump<f64> = Fcidump::new(&mut written.as_slice(), false).unwrap();
+        assert_eq!(reread, fcidump);
+    }
+
+    #[test]
+    fn test_complex_integrals() {
+        let text = " &FCI NORB=2,NELEC=2,\n &END\n (0.5,0.1) 1 2 1 1\n (-1.0,0.0) 1 1 0 0\n \
+            (0.0,0.2) 2 1 0 0\n (1.5,0.0) 0 0 0 0\n";
+        let fcidump: Fcidump<Complex<f64>> = Fcidump::new(&mut text.as_bytes(), true).unwrap();
+        let eri = &fcidump.two_electron[0];
+        assert_eq!(eri.get([1, 0, 0, 0]), Complex::new(0.5, -0.1));
+        assert_eq!(eri.get([0, 0, 0, 1]), Complex::new(0.5, 0.1));
+        assert_eq!(fcidump.one_electron[0][(0, 1)], Complex::new(0.0, -0.2));
+        assert_eq!(fcidump.core_energy, Complex::new(1.5, 0.0));
+        let mut written: Vec<u8> = Vec::new();
+        fcidump.write(&mut written, 1e-12).unwrap();
+        let reread: Fcidump<Complex<f64>> = Fcidump::new(&mut written.as_slice(), true).unwrap();
+        assert_eq!(reread, fcidump);
+    }
+}
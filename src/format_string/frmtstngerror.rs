@@ -9,6 +9,8 @@ pub struct ErrorMessage {
 pub enum ParseFortranFormattedError {
     /// Raised when the format is unknown.
     UnknownFormat(ErrorMessage),
+    /// Raised when a field cannot be parsed according to its format.
+    ValueError(ErrorMessage),
 }
 
 impl Display for ParseFortranFormattedError {
@@ -17,10 +19,15 @@ impl Display for ParseFortranFormattedError {
             Self::UnknownFormat(fmtstg) => {
                 write!(f, "Unidentifiable format string {}", fmtstg.msg)
             }
+            Self::ValueError(fmtstg) => {
+                write!(f, "Unparsable field {}", fmtstg.msg)
+            }
         }
     }
 }
 
+impl std::error::Error for ParseFortranFormattedError {}
+
 impl From<ParseIntError> for ParseFortranFormattedError {
     fn from(value: ParseIntError) -> Self {
         Self::UnknownFormat(ErrorMessage {
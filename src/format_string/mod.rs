@@ -6,7 +6,6 @@
 //! !c 10 floats with field length 8 three of them decimal places.
 //!     (3I5,1x,10F8.3)
 //! ```
-use core::panic;
 use num::Num;
 use std::{io::BufRead, str::FromStr};
 
@@ -118,6 +117,50 @@ where
     T::from_str
 }
 
+/// Function to parse a real number which may carry a Fortran double precision exponent, e.g.
+/// `0.1D+01`.
+/// # Arguments
+///  * `slice` - The string slice to parse.
+pub fn parse_fortran_real<T>(slice: &str) -> Result<T, <T as FromStr>::Err>
+where
+    T: FromStr,
+{
+    match slice.parse::<T>() {
+        Ok(parsed) => Ok(parsed),
+        Err(_) if slice.contains(['d', 'D']) => slice.replace(['d', 'D'], "e").parse::<T>(),
+        Err(e) => Err(e),
+    }
+}
+
+/// Function to format a real number in Fortran exponential notation with a two digit exponent,
+/// e.g. ` 0.12345E-02` for `E11.5` or `0.1000D+01` with `D` as exponent character.
+/// # Arguments
+///  * `value` - The number to format.
+///  * `width` - Minimum field width, the number is right aligned.
+///  * `precision` - Number of digits of the mantissa.
+///  * `exponent` - The exponent character, `E` or `D`.
+pub fn format_fortran_real(value: f64, width: usize, precision: usize, exponent: char) -> String {
+    let formatted = format!("{:.*e}", precision.saturating_sub(1), value);
+    let (mantissa, power) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+    let power = power.parse::<i32>().unwrap_or(0);
+    // Shift the mantissa from d.ddd to 0.dddd as Fortran does.
+    let (sign, digits) = match mantissa.strip_prefix('-') {
+        Some(d) => ("-", d),
+        None => ("", mantissa),
+    };
+    let digits: String = digits.chars().filter(|c| *c != '.').collect();
+    let power = if value == 0.0 { 0 } else { power + 1 };
+    let field = format!(
+        "{}0.{}{}{}{:02}",
+        sign,
+        digits,
+        exponent,
+        if power < 0 { '-' } else { '+' },
+        power.abs()
+    );
+    format!("{:>w$}", field, w = width)
+}
+
 /// Enum for the possible outcomes of parsing.
 #[derive(Debug, PartialEq)]
 pub enum ParsedValue {
@@ -127,6 +170,93 @@ pub enum ParsedValue {
     St(String),
 }
 
+/// Function to parse a single slice according to the format descriptor.
+fn parse_formatted_slice(
+    slice: &str,
+    kind: &str,
+) -> Result<ParsedValue, ParseFortranFormattedError> {
+    let value_error = || {
+        ParseFortranFormattedError::ValueError(frmtstngerror::ErrorMessage {
+            msg: format!("Cannot parse '{}' with descriptor '{}'.", slice, kind),
+        })
+    };
+    match kind {
+        "f" | "e" | "d" | "g" => Ok(ParsedValue::Fl(
+            parse_fortran_real::<f64>(slice).map_err(|_| value_error())?,
+        )),
+        "i" => Ok(ParsedValue::In(
+            slice.parse::<i32>().map_err(|_| value_error())?,
+        )),
+        "a" => Ok(ParsedValue::St(slice.to_string())),
+        "l" => match slice.trim_start_matches('.').chars().next() {
+            Some('T') | Some('t') => Ok(ParsedValue::Lo(true)),
+            Some('F') | Some('f') => Ok(ParsedValue::Lo(false)),
+            _ => Err(value_error()),
+        },
+        _ => Err(ParseFortranFormattedError::UnknownFormat(
+            frmtstngerror::ErrorMessage {
+                msg: format!("I do not know how to parse {}!!", kind),
+            },
+        )),
+    }
+}
+
+/// Function to parse a single line according to the given Fortran formats. A line, which ends
+/// before all formats are used up, is parsed up to its end. This happens e.g. for the last line
+/// of a block of values.
+/// # Arguments
+///  * `line` - The line to parse.
+///  * `ff` - The formats of the fields in the line.
+pub fn parse_fortran_formatted_line(
+    line: &str,
+    ff: &[FortranFormat],
+) -> Result<Vec<Vec<ParsedValue>>, ParseFortranFormattedError> {
+    let mut result_data: Vec<Vec<ParsedValue>> = Vec::with_capacity(ff.len());
+    let mut start: usize = 0;
+    for f in ff.iter() {
+        if f.kind == *"x" {
+            start += f.rep * f.fw;
+            continue;
+        }
+        let mut to_push: Vec<ParsedValue> = Vec::with_capacity(f.rep);
+        for _r in 0..(f.rep) {
+            if start >= line.len() {
+                break;
+            }
+            let end = (start + f.fw).min(line.len());
+            let slice = line
+                .get(start..end)
+                .ok_or_else(|| {
+                    ParseFortranFormattedError::ValueError(frmtstngerror::ErrorMessage {
+                        msg: format!("Cannot slice '{}' at {}..{}.", line, start, end),
+                    })
+                })?
+                .trim();
+            to_push.push(parse_formatted_slice(slice, &f.kind)?);
+            start = end;
+        }
+        if !to_push.is_empty() {
+            result_data.push(to_push);
+        }
+    }
+    Ok(result_data)
+}
+
+/// Function to parse a single line like `parse_fortran_formatted_line`, but returns the values
+/// of all formats in a single vector, e.g. to access the fields of a record by their position.
+/// # Arguments
+///  * `line` - The line to parse.
+///  * `ff` - The formats of the fields in the line.
+pub fn parse_fortran_formatted_values(
+    line: &str,
+    ff: &[FortranFormat],
+) -> Result<Vec<ParsedValue>, ParseFortranFormattedError> {
+    Ok(parse_fortran_formatted_line(line, ff)?
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
 /// Function to parse a file with data formatted according to a given Fortran format string.
 /// # Arguments
 ///  * `f_ff` - File to process.
@@ -138,28 +268,7 @@ pub fn parse_fortran_formatted_buf<I: BufRead>(
     let ff: Vec<FortranFormat> = get_formats(line_buffer.next().unwrap())?;
     let mut result_data: Vec<Vec<ParsedValue>> = Vec::new();
     for l in line_buffer {
-        let mut start: usize = 0;
-        for f in ff.iter() {
-            let slice_len = f.fw;
-            let mut to_push: Vec<ParsedValue> = Vec::with_capacity(f.rep);
-            // TODO Refactor!!!
-            for _r in 0..(f.rep) {
-                let parsed: ParsedValue;
-                let slice: &str = l[start..start + slice_len].trim();
-                if f.kind == *"f" {
-                    parsed = ParsedValue::Fl(slice.parse::<f64>().unwrap());
-                } else if f.kind == *"i" {
-                    parsed = ParsedValue::In(slice.parse::<i32>().unwrap());
-                } else if f.kind == *"a" {
-                    parsed = ParsedValue::St(slice.to_string());
-                } else {
-                    panic!("I do not know how to parse {}!!", f.kind);
-                }
-                to_push.push(parsed);
-                start += slice_len;
-            }
-            result_data.push(to_push);
-        }
+        result_data.extend(parse_fortran_formatted_line(&l, &ff)?);
     }
     Ok(result_data)
 }
@@ -171,7 +280,11 @@ mod unit_test {
 
     use crate::format_string::{FortranFormat, _parse_numeric_slice};
 
-    use super::{get_formats, parse_fortran_formatted_buf, ParsedValue};
+    use super::{
+        format_fortran_real, get_formats, parse_fortran_formatted_buf,
+        parse_fortran_formatted_line, parse_fortran_formatted_values, parse_fortran_real,
+        ParsedValue,
+    };
 
     #[test]
     fn test_from_str() {
@@ -251,6 +364,58 @@ mod unit_test {
         );
     }
 
+    #[test]
+    fn test_parse_fortran_real() {
+        assert_eq!(parse_fortran_real::<f64>("0.1D+01").unwrap(), 1.0);
+        assert_eq!(parse_fortran_real::<f64>("-2.5d-1").unwrap(), -0.25);
+        assert_eq!(parse_fortran_real::<f64>("3.0E2").unwrap(), 300.0);
+        assert!(parse_fortran_real::<f64>("D").is_err());
+    }
+
+    #[test]
+    fn test_format_fortran_real() {
+        assert_eq!(format_fortran_real(1.0, 12, 5, 'E'), " 0.10000E+01");
+        assert_eq!(format_fortran_real(-0.00123456, 11, 4, 'D'), "-0.1235D-02");
+        assert_eq!(format_fortran_real(0.0, 0, 3, 'E'), "0.000E+00");
+        assert_eq!(
+            parse_fortran_real::<f64>(&format_fortran_real(6.02214076e23, 0, 9, 'D')).unwrap(),
+            6.02214076e23
+        );
+    }
+
+    #[test]
+    fn test_parse_fortran_line() {
+        let ff = get_formats("(a2,1x,2e16.8,l2)".to_string()).unwrap();
+        let parsed =
+            parse_fortran_formatted_line("C   1.00000000E+00 -2.50000000D-01 T", &ff).unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                vec![ParsedValue::St("C".to_string())],
+                vec![ParsedValue::Fl(1.0), ParsedValue::Fl(-0.25)],
+                vec![ParsedValue::Lo(true)],
+            ]
+        );
+        // The last line of a block may hold fewer values than the format.
+        let ff = get_formats("(6i12)".to_string()).unwrap();
+        let parsed = parse_fortran_formatted_line("           1          18", &ff).unwrap();
+        assert_eq!(parsed, vec![vec![ParsedValue::In(1), ParsedValue::In(18)]]);
+    }
+
+    #[test]
+    fn test_parse_fortran_values() {
+        let ff = get_formats("(a6,2i3)".to_string()).unwrap();
+        let parsed = parse_fortran_formatted_values("CONECT  1  2", &ff).unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                ParsedValue::St("CONECT".to_string()),
+                ParsedValue::In(1),
+                ParsedValue::In(2),
+            ]
+        );
+    }
+
     #[test]
     fn test_parse_fortran() {
         let test_file = File::open("tests/test_file.dat").unwrap();
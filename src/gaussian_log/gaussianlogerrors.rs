@@ -0,0 +1,37 @@
+use std::error;
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum ParseGaussianLogError {
+    /// Raised when the underlying reader fails.
+    Io(std::io::Error),
+    /// Raised when a line of a recognized block cannot be parsed.
+    InvalidLine(String),
+    /// Raised when a job ended with `Error termination`.
+    ErrorTermination(String),
+}
+
+impl Display for ParseGaussianLogError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Could not read the Gaussian log file: {}", e),
+            Self::InvalidLine(line) => write!(f, "Could not parse the line '{}'.", line),
+            Self::ErrorTermination(line) => write!(f, "The job failed: '{}'", line),
+        }
+    }
+}
+
+impl error::Error for ParseGaussianLogError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseGaussianLogError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
@@ -0,0 +1,427 @@
+//! This module provides a parser for Gaussian output files (`.log` or `.out`). The file is
+//! scanned for the tables and summaries printed by the links, e.g.
+//! ```text
+//!                          Standard orientation:
+//!  ---------------------------------------------------------------------
+//!  Center     Atomic      Atomic             Coordinates (Angstroms)
+//!  Number     Number       Type             X           Y           Z
+//!  ---------------------------------------------------------------------
+//!       1          8           0        0.000000    0.000000    0.119748
+//!  ---------------------------------------------------------------------
+//!  SCF Done:  E(RB3LYP) =  -76.4089533056     A.U. after   10 cycles
+//! ```
+//! Every step of a `--Link1--` job starts with `Entering Link 1` and is parsed into a job of its
+//! own.
+use nalgebra::{DMatrix, Point3};
+use std::io::BufRead;
+
+use crate::format_string::parse_fortran_real;
+use crate::xyz::xyzline::numeric::XYZLineNumeric;
+use crate::xyz::{Xyz, XyzLine};
+
+use self::gaussianlogerrors::ParseGaussianLogError;
+pub mod gaussianlogerrors;
+
+/// The line printed at the start of every step of a job.
+const LINK1: &str = "Entering Link 1 =";
+
+/// The orientation of a geometry table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    /// The `Input orientation` table.
+    Input,
+    /// The `Standard orientation` table.
+    Standard,
+}
+
+/// A geometry table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaussianFrame {
+    /// Input or standard orientation.
+    pub orientation: Orientation,
+    /// Atomic numbers and positions in angstrom.
+    pub atoms: Vec<XYZLineNumeric<f64>>,
+}
+
+/// A line of the convergence table of an optimization step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvergenceItem {
+    /// The criterion, e.g. `Maximum Force`.
+    pub name: String,
+    pub value: f64,
+    pub threshold: f64,
+    pub converged: bool,
+}
+
+/// The thermochemistry summary of a frequency calculation, energies in hartree.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Thermochemistry {
+    /// Temperature in kelvin.
+    pub temperature: f64,
+    /// Pressure in atm.
+    pub pressure: f64,
+    pub zero_point_correction: f64,
+    pub energy_correction: f64,
+    pub enthalpy_correction: f64,
+    pub free_energy_correction: f64,
+    /// Sum of electronic and zero-point energies.
+    pub zero_point_energy: f64,
+    /// Sum of electronic and thermal energies.
+    pub energy: f64,
+    /// Sum of electronic and thermal enthalpies.
+    pub enthalpy: f64,
+    /// Sum of electronic and thermal free energies.
+    pub free_energy: f64,
+}
+
+/// The results of a single step of a Gaussian job.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GaussianJob {
+    /// The route section, e.g. `#P B3LYP/6-31G(d) Opt Freq`.
+    pub route: String,
+    /// All orientation tables in the order they were printed.
+    pub frames: Vec<GaussianFrame>,
+    /// All converged SCF energies in hartree.
+    pub scf_energies: Vec<f64>,
+    /// The convergence table of each optimization step.
+    pub convergence: Vec<Vec<ConvergenceItem>>,
+    /// All printed forces in hartree/bohr with one row per atom.
+    pub forces: Vec<DMatrix<f64>>,
+    /// Harmonic frequencies in cm^-1, imaginary ones as negative numbers.
+    pub frequencies: Vec<f64>,
+    /// IR intensities in km/mol.
+    pub ir_intensities: Vec<f64>,
+    /// The cartesian displacements of each normal mode with one row per atom.
+    pub normal_modes: Vec<DMatrix<f64>>,
+    /// The thermochemistry summary of a frequency calculation.
+    pub thermochemistry: Option<Thermochemistry>,
+    /// Natural charges of the NBO population analysis.
+    pub nbo_charges: Vec<f64>,
+    /// The archive entry with the line breaks removed.
+    pub archive: Option<String>,
+}
+
+/// Represents a Gaussian log file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaussianLog {
+    /// One entry per step of a `--Link1--` job.
+    pub jobs: Vec<GaussianJob>,
+}
+
+/// Returns the rows of the table following `start`, i.e. the lines after the `ndashes`-th dashed
+/// line up to the next dashed or blank line.
+fn table_rows(lines: &[String], start: usize, ndashes: usize) -> &[String] {
+    let is_dashes = |l: &String| l.trim().starts_with("---");
+    let Some(dashes) = lines
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(8)
+        .filter(|(_, l)| is_dashes(l))
+        .nth(ndashes - 1)
+        .map(|(i, _)| i)
+    else {
+        return &[];
+    };
+    let first = dashes + 1;
+    let count = lines[first..]
+        .iter()
+        .position(|l| is_dashes(l) || l.trim().is_empty())
+        .unwrap_or(lines.len() - first);
+    &lines[first..first + count]
+}
+
+/// Parses tokens as reals.
+fn parse_reals(tokens: &[&str], line: &str) -> Result<Vec<f64>, ParseGaussianLogError> {
+    tokens
+        .iter()
+        .map(|t| parse_fortran_real::<f64>(t))
+        .collect::<Result<Vec<f64>, _>>()
+        .map_err(|_| ParseGaussianLogError::InvalidLine(line.to_string()))
+}
+
+/// Parses the last three tokens of each row into a matrix with one row per atom.
+fn parse_vector_rows(rows: &[String]) -> Result<DMatrix<f64>, ParseGaussianLogError> {
+    let mut values = Vec::with_capacity(3 * rows.len());
+    for row in rows {
+        let tokens: Vec<&str> = row.split_whitespace().collect();
+        if tokens.len() < 3 {
+            return Err(ParseGaussianLogError::InvalidLine(row.clone()));
+        }
+        values.extend(parse_reals(&tokens[tokens.len() - 3..], row)?);
+    }
+    Ok(DMatrix::from_row_slice(rows.len(), 3, &values))
+}
+
+/// Parses the values after the `--` of a frequency block line.
+fn values_after_dashes(line: &str) -> Result<Vec<f64>, ParseGaussianLogError> {
+    let (_, values) = line
+        .split_once("--")
+        .ok_or_else(|| ParseGaussianLogError::InvalidLine(line.to_string()))?;
+    let tokens: Vec<&str> = values.split_whitespace().collect();
+    parse_reals(&tokens, line)
+}
+
+/// Parses the first real after `=` of a thermochemistry line.
+fn value_after_equals(line: &str) -> Result<f64, ParseGaussianLogError> {
+    line.split_once('=')
+        .and_then(|(_, v)| v.split_whitespace().next())
+        .and_then(|t| parse_fortran_real::<f64>(t).ok())
+        .ok_or_else(|| ParseGaussianLogError::InvalidLine(line.to_string()))
+}
+
+impl GaussianJob {
+    /// Returns the geometries of one orientation as trajectory.
+    /// # Arguments
+    ///  * `orientation` - Input or standard orientation.
+    pub fn trajectory(&self, orientation: Orientation) -> Vec<Xyz<f64>> {
+        self.frames
+            .iter()
+            .filter(|f| f.orientation == orientation)
+            .map(|f| Xyz {
+                number_of_atoms: f.atoms.len(),
+                distances_in: "ang".to_string(),
+                info_line: self.route.clone(),
+                lines: f
+                    .atoms
+                    .iter()
+                    .map(|a| XyzLine::Numeric(a.clone()))
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Returns a value of the archive entry, e.g. `HF` or `Version`.
+    /// # Arguments
+    ///  * `key` - The key in front of the `=`.
+    pub fn archive_value(&self, key: &str) -> Option<&str> {
+        self.archive.as_ref()?.split('\\').find_map(|field| {
+            field
+                .split_once('=')
+                .filter(|(k, _)| *k == key)
+                .map(|(_, v)| v)
+        })
+    }
+}
+
+impl GaussianLog {
+    /// Constructor for a GaussianLog struct.
+    /// # Arguments:
+    ///  * `file_handle` - A handle to a `BufReader`, i.e. the log file.
+    ///
+    ///  # Example:
+    ///
+    /// ```no_run
+    ///  use std::fs::File;
+    ///  use std::io::BufReader;
+    ///  use qc_file_parsers::gaussian_log::GaussianLog;
+    ///  fn main() -> std::io::Result<()> {
+    ///     let f = File::open("water.log")?;
+    ///     let mut b = BufReader::new(f);
+    ///     let log = GaussianLog::new(&mut b).unwrap();
+    ///     let energy = log.jobs[0].scf_energies.last();
+    ///     Ok(())
+    ///  }
+    ///  ```
+    pub fn new<I: BufRead>(file_handle: &mut I) -> Result<Self, ParseGaussianLogError> {
+        let lines = file_handle.lines().collect::<Result<Vec<String>, _>>()?;
+        let mut jobs: Vec<GaussianJob> = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.starts_with("Error termination") {
+                return Err(ParseGaussianLogError::ErrorTermination(trimmed.to_string()));
+            }
+            // Lines in front of the first step belong to it.
+            if jobs.is_empty()
+                || (trimmed.starts_with(LINK1) && jobs.last() != Some(&GaussianJob::default()))
+            {
+                jobs.push(GaussianJob::default());
+            }
+            let job = jobs.last_mut().expect("A job is pushed above.");
+            let tokens: Vec<&str> = trimmed.split_whitespace().collect();
+            if trimmed.starts_with('#') && job.route.is_empty() {
+                // The route may be wrapped over several lines up to the next dashed line, which
+                // start with a single blank like the archive entry.
+                job.route = lines[i..]
+                    .iter()
+                    .take_while(|l| !l.trim().starts_with("---"))
+                    .map(|l| l.strip_prefix(' ').unwrap_or(l))
+                    .collect::<String>()
+                    .trim()
+                    .to_string();
+            } else if trimmed == "Standard orientation:" || trimmed == "Input orientation:" {
+                let orientation = if trimmed.starts_with("Standard") {
+                    Orientation::Standard
+                } else {
+                    Orientation::Input
+                };
+                let mut atoms = Vec::new();
+                for row in table_rows(&lines, i + 1, 2) {
+                    let tokens: Vec<&str> = row.split_whitespace().collect();
+                    let z_value = tokens.get(1).and_then(|t| t.parse::<usize>().ok());
+                    let (Some(z_value), 6) = (z_value, tokens.len()) else {
+                        return Err(ParseGaussianLogError::InvalidLine(row.clone()));
+                    };
+                    let xyz = parse_reals(&tokens[3..6], row)?;
+                    atoms.push(XYZLineNumeric {
+                        z_value,
+                        xyz: Point3::new(xyz[0], xyz[1], xyz[2]),
+                    });
+                }
+                job.frames.push(GaussianFrame { orientation, atoms });
+            } else if trimmed.starts_with("SCF Done:") {
+                job.scf_energies.push(value_after_equals(trimmed)?);
+            } else if tokens.starts_with(&["Item", "Value", "Threshold", "Converged?"]) {
+                let mut items = Vec::new();
+                for row in lines[i + 1..].iter() {
+                    let tokens: Vec<&str> = row.split_whitespace().collect();
+                    let [name @ .., value, threshold, converged] = tokens.as_slice() else {
+                        break;
+                    };
+                    if *converged != "YES" && *converged != "NO" {
+                        break;
+                    }
+                    items.push(ConvergenceItem {
+                        name: name.join(" "),
+                        value: parse_reals(&[value], row)?[0],
+                        threshold: parse_reals(&[threshold], row)?[0],
+                        converged: *converged == "YES",
+                    });
+                }
+                job.convergence.push(items);
+            } else if trimmed.contains("Forces (Hartrees/Bohr)") {
+                job.forces
+                    .push(parse_vector_rows(table_rows(&lines, i + 1, 1))?);
+            } else if trimmed.starts_with("Harmonic frequencies (cm**-1)") {
+                job.frequencies.clear();
+                job.ir_intensities.clear();
+                job.normal_modes.clear();
+            } else if tokens.get(1) == Some(&"--") && tokens[0] == "Frequencies" {
+                job.frequencies.extend(values_after_dashes(trimmed)?);
+            } else if trimmed.starts_with("IR Inten") && tokens.contains(&"--") {
+                job.ir_intensities.extend(values_after_dashes(trimmed)?);
+            } else if tokens.starts_with(&["Atom", "AN", "X", "Y", "Z"]) {
+                let nmodes = (tokens.len() - 2) / 3;
+                let mut modes: Vec<Vec<f64>> = vec![Vec::new(); nmodes];
+                let mut natoms = 0;
+                for row in lines[i + 1..].iter() {
+                    let tokens: Vec<&str> = row.split_whitespace().collect();
+                    if tokens.len() != 2 + 3 * nmodes || tokens[0].parse::<usize>().is_err() {
+                        break;
+                    }
+                    let values = parse_reals(&tokens[2..], row)?;
+                    for (mode, displacement) in modes.iter_mut().zip(values.chunks(3)) {
+                        mode.extend_from_slice(displacement);
+                    }
+                    natoms += 1;
+                }
+                job.normal_modes
+                    .extend(modes.iter().map(|m| DMatrix::from_row_slice(natoms, 3, m)));
+            } else if trimmed.starts_with("Temperature") && trimmed.contains("Pressure") {
+                let value_after = |label: &str| {
+                    tokens
+                        .iter()
+                        .position(|t| *t == label)
+                        .and_then(|p| tokens.get(p + 1))
+                        .and_then(|t| parse_fortran_real::<f64>(t).ok())
+                        .ok_or_else(|| ParseGaussianLogError::InvalidLine(trimmed.to_string()))
+                };
+                let temperature = value_after("Temperature")?;
+                let pressure = value_after("Pressure")?;
+                let thermo = job.thermochemistry.get_or_insert_with(Default::default);
+                thermo.temperature = temperature;
+                thermo.pressure = pressure;
+            } else if let Some(thermo) = job.thermochemistry.as_mut() {
+                let field = match trimmed.split('=').next().unwrap_or_default() {
+                    "Zero-point correction" => Some(&mut thermo.zero_point_correction),
+                    "Thermal correction to Energy" => Some(&mut thermo.energy_correction),
+                    "Thermal correction to Enthalpy" => Some(&mut thermo.enthalpy_correction),
+                    "Thermal correction to Gibbs Free Energy" => {
+                        Some(&mut thermo.free_energy_correction)
+                    }
+                    "Sum of electronic and zero-point Energies" => {
+                        Some(&mut thermo.zero_point_energy)
+                    }
+                    "Sum of electronic and thermal Energies" => Some(&mut thermo.energy),
+                    "Sum of electronic and thermal Enthalpies" => Some(&mut thermo.enthalpy),
+                    "Sum of electronic and thermal Free Energies" => Some(&mut thermo.free_energy),
+                    _ => None,
+                };
+                if let Some(field) = field {
+                    *field = value_after_equals(trimmed)?;
+                }
+            }
+            if trimmed == "Summary of Natural Population Analysis:" {
+                let rows = lines[i + 1..]
+                    .iter()
+                    .skip_while(|l| !l.trim().starts_with("---"))
+                    .skip(1)
+                    .take_while(|l| !l.trim().starts_with("==="));
+                job.nbo_charges = rows
+                    .map(|row| {
+                        let tokens: Vec<&str> = row.split_whitespace().collect();
+                        tokens
+                            .get(2)
+                            .and_then(|t| parse_fortran_real::<f64>(t).ok())
+                            .ok_or_else(|| ParseGaussianLogError::InvalidLine(row.clone()))
+                    })
+                    .collect::<Result<Vec<f64>, _>>()?;
+            } else if trimmed.starts_with("1\\1\\") {
+                // Wrapped lines start with a single blank, which is not part of the entry.
+                let mut archive = String::new();
+                for l in lines[i..].iter() {
+                    archive.push_str(l.strip_prefix(' ').unwrap_or(l));
+                    if archive.contains("\\@") {
+                        break;
+                    }
+                }
+                job.archive = Some(archive);
+            }
+        }
+        Ok(Self { jobs })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_error_termination() {
+        let text = " SCF Done:  E(RHF) =  -75.0   A.U. after  129 cycles\n\
+                    \x20Error termination via Lnk1e in /opt/g16/l502.exe at Mon Oct 19 2026.\n";
+        assert!(matches!(
+            GaussianLog::new(&mut Cursor::new(text)),
+            Err(ParseGaussianLogError::ErrorTermination(_))
+        ));
+    }
+
+    #[test]
+    fn test_thermochemistry_line() {
+        let text = " Temperature   298.150 Kelvin.  Pressure   1.00000 Atm.\n";
+        let log = GaussianLog::new(&mut Cursor::new(text)).unwrap();
+        let thermo = log.jobs[0].thermochemistry.as_ref().unwrap();
+        assert_eq!((thermo.temperature, thermo.pressure), (298.15, 1.0));
+        for text in [
+            " Temperature   298.150 Kelvin.  Pressure=   1.00000 Atm.\n",
+            " Temperature   298.150 Kelvin.  Pressure\n",
+            " Temperature  Pressure   1.00000 Atm.\n",
+        ] {
+            assert!(matches!(
+                GaussianLog::new(&mut Cursor::new(text)),
+                Err(ParseGaussianLogError::InvalidLine(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_archive_value() {
+        let job = GaussianJob {
+            archive: Some("1\\1\\GINC\\SP\\\\#P HF\\\\HF=-76.0\\RMSD=1e-9\\@".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(job.archive_value("HF"), Some("-76.0"));
+        assert_eq!(job.archive_value("Dipole"), None);
+    }
+}
@@ -1,7 +1,24 @@
 pub mod array_text;
+pub mod basis;
+pub mod cfour;
+pub mod cif;
+pub mod common;
+pub mod cube;
+pub mod fchk;
+pub mod fcidump;
 pub mod format_string;
+pub mod gaussian_log;
+pub mod mol2;
+pub mod molden;
+pub mod molfile;
+pub mod orca;
+pub mod pdb;
+pub mod psi4;
+pub mod turbomole;
+pub mod vasp;
 pub mod xyz;
 
+#[allow(unused_macros)]
 macro_rules! open_file {
     ($s:expr) => {{
         let file_handle = std::fs::File::open($s.to_string()).unwrap();
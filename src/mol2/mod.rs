@@ -0,0 +1,453 @@
+//! This module provides a reader and a writer for Tripos MOL2 files. A file holds one or more
+//! molecules, each starting with a `MOLECULE` record followed by `ATOM`, `BOND` and
+//! `SUBSTRUCTURE` records:
+//! ```text
+//! @<TRIPOS>MOLECULE
+//! water
+//!     3     2     1     0     0
+//! SMALL
+//! USER_CHARGES
+//!
+//! @<TRIPOS>ATOM
+//!       1 OW           0.0000     0.0000     0.1170 O.3         1 HOH1        -0.8340
+//! ...
+//! @<TRIPOS>BOND
+//!      1     1     2 1
+//! ...
+//! ```
+//! Other records are skipped, as are comment lines starting with `#`.
+use nalgebra::Point3;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+use crate::xyz::xyzline::symbol::XYZLineSymbol;
+use crate::xyz::{Xyz, XyzLine};
+
+use self::mol2errors::ParseMol2Error;
+pub mod mol2errors;
+
+/// Prefix of record type indicators.
+const RECORD_PREFIX: &str = "@<TRIPOS>";
+
+/// The bond types of MOL2 files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mol2BondType {
+    Single,
+    Double,
+    Triple,
+    Amide,
+    Aromatic,
+    Dummy,
+    Unknown,
+    NotConnected,
+}
+
+/// The bond types together with their labels.
+const BOND_TYPES: [(Mol2BondType, &str); 8] = [
+    (Mol2BondType::Single, "1"),
+    (Mol2BondType::Double, "2"),
+    (Mol2BondType::Triple, "3"),
+    (Mol2BondType::Amide, "am"),
+    (Mol2BondType::Aromatic, "ar"),
+    (Mol2BondType::Dummy, "du"),
+    (Mol2BondType::Unknown, "un"),
+    (Mol2BondType::NotConnected, "nc"),
+];
+
+impl Mol2BondType {
+    /// Returns the bond type of a label like `2` or `ar`, ignoring case.
+    fn from_label(label: &str) -> Option<Self> {
+        BOND_TYPES
+            .iter()
+            .find(|(_, l)| l.eq_ignore_ascii_case(label))
+            .map(|(t, _)| *t)
+    }
+
+    /// The label of the bond type in the file.
+    pub fn label(&self) -> &'static str {
+        BOND_TYPES
+            .iter()
+            .find(|(t, _)| t == self)
+            .map(|(_, l)| *l)
+            .expect("Every bond type has a label.")
+    }
+
+    /// The bond order, 1.5 for aromatic bonds and `None` for dummy, unknown and missing bonds.
+    pub fn order(&self) -> Option<f64> {
+        match self {
+            Self::Single | Self::Amide => Some(1.0),
+            Self::Double => Some(2.0),
+            Self::Triple => Some(3.0),
+            Self::Aromatic => Some(1.5),
+            _ => None,
+        }
+    }
+}
+
+/// A line of the `ATOM` record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mol2Atom {
+    pub name: String,
+    /// The position in angstrom.
+    pub xyz: Point3<f64>,
+    /// The SYBYL atom type, e.g. `C.ar`.
+    pub atom_type: String,
+    /// Id of the substructure, 1 if not given.
+    pub subst_id: usize,
+    /// Name of the substructure, `UNL1` if not given.
+    pub subst_name: String,
+    /// The partial charge, 0 if not given.
+    pub charge: f64,
+}
+
+impl Mol2Atom {
+    /// The element symbol, i.e. the SYBYL atom type without its hybridization.
+    pub fn element(&self) -> &str {
+        self.atom_type.split('.').next().unwrap_or_default()
+    }
+}
+
+/// A line of the `BOND` record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mol2Bond {
+    /// Indices of the bonded atoms, starting at 0.
+    pub atoms: (usize, usize),
+    pub bond_type: Mol2BondType,
+}
+
+/// A line of the `SUBSTRUCTURE` record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mol2Substructure {
+    pub id: usize,
+    pub name: String,
+    /// Index of the root atom, starting at 0.
+    pub root_atom: usize,
+    /// The substructure type, e.g. `RESIDUE`, empty if not given.
+    pub kind: String,
+    pub chain: Option<String>,
+    /// The residue type, e.g. `ALA`.
+    pub residue: Option<String>,
+}
+
+/// A molecule of a MOL2 file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mol2Molecule {
+    pub name: String,
+    /// The molecule type, e.g. `SMALL` or `PROTEIN`.
+    pub molecule_type: String,
+    /// The charge type, e.g. `USER_CHARGES` or `NO_CHARGES`.
+    pub charge_type: String,
+    pub comment: String,
+    pub atoms: Vec<Mol2Atom>,
+    pub bonds: Vec<Mol2Bond>,
+    pub substructures: Vec<Mol2Substructure>,
+}
+
+impl Mol2Molecule {
+    /// Function to parse the lines of a `MOLECULE` record.
+    fn from_header(lines: &[String]) -> Result<Self, ParseMol2Error> {
+        if lines.len() < 2 {
+            return Err(ParseMol2Error::UnexpectedEof);
+        }
+        let field = |i: usize| {
+            lines
+                .get(i)
+                .map(|l| l.trim().to_string())
+                .unwrap_or_default()
+        };
+        Ok(Self {
+            name: field(0),
+            molecule_type: field(2),
+            charge_type: field(3),
+            comment: field(5),
+            atoms: Vec::new(),
+            bonds: Vec::new(),
+            substructures: Vec::new(),
+        })
+    }
+
+    /// The partial charges of the atoms.
+    pub fn charges(&self) -> Vec<f64> {
+        self.atoms.iter().map(|a| a.charge).collect()
+    }
+
+    /// Returns the geometry in angstrom with the elements of the atom types. The bonds refer to
+    /// the lines of the geometry by their indices.
+    pub fn geometry(&self) -> Xyz<f64> {
+        Xyz {
+            number_of_atoms: self.atoms.len(),
+            distances_in: "ang".to_string(),
+            info_line: self.name.clone(),
+            lines: self
+                .atoms
+                .iter()
+                .map(|a| {
+                    XyzLine::Symbolic(XYZLineSymbol {
+                        symbol: a.element().to_lowercase(),
+                        xyz: a.xyz,
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// Constructor for a molecule from a geometry and partial charges, e.g. computed by a
+    /// population analysis. The elements serve as atom types and names, all atoms belong to the
+    /// substructure `UNL1`.
+    /// # Arguments:
+    ///  * `geometry` - The geometry, in bohr or angstrom.
+    ///  * `charges` - The partial charges of the atoms, empty if there are none.
+    pub fn from_xyz(geometry: &Xyz<f64>, charges: &[f64]) -> Self {
+        let atoms = geometry
+            .labeled_atoms_in_angstrom()
+            .into_iter()
+            .enumerate()
+            .map(|(i, (element, xyz))| Mol2Atom {
+                name: format!("{}{}", element, i + 1),
+                xyz,
+                atom_type: element,
+                subst_id: 1,
+                subst_name: "UNL1".to_string(),
+                charge: charges.get(i).copied().unwrap_or_default(),
+            })
+            .collect();
+        Self {
+            name: geometry.info_line.trim().to_string(),
+            molecule_type: "SMALL".to_string(),
+            charge_type: if charges.is_empty() {
+                "NO_CHARGES"
+            } else {
+                "USER_CHARGES"
+            }
+            .to_string(),
+            comment: String::new(),
+            atoms,
+            bonds: Vec::new(),
+            substructures: Vec::new(),
+        }
+    }
+
+    /// Writes the molecule.
+    /// # Arguments
+    ///  * `dest` - Where to write to.
+    pub fn write<W: Write>(&self, dest: &mut W) -> Result<(), ParseMol2Error> {
+        writeln!(dest, "{}MOLECULE", RECORD_PREFIX)?;
+        writeln!(dest, "{}", self.name)?;
+        writeln!(
+            dest,
+            "{:5} {:5} {:5}     0     0",
+            self.atoms.len(),
+            self.bonds.len(),
+            self.substructures.len()
+        )?;
+        writeln!(dest, "{}", self.molecule_type)?;
+        writeln!(dest, "{}", self.charge_type)?;
+        if !self.comment.is_empty() {
+            writeln!(dest, "****")?;
+            writeln!(dest, "{}", self.comment)?;
+        }
+        writeln!(dest)?;
+        writeln!(dest, "{}ATOM", RECORD_PREFIX)?;
+        for (i, atom) in self.atoms.iter().enumerate() {
+            writeln!(
+                dest,
+                "{:7} {:<8} {:10.4} {:10.4} {:10.4} {:<8} {:3} {:<8} {:10.4}",
+                i + 1,
+                atom.name,
+                atom.xyz.x,
+                atom.xyz.y,
+                atom.xyz.z,
+                atom.atom_type,
+                atom.subst_id,
+                atom.subst_name,
+                atom.charge
+            )?;
+        }
+        writeln!(dest, "{}BOND", RECORD_PREFIX)?;
+        for (i, bond) in self.bonds.iter().enumerate() {
+            writeln!(
+                dest,
+                "{:6} {:5} {:5} {}",
+                i + 1,
+                bond.atoms.0 + 1,
+                bond.atoms.1 + 1,
+                bond.bond_type.label()
+            )?;
+        }
+        if self.substructures.is_empty() {
+            return Ok(());
+        }
+        writeln!(dest, "{}SUBSTRUCTURE", RECORD_PREFIX)?;
+        for substructure in self.substructures.iter() {
+            write!(
+                dest,
+                "{:6} {:<8} {:5} {}",
+                substructure.id,
+                substructure.name,
+                substructure.root_atom + 1,
+                substructure.kind
+            )?;
+            if substructure.chain.is_some() || substructure.residue.is_some() {
+                write!(
+                    dest,
+                    " 1 {} {}",
+                    substructure.chain.as_deref().unwrap_or("****"),
+                    substructure.residue.as_deref().unwrap_or("****")
+                )?;
+            }
+            writeln!(dest)?;
+        }
+        Ok(())
+    }
+}
+
+/// Function to parse a line of the `ATOM` record.
+fn parse_atom(line: &str) -> Result<(String, Mol2Atom), ParseMol2Error> {
+    let invalid = || ParseMol2Error::InvalidLine(line.to_string());
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    if tokens.len() < 6 {
+        return Err(invalid());
+    }
+    let coordinate = |t: &str| t.parse::<f64>().map_err(|_| invalid());
+    Ok((
+        tokens[0].to_string(),
+        Mol2Atom {
+            name: tokens[1].to_string(),
+            xyz: Point3::new(
+                coordinate(tokens[2])?,
+                coordinate(tokens[3])?,
+                coordinate(tokens[4])?,
+            ),
+            atom_type: tokens[5].to_string(),
+            subst_id: match tokens.get(6) {
+                Some(t) => t.parse::<usize>().map_err(|_| invalid())?,
+                None => 1,
+            },
+            subst_name: tokens.get(7).unwrap_or(&"UNL1").to_string(),
+            charge: match tokens.get(8) {
+                Some(t) => coordinate(t)?,
+                None => 0.0,
+            },
+        },
+    ))
+}
+
+/// Function to parse the molecules of a MOL2 file.
+/// # Arguments
+///  * `source` - mutable reference of the file buffer.
+pub fn parse_mol2<I: BufRead>(source: &mut I) -> Result<Vec<Mol2Molecule>, ParseMol2Error> {
+    let mut molecules: Vec<Mol2Molecule> = Vec::new();
+    // Lines of the `MOLECULE` record being read.
+    let mut header: Option<Vec<String>> = None;
+    let mut record = String::new();
+    // Ids of the atoms of the current molecule.
+    let mut atom_ids: HashMap<String, usize> = HashMap::new();
+    for line in source.lines() {
+        let line = line?;
+        if line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.trim().strip_prefix(RECORD_PREFIX) {
+            if let Some(lines) = header.take() {
+                molecules.push(Mol2Molecule::from_header(&lines)?);
+            }
+            record = name.trim().to_uppercase();
+            if record == "MOLECULE" {
+                header = Some(Vec::new());
+                atom_ids.clear();
+            } else if molecules.is_empty() {
+                return Err(ParseMol2Error::MissingMolecule(record));
+            }
+            continue;
+        }
+        if let Some(lines) = header.as_mut() {
+            lines.push(line);
+            continue;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some(molecule) = molecules.last_mut() else {
+            continue;
+        };
+        let invalid = || ParseMol2Error::InvalidLine(line.to_string());
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match record.as_str() {
+            "ATOM" => {
+                let (id, atom) = parse_atom(&line)?;
+                atom_ids.insert(id, molecule.atoms.len());
+                molecule.atoms.push(atom);
+            }
+            "BOND" => {
+                if tokens.len() < 4 {
+                    return Err(invalid());
+                }
+                let atom = |t: &str| atom_ids.get(t).copied().ok_or_else(invalid);
+                molecule.bonds.push(Mol2Bond {
+                    atoms: (atom(tokens[1])?, atom(tokens[2])?),
+                    bond_type: Mol2BondType::from_label(tokens[3]).ok_or_else(invalid)?,
+                });
+            }
+            "SUBSTRUCTURE" => {
+                if tokens.len() < 3 {
+                    return Err(invalid());
+                }
+                let optional = |i: usize| {
+                    tokens
+                        .get(i)
+                        .filter(|t| **t != "****")
+                        .map(|t| t.to_string())
+                };
+                molecule.substructures.push(Mol2Substructure {
+                    id: tokens[0].parse::<usize>().map_err(|_| invalid())?,
+                    name: tokens[1].to_string(),
+                    root_atom: atom_ids.get(tokens[2]).copied().ok_or_else(invalid)?,
+                    kind: optional(3).unwrap_or_default(),
+                    chain: optional(5),
+                    residue: optional(6),
+                });
+            }
+            _ => (),
+        }
+    }
+    if let Some(lines) = header.take() {
+        molecules.push(Mol2Molecule::from_header(&lines)?);
+    }
+    Ok(molecules)
+}
+
+/// Function to write molecules as MOL2 file.
+/// # Arguments
+///  * `dest` - Where to write to.
+///  * `molecules` - The molecules to write.
+pub fn write_mol2<W: Write>(
+    dest: &mut W,
+    molecules: &[Mol2Molecule],
+) -> Result<(), ParseMol2Error> {
+    for (i, molecule) in molecules.iter().enumerate() {
+        if i > 0 {
+            writeln!(dest)?;
+        }
+        molecule.write(dest)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bond_types_and_elements() {
+        for (bond_type, label) in BOND_TYPES {
+            assert_eq!(Mol2BondType::from_label(label), Some(bond_type));
+            assert_eq!(bond_type.label(), label);
+        }
+        assert_eq!(Mol2BondType::from_label("AR").unwrap().order(), Some(1.5));
+        let (id, atom) = parse_atom("  3 CL3  1.0 -2.5 0.25 Cl").unwrap();
+        assert_eq!(id, "3");
+        assert_eq!(atom.element(), "Cl");
+        assert_eq!(atom.subst_name, "UNL1");
+        assert_eq!(atom.charge, 0.0);
+    }
+}
@@ -0,0 +1,42 @@
+use std::error;
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum ParseMol2Error {
+    /// Raised when the underlying reader or writer fails.
+    Io(std::io::Error),
+    /// Raised when a molecule lacks the name or counts line of its `MOLECULE` record.
+    UnexpectedEof,
+    /// Raised when a line of a record cannot be parsed.
+    InvalidLine(String),
+    /// Raised when records precede the first `MOLECULE` record.
+    MissingMolecule(String),
+}
+
+impl Display for ParseMol2Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Could not read the MOL2 file: {}", e),
+            Self::UnexpectedEof => write!(f, "The MOLECULE record ended prematurely."),
+            Self::InvalidLine(line) => write!(f, "Could not parse the line '{}'.", line),
+            Self::MissingMolecule(record) => {
+                write!(f, "The record '{}' precedes any MOLECULE record.", record)
+            }
+        }
+    }
+}
+
+impl error::Error for ParseMol2Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseMol2Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
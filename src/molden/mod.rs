@@ -0,0 +1,576 @@
+//! This module provides a reader and writer for Molden files, which carry geometry, basis set,
+//! molecular orbitals and normal modes between programs and into visualizers.
+//! ```text
+//! [Molden Format]
+//! [Atoms] AU
+//! O     1    8    0.000000    0.000000    0.221904
+//! [GTO]
+//!   1 0
+//!  s    3 1.00
+//!   0.1307093200D+03  0.1543289700D+00
+//! [MO]
+//!  Sym= A1
+//!  Ene= -20.2516
+//!  Spin= Alpha
+//!  Occup= 2.0
+//!    1  -0.994216
+//! ```
+//! Sections and keys are matched case insensitively, the unit of `[Atoms]` may be given with or
+//! without parentheses and zero MO coefficients may be left out, which covers the files written
+//! by ORCA, Psi4, Molpro and Turbomole.
+use nalgebra::{DMatrix, Point3};
+use std::io::{BufRead, Write};
+
+use crate::common::Spin;
+use crate::format_string::{format_fortran_real, parse_fortran_real};
+use crate::xyz::xyzline::numeric::XYZLineNumeric;
+use crate::xyz::xyzline::symbol::XYZLineSymbol;
+use crate::xyz::{Xyz, XyzLine};
+
+use self::moldenerrors::ParseMoldenError;
+pub mod moldenerrors;
+
+/// Labels of the shells in order of their angular momentum.
+const SHELL_LABELS: &str = "spdfghi";
+
+/// An atom of the `[Atoms]` section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoldenAtom {
+    /// The label of the atom, usually its element symbol.
+    pub label: String,
+    /// Atomic number and position.
+    pub atom: XYZLineNumeric<f64>,
+}
+
+/// A contracted shell of the `[GTO]` section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoldenShell {
+    /// Zero based index of the atom the shell is centered on.
+    pub atom: usize,
+    /// The lower case label of the shell, i.e. `s`, `p`, `sp`, `d`, ...
+    pub kind: String,
+    /// Exponents of the primitives, with the scale factor of the shell applied.
+    pub exponents: Vec<f64>,
+    /// Contraction coefficients of the primitives.
+    pub coefficients: Vec<f64>,
+    /// The p contraction coefficients of sp shells.
+    pub sp_coefficients: Option<Vec<f64>>,
+}
+
+/// Which shells use spherical instead of cartesian functions, set by the `[5D]`, `[5D7F]`,
+/// `[5D10F]`, `[7F]` and `[9G]` flags.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SphericalShells {
+    pub d: bool,
+    pub f: bool,
+    pub g: bool,
+}
+
+/// A molecular orbital of the `[MO]` section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoldenOrbital {
+    /// The symmetry label, e.g. `A1` or `1.1`.
+    pub symmetry: String,
+    /// The orbital energy in hartree.
+    pub energy: f64,
+    /// Alpha or beta orbital.
+    pub spin: Spin,
+    /// The occupation number.
+    pub occupation: f64,
+    /// The coefficients of all basis functions.
+    pub coefficients: Vec<f64>,
+}
+
+/// Represents a Molden file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Molden {
+    /// The content of the `[Title]` section.
+    pub title: String,
+    /// The unit of the atom positions, 'bohr' === `bohr` or 'angstroem' === `ang`.
+    pub distances_in: String,
+    /// The atoms of the molecule.
+    pub atoms: Vec<MoldenAtom>,
+    /// The contracted shells of the basis set.
+    pub shells: Vec<MoldenShell>,
+    /// The kind of functions of d, f and g shells.
+    pub spherical: SphericalShells,
+    /// The molecular orbitals.
+    pub orbitals: Vec<MoldenOrbital>,
+    /// Vibrational frequencies in cm^-1.
+    pub frequencies: Vec<f64>,
+    /// The geometry of the frequency calculation in bohr.
+    pub frequency_coordinates: Vec<XYZLineSymbol<f64>>,
+    /// The cartesian displacements of each normal mode with one row per atom.
+    pub normal_modes: Vec<DMatrix<f64>>,
+}
+
+/// The sections of a Molden file which are parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Title,
+    Atoms,
+    Gto,
+    Mo,
+    Freq,
+    FrCoord,
+    FrNormCoord,
+    Other,
+}
+
+/// Parses all tokens of a line as reals.
+fn parse_reals(line: &str) -> Result<Vec<f64>, ParseMoldenError> {
+    line.split_whitespace()
+        .map(|t| {
+            parse_fortran_real::<f64>(t)
+                .map_err(|_| ParseMoldenError::InvalidLine(line.to_string()))
+        })
+        .collect()
+}
+
+impl MoldenShell {
+    /// The angular momentum of the shell, where sp shells count as p shells.
+    pub fn angular_momentum(&self) -> usize {
+        match self.kind.as_str() {
+            "sp" => 1,
+            kind => SHELL_LABELS.find(kind).unwrap_or_default(),
+        }
+    }
+
+    /// The number of basis functions of the shell.
+    /// # Arguments
+    ///  * `spherical` - Which shells use spherical functions.
+    pub fn nfunctions(&self, spherical: &SphericalShells) -> usize {
+        let l = self.angular_momentum();
+        let is_spherical = match l {
+            0 | 1 => true,
+            2 => spherical.d,
+            3 => spherical.f,
+            4 => spherical.g,
+            _ => true,
+        };
+        match (self.kind.as_str(), is_spherical) {
+            ("sp", _) => 4,
+            (_, true) => 2 * l + 1,
+            (_, false) => (l + 1) * (l + 2) / 2,
+        }
+    }
+}
+
+impl Molden {
+    /// Constructor for a Molden struct.
+    /// # Arguments:
+    ///  * `file_handle` - A handle to a `BufReader`, i.e. the molden file.
+    ///
+    ///  # Example:
+    ///
+    /// ```no_run
+    ///  use std::fs::File;
+    ///  use std::io::BufReader;
+    ///  use qc_file_parsers::common::Spin;
+    ///  use qc_file_parsers::molden::Molden;
+    ///  fn main() -> std::io::Result<()> {
+    ///     let f = File::open("orca.molden.input")?;
+    ///     let mut b = BufReader::new(f);
+    ///     let molden = Molden::new(&mut b).unwrap();
+    ///     let coefficients = molden.mo_coefficients(Spin::Alpha);
+    ///     Ok(())
+    ///  }
+    ///  ```
+    pub fn new<I: BufRead>(file_handle: &mut I) -> Result<Self, ParseMoldenError> {
+        let mut molden = Self {
+            title: String::new(),
+            distances_in: "bohr".to_string(),
+            atoms: Vec::new(),
+            shells: Vec::new(),
+            spherical: SphericalShells::default(),
+            orbitals: Vec::new(),
+            frequencies: Vec::new(),
+            frequency_coordinates: Vec::new(),
+            normal_modes: Vec::new(),
+        };
+        let mut section = Section::Other;
+        let mut has_atoms = false;
+        let mut shell_atom = 0;
+        let mut scale = 1.0;
+        // Coefficients are collected by index, as zeros may be left out.
+        let mut sparse_coefficients: Vec<Vec<(usize, f64)>> = Vec::new();
+        let mut modes: Vec<Vec<f64>> = Vec::new();
+        for line in file_handle.lines() {
+            let line = line?;
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            let invalid = || ParseMoldenError::InvalidLine(line.clone());
+            if let Some(header) = trimmed.strip_prefix('[') {
+                let (name, flags) = header.split_once(']').ok_or_else(invalid)?;
+                let flags = flags.trim().to_uppercase();
+                section = match name.trim().to_uppercase().as_str() {
+                    "TITLE" => Section::Title,
+                    "ATOMS" => {
+                        has_atoms = true;
+                        if flags.contains("ANG") {
+                            molden.distances_in = "ang".to_string();
+                        }
+                        Section::Atoms
+                    }
+                    "GTO" => Section::Gto,
+                    "MO" => Section::Mo,
+                    "FREQ" => Section::Freq,
+                    "FR-COORD" => Section::FrCoord,
+                    "FR-NORM-COORD" => Section::FrNormCoord,
+                    "5D" | "5D7F" => {
+                        molden.spherical.d = true;
+                        molden.spherical.f = true;
+                        Section::Other
+                    }
+                    "5D10F" => {
+                        molden.spherical.d = true;
+                        Section::Other
+                    }
+                    "7F" => {
+                        molden.spherical.f = true;
+                        Section::Other
+                    }
+                    "9G" => {
+                        molden.spherical.g = true;
+                        Section::Other
+                    }
+                    _ => Section::Other,
+                };
+                continue;
+            }
+            let tokens: Vec<&str> = trimmed.split_whitespace().collect();
+            match section {
+                Section::Title => {
+                    if !molden.title.is_empty() {
+                        molden.title.push('\n');
+                    }
+                    molden.title.push_str(trimmed);
+                }
+                Section::Atoms => {
+                    if tokens.len() < 6 {
+                        return Err(invalid());
+                    }
+                    // Some programs write the atomic number as real.
+                    let z_value = parse_fortran_real::<f64>(tokens[2]).map_err(|_| invalid())?;
+                    let xyz = parse_reals(&tokens[3..6].join(" "))?;
+                    molden.atoms.push(MoldenAtom {
+                        label: tokens[0].to_string(),
+                        atom: XYZLineNumeric {
+                            z_value: z_value.round() as usize,
+                            xyz: Point3::new(xyz[0], xyz[1], xyz[2]),
+                        },
+                    });
+                }
+                Section::Gto => {
+                    if let Ok(atom) = tokens[0].parse::<usize>() {
+                        shell_atom = atom.checked_sub(1).ok_or_else(invalid)?;
+                    } else if tokens[0].chars().all(|c| c.is_ascii_alphabetic()) {
+                        let kind = tokens[0].to_lowercase();
+                        if kind != "sp" && (kind.len() != 1 || !SHELL_LABELS.contains(&kind)) {
+                            return Err(invalid());
+                        }
+                        scale = match tokens.get(2) {
+                            Some(s) => parse_fortran_real::<f64>(s).map_err(|_| invalid())?,
+                            None => 1.0,
+                        };
+                        molden.shells.push(MoldenShell {
+                            atom: shell_atom,
+                            sp_coefficients: (kind == "sp").then(Vec::new),
+                            kind,
+                            exponents: Vec::new(),
+                            coefficients: Vec::new(),
+                        });
+                    } else {
+                        let values = parse_reals(trimmed)?;
+                        let shell = molden.shells.last_mut().ok_or_else(invalid)?;
+                        if values.len() < 2 {
+                            return Err(invalid());
+                        }
+                        shell.exponents.push(values[0] * scale * scale);
+                        shell.coefficients.push(values[1]);
+                        if let Some(sp) = shell.sp_coefficients.as_mut() {
+                            sp.push(*values.get(2).ok_or_else(invalid)?);
+                        }
+                    }
+                }
+                Section::Mo => {
+                    if let Some((key, value)) = trimmed.split_once('=') {
+                        // A key after coefficients starts the next orbital.
+                        let starts_orbital =
+                            sparse_coefficients.last().is_none_or(|c| !c.is_empty());
+                        if starts_orbital {
+                            molden.orbitals.push(MoldenOrbital {
+                                symmetry: String::new(),
+                                energy: 0.0,
+                                spin: Spin::Alpha,
+                                occupation: 0.0,
+                                coefficients: Vec::new(),
+                            });
+                            sparse_coefficients.push(Vec::new());
+                        }
+                        let orbital = molden.orbitals.last_mut().ok_or_else(invalid)?;
+                        let value = value.trim();
+                        match key.trim().to_lowercase().as_str() {
+                            "sym" => orbital.symmetry = value.to_string(),
+                            "ene" => {
+                                orbital.energy = parse_fortran_real(value).map_err(|_| invalid())?
+                            }
+                            "spin" if value.to_lowercase().starts_with('b') => {
+                                orbital.spin = Spin::Beta
+                            }
+                            "occup" => {
+                                orbital.occupation =
+                                    parse_fortran_real(value).map_err(|_| invalid())?
+                            }
+                            _ => (),
+                        }
+                    } else {
+                        let coefficients = sparse_coefficients.last_mut().ok_or_else(invalid)?;
+                        if tokens.len() != 2 {
+                            return Err(invalid());
+                        }
+                        let index = tokens[0]
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|i| i.checked_sub(1))
+                            .ok_or_else(invalid)?;
+                        let value = parse_fortran_real::<f64>(tokens[1]).map_err(|_| invalid())?;
+                        coefficients.push((index, value));
+                    }
+                }
+                Section::Freq => molden.frequencies.extend(parse_reals(trimmed)?),
+                Section::FrCoord => {
+                    if tokens.len() < 4 {
+                        return Err(invalid());
+                    }
+                    let xyz = parse_reals(&tokens[1..4].join(" "))?;
+                    molden.frequency_coordinates.push(XYZLineSymbol {
+                        symbol: tokens[0].to_lowercase(),
+                        xyz: Point3::new(xyz[0], xyz[1], xyz[2]),
+                    });
+                }
+                Section::FrNormCoord => {
+                    if tokens[0].eq_ignore_ascii_case("vibration") {
+                        modes.push(Vec::new());
+                    } else {
+                        let values = parse_reals(trimmed)?;
+                        if values.len() != 3 {
+                            return Err(invalid());
+                        }
+                        modes.last_mut().ok_or_else(invalid)?.extend(values);
+                    }
+                }
+                Section::Other => (),
+            }
+        }
+        if !has_atoms {
+            return Err(ParseMoldenError::MissingSection("Atoms".to_string()));
+        }
+        let nbasis = sparse_coefficients
+            .iter()
+            .flatten()
+            .map(|(i, _)| i + 1)
+            .max()
+            .unwrap_or_default()
+            .max(molden.nbasis());
+        for (orbital, sparse) in molden.orbitals.iter_mut().zip(sparse_coefficients) {
+            orbital.coefficients = vec![0.0; nbasis];
+            for (i, value) in sparse {
+                orbital.coefficients[i] = value;
+            }
+        }
+        molden.normal_modes = modes
+            .into_iter()
+            .map(|m| DMatrix::from_row_slice(m.len() / 3, 3, &m))
+            .collect();
+        Ok(molden)
+    }
+
+    /// The number of basis functions of the `[GTO]` section.
+    pub fn nbasis(&self) -> usize {
+        self.shells
+            .iter()
+            .map(|s| s.nfunctions(&self.spherical))
+            .sum()
+    }
+
+    /// Returns the geometry of the `[Atoms]` section.
+    pub fn geometry(&self) -> Xyz<f64> {
+        Xyz {
+            number_of_atoms: self.atoms.len(),
+            distances_in: self.distances_in.clone(),
+            info_line: self.title.clone(),
+            lines: self
+                .atoms
+                .iter()
+                .map(|a| XyzLine::Numeric(a.atom.clone()))
+                .collect(),
+        }
+    }
+
+    /// Returns the MO coefficients with one column per orbital.
+    /// # Arguments
+    ///  * `spin` - Alpha or beta orbitals.
+    pub fn mo_coefficients(&self, spin: Spin) -> DMatrix<f64> {
+        let columns: Vec<&MoldenOrbital> =
+            self.orbitals.iter().filter(|o| o.spin == spin).collect();
+        let nbasis = self.orbitals.first().map_or(0, |o| o.coefficients.len());
+        DMatrix::from_fn(nbasis, columns.len(), |i, j| columns[j].coefficients[i])
+    }
+
+    /// Writes the Molden file. Exponents are written with the scale factor applied.
+    /// # Arguments
+    ///  * `dest` - Where to write to.
+    pub fn write<W: Write>(&self, dest: &mut W) -> Result<(), ParseMoldenError> {
+        writeln!(dest, "[Molden Format]")?;
+        if !self.title.is_empty() {
+            writeln!(dest, "[Title]\n{}", self.title)?;
+        }
+        let unit = if self.distances_in == "ang" {
+            "Angs"
+        } else {
+            "AU"
+        };
+        writeln!(dest, "[Atoms] {}", unit)?;
+        for (i, a) in self.atoms.iter().enumerate() {
+            writeln!(
+                dest,
+                "{:<4}{:>5}{:>5}{:>20.10}{:>20.10}{:>20.10}",
+                a.label,
+                i + 1,
+                a.atom.z_value,
+                a.atom.xyz.x,
+                a.atom.xyz.y,
+                a.atom.xyz.z
+            )?;
+        }
+        if !self.shells.is_empty() {
+            writeln!(dest, "[GTO]")?;
+            for atom in 0..self.atoms.len() {
+                writeln!(dest, "{:>4} 0", atom + 1)?;
+                for shell in self.shells.iter().filter(|s| s.atom == atom) {
+                    writeln!(dest, " {:<4}{:>3} 1.00", shell.kind, shell.exponents.len())?;
+                    for (p, (e, c)) in shell.exponents.iter().zip(&shell.coefficients).enumerate() {
+                        write!(
+                            dest,
+                            "{}{}",
+                            format_fortran_real(*e, 20, 10, 'D'),
+                            format_fortran_real(*c, 20, 10, 'D')
+                        )?;
+                        if let Some(sp) = shell.sp_coefficients.as_ref() {
+                            write!(dest, "{}", format_fortran_real(sp[p], 20, 10, 'D'))?;
+                        }
+                        writeln!(dest)?;
+                    }
+                }
+                writeln!(dest)?;
+            }
+        }
+        match (self.spherical.d, self.spherical.f) {
+            (true, true) => writeln!(dest, "[5D7F]")?,
+            (true, false) => writeln!(dest, "[5D10F]")?,
+            (false, true) => writeln!(dest, "[7F]")?,
+            (false, false) => (),
+        }
+        if self.spherical.g {
+            writeln!(dest, "[9G]")?;
+        }
+        if !self.orbitals.is_empty() {
+            writeln!(dest, "[MO]")?;
+            for orbital in self.orbitals.iter() {
+                let spin = match orbital.spin {
+                    Spin::Alpha => "Alpha",
+                    Spin::Beta => "Beta",
+                };
+                writeln!(dest, " Sym= {}", orbital.symmetry)?;
+                writeln!(dest, " Ene= {:.10}", orbital.energy)?;
+                writeln!(dest, " Spin= {}", spin)?;
+                writeln!(dest, " Occup= {:.6}", orbital.occupation)?;
+                for (i, c) in orbital.coefficients.iter().enumerate() {
+                    writeln!(dest, "{:>5} {:>20.12}", i + 1, c)?;
+                }
+            }
+        }
+        if !self.frequencies.is_empty() {
+            writeln!(dest, "[FREQ]")?;
+            for frequency in self.frequencies.iter() {
+                writeln!(dest, "{:>12.4}", frequency)?;
+            }
+        }
+        if !self.frequency_coordinates.is_empty() {
+            writeln!(dest, "[FR-COORD]")?;
+            for a in self.frequency_coordinates.iter() {
+                writeln!(
+                    dest,
+                    "{:<4}{:>20.10}{:>20.10}{:>20.10}",
+                    a.symbol, a.xyz.x, a.xyz.y, a.xyz.z
+                )?;
+            }
+        }
+        if !self.normal_modes.is_empty() {
+            writeln!(dest, "[FR-NORM-COORD]")?;
+            for (i, mode) in self.normal_modes.iter().enumerate() {
+                writeln!(dest, " vibration {}", i + 1)?;
+                for row in mode.row_iter() {
+                    writeln!(dest, "{:>20.10}{:>20.10}{:>20.10}", row[0], row[1], row[2])?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_molpro_dialect() {
+        let text = "[Molden Format]
+[ATOMS] (ANGS)
+H 1 1.0 0.0 0.0 0.0
+H 2 1.0 0.0 0.0 0.74
+[gto]
+1
+d 1 2.0
+ 0.5D0 1.0D0
+2
+S 1 1.0
+ 1.0 1.0
+[5D]
+[mo]
+Sym=1.1
+Ene=-0.5
+Spin=Beta
+Occup=1.0
+  6 0.7
+";
+        let molden = Molden::new(&mut Cursor::new(text)).unwrap();
+        assert_eq!(molden.distances_in, "ang");
+        assert_eq!(molden.atoms[1].atom.z_value, 1);
+        assert_eq!(molden.shells[0].exponents, vec![2.0]);
+        assert_eq!(molden.shells[1].atom, 1);
+        assert_eq!(molden.nbasis(), 6);
+        assert_eq!(molden.orbitals[0].spin, Spin::Beta);
+        assert_eq!(
+            molden.orbitals[0].coefficients,
+            vec![0.0, 0.0, 0.0, 0.0, 0.0, 0.7]
+        );
+        assert_eq!(molden.mo_coefficients(Spin::Alpha).ncols(), 0);
+        let mut written: Vec<u8> = Vec::new();
+        molden.write(&mut written).unwrap();
+        assert_eq!(Molden::new(&mut written.as_slice()).unwrap(), molden);
+    }
+
+    #[test]
+    fn test_missing_atoms() {
+        let text = "[Molden Format]\n[MO]\n";
+        assert!(matches!(
+            Molden::new(&mut Cursor::new(text)),
+            Err(ParseMoldenError::MissingSection(_))
+        ));
+    }
+}
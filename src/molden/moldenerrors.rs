@@ -0,0 +1,37 @@
+use std::error;
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum ParseMoldenError {
+    /// Raised when the underlying reader or writer fails.
+    Io(std::io::Error),
+    /// Raised when a line of a section cannot be parsed.
+    InvalidLine(String),
+    /// Raised when a required section is not in the file.
+    MissingSection(String),
+}
+
+impl Display for ParseMoldenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Could not read the molden file: {}", e),
+            Self::InvalidLine(line) => write!(f, "Could not parse the line '{}'.", line),
+            Self::MissingSection(name) => write!(f, "There is no section '[{}]'.", name),
+        }
+    }
+}
+
+impl error::Error for ParseMoldenError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseMoldenError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
@@ -0,0 +1,554 @@
+//! This module provides a reader and a writer for MDL Molfiles in the V2000 and V3000 formats,
+//! see [`sdf`] for structure-data files holding several records. A V2000 connection table
+//! consists of a header, a counts line, fixed column atom and bond blocks and a properties block:
+//! ```text
+//! formate
+//!   qc_file_parsers   3D
+//!
+//!   4  3  0  0  0  0            999 V2000
+//!     0.0000    0.0000    0.0000 C   0  0  0  0  0  0  0  0  0  0  0  0
+//! ...
+//!   1  2  2  0
+//! M  CHG  1   3  -1
+//! M  END
+//! ```
+//! V3000 connection tables list the same data as `M  V30` lines between `BEGIN CTAB` and
+//! `END CTAB`.
+use nalgebra::Point3;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+use crate::format_string::{get_formats, parse_fortran_formatted_values, ParsedValue};
+use crate::xyz::xyzline::symbol::XYZLineSymbol;
+use crate::xyz::{Xyz, XyzLine};
+
+use self::molfileerrors::ParseMolfileError;
+pub mod molfileerrors;
+pub mod sdf;
+
+/// Columns of the V2000 counts line.
+const COUNTS_FORMAT: &str = "(a3,a3,a3,a3,a3)";
+/// Columns of the V2000 atom block up to the charge.
+const ATOM_FORMAT: &str = "(3f10.4,1x,a3,a2,a3)";
+/// Columns of the V2000 bond block.
+const BOND_FORMAT: &str = "(a3,a3,a3,a3)";
+
+/// The version of the connection table.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MolfileVersion {
+    #[default]
+    V2000,
+    V3000,
+}
+
+/// An atom of the connection table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MolAtom {
+    /// The element symbol as given in the file.
+    pub symbol: String,
+    /// The position in angstrom.
+    pub xyz: Point3<f64>,
+    pub charge: i32,
+    /// The mass number of an isotope.
+    pub isotope: Option<u32>,
+}
+
+/// A bond of the connection table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MolBond {
+    /// Indices of the bonded atoms, starting at 0.
+    pub atoms: (usize, usize),
+    /// The bond type, i.e. 1, 2 or 3 for single, double and triple bonds and 4 for aromatic
+    /// ones.
+    pub order: u8,
+    /// The V2000 stereo code, i.e. 1 for wedges, 6 for hashes and 4 for either.
+    pub stereo: u8,
+}
+
+/// Represents a Molfile.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Molfile {
+    pub name: String,
+    /// The second header line, holding the program and the date.
+    pub program: String,
+    pub comment: String,
+    pub version: MolfileVersion,
+    pub chiral: bool,
+    pub atoms: Vec<MolAtom>,
+    pub bonds: Vec<MolBond>,
+}
+
+/// Returns an integer of a text field, 0 if the field is blank or the line ends before it.
+fn integer(values: &[ParsedValue], index: usize, line: &str) -> Result<i32, ParseMolfileError> {
+    match values.get(index) {
+        Some(ParsedValue::St(s)) if !s.is_empty() => s
+            .parse::<i32>()
+            .map_err(|_| ParseMolfileError::InvalidLine(line.to_string())),
+        _ => Ok(0),
+    }
+}
+
+/// Returns the 1-based atom number of a field as index.
+fn atom_index(
+    values: &[ParsedValue],
+    index: usize,
+    natoms: usize,
+    line: &str,
+) -> Result<usize, ParseMolfileError> {
+    match integer(values, index, line)? {
+        n if n > 0 && n as usize <= natoms => Ok(n as usize - 1),
+        _ => Err(ParseMolfileError::InvalidLine(line.to_string())),
+    }
+}
+
+/// Converts the V2000 charge code of the atom block into a charge.
+fn charge_from_code(code: i32) -> i32 {
+    match code {
+        1..=3 | 5..=7 => 4 - code,
+        _ => 0,
+    }
+}
+
+/// Converts a charge into the V2000 charge code of the atom block.
+fn code_from_charge(charge: i32) -> i32 {
+    match charge {
+        -3..=-1 | 1..=3 => 4 - charge,
+        _ => 0,
+    }
+}
+
+/// Converts a V3000 bond configuration into the V2000 stereo code and back.
+const STEREO_CONFIGURATIONS: [(u8, u8); 3] = [(1, 1), (2, 4), (3, 6)];
+
+impl Molfile {
+    /// Constructor for a Molfile struct, reading up to `M  END`.
+    /// # Arguments:
+    ///  * `file_handle` - A handle to a `BufReader`, i.e. the Molfile.
+    ///
+    ///  # Example:
+    ///
+    /// ```no_run
+    ///  use std::fs::File;
+    ///  use std::io::BufReader;
+    ///  use qc_file_parsers::molfile::Molfile;
+    ///  fn main() -> std::io::Result<()> {
+    ///     let f = File::open("ligand.mol")?;
+    ///     let mut b = BufReader::new(f);
+    ///     let molfile = Molfile::new(&mut b).unwrap();
+    ///     let geometry = molfile.geometry();
+    ///     Ok(())
+    ///  }
+    ///  ```
+    pub fn new<I: BufRead>(file_handle: &mut I) -> Result<Self, ParseMolfileError> {
+        let mut lines = Vec::new();
+        for line in file_handle.lines() {
+            let line = line?;
+            let end = line.starts_with("M  END");
+            lines.push(line);
+            if end {
+                break;
+            }
+        }
+        Ok(Self::parse_lines(&lines)?.0)
+    }
+
+    /// Function to parse a connection table. Returns the Molfile and the number of lines read,
+    /// including `M  END`.
+    /// # Arguments
+    ///  * `lines` - The lines of the Molfile.
+    pub(crate) fn parse_lines(lines: &[String]) -> Result<(Self, usize), ParseMolfileError> {
+        if lines.len() < 4 {
+            return Err(ParseMolfileError::UnexpectedEof);
+        }
+        let mut molfile = Self {
+            name: lines[0].trim_end().to_string(),
+            program: lines[1].trim_end().to_string(),
+            comment: lines[2].trim_end().to_string(),
+            ..Default::default()
+        };
+        let read = if lines[3].contains("V3000") {
+            molfile.version = MolfileVersion::V3000;
+            molfile.parse_v3000(lines)?
+        } else {
+            molfile.parse_v2000(lines)?
+        };
+        Ok((molfile, read))
+    }
+
+    /// Function to parse the counts line, the atom, bond and properties blocks of a V2000
+    /// connection table.
+    fn parse_v2000(&mut self, lines: &[String]) -> Result<usize, ParseMolfileError> {
+        let counts =
+            parse_fortran_formatted_values(&lines[3], &get_formats(COUNTS_FORMAT.to_string())?)?;
+        let count = |i: usize| {
+            usize::try_from(integer(&counts, i, &lines[3])?)
+                .map_err(|_| ParseMolfileError::InvalidLine(lines[3].clone()))
+        };
+        let natoms = count(0)?;
+        let nbonds = count(1)?;
+        self.chiral = integer(&counts, 4, &lines[3])? == 1;
+        if lines.len() < 4 + natoms + nbonds {
+            return Err(ParseMolfileError::UnexpectedEof);
+        }
+        let atom_formats = get_formats(ATOM_FORMAT.to_string())?;
+        for line in lines[4..4 + natoms].iter() {
+            let values = parse_fortran_formatted_values(line, &atom_formats)?;
+            let invalid = || ParseMolfileError::InvalidLine(line.to_string());
+            let coordinate = |i: usize| match values.get(i) {
+                Some(ParsedValue::Fl(v)) => Ok(*v),
+                _ => Err(invalid()),
+            };
+            let xyz = Point3::new(coordinate(0)?, coordinate(1)?, coordinate(2)?);
+            let symbol = match values.get(3) {
+                Some(ParsedValue::St(s)) if !s.is_empty() => s.clone(),
+                _ => return Err(invalid()),
+            };
+            self.atoms.push(MolAtom {
+                symbol,
+                xyz,
+                charge: charge_from_code(integer(&values, 5, line)?),
+                isotope: None,
+            });
+        }
+        let bond_formats = get_formats(BOND_FORMAT.to_string())?;
+        for line in lines[4 + natoms..4 + natoms + nbonds].iter() {
+            let values = parse_fortran_formatted_values(line, &bond_formats)?;
+            self.bonds.push(MolBond {
+                atoms: (
+                    atom_index(&values, 0, natoms, line)?,
+                    atom_index(&values, 1, natoms, line)?,
+                ),
+                order: u8::try_from(integer(&values, 2, line)?)
+                    .map_err(|_| ParseMolfileError::InvalidLine(line.to_string()))?,
+                stereo: u8::try_from(integer(&values, 3, line)?)
+                    .map_err(|_| ParseMolfileError::InvalidLine(line.to_string()))?,
+            });
+        }
+        let mut read = 4 + natoms + nbonds;
+        // Charges of `M  CHG` lines supersede those of the atom block.
+        let mut charges_reset = false;
+        for line in lines[read..].iter() {
+            read += 1;
+            if line.starts_with("M  END") {
+                break;
+            }
+            let property = line.get(..6).unwrap_or_default();
+            if property != "M  CHG" && property != "M  ISO" {
+                continue;
+            }
+            let invalid = || ParseMolfileError::InvalidLine(line.to_string());
+            let values = line[6..]
+                .split_whitespace()
+                .map(|t| t.parse::<i32>().map_err(|_| invalid()))
+                .collect::<Result<Vec<i32>, _>>()?;
+            let Some((&n, pairs)) = values.split_first() else {
+                return Err(invalid());
+            };
+            if pairs.len() != 2 * usize::try_from(n).map_err(|_| invalid())? {
+                return Err(invalid());
+            }
+            if property == "M  CHG" && !charges_reset {
+                self.atoms.iter_mut().for_each(|a| a.charge = 0);
+                charges_reset = true;
+            }
+            for pair in pairs.chunks(2) {
+                let atom = usize::try_from(pair[0])
+                    .ok()
+                    .and_then(|i| i.checked_sub(1))
+                    .and_then(|i| self.atoms.get_mut(i))
+                    .ok_or_else(invalid)?;
+                if property == "M  CHG" {
+                    atom.charge = pair[1];
+                } else {
+                    atom.isotope = Some(u32::try_from(pair[1]).map_err(|_| invalid())?);
+                }
+            }
+        }
+        Ok(read)
+    }
+
+    /// Function to parse the `M  V30` lines of a V3000 connection table.
+    fn parse_v3000(&mut self, lines: &[String]) -> Result<usize, ParseMolfileError> {
+        let mut read = 4;
+        let mut logical_lines: Vec<String> = Vec::new();
+        let mut continued = false;
+        for line in lines[4..].iter() {
+            read += 1;
+            if line.starts_with("M  END") {
+                break;
+            }
+            let Some(content) = line.strip_prefix("M  V30 ") else {
+                continue;
+            };
+            let (content, continues) = match content.trim_end().strip_suffix('-') {
+                Some(c) => (c, true),
+                None => (content.trim_end(), false),
+            };
+            match (continued, logical_lines.last_mut()) {
+                (true, Some(last)) => last.push_str(content),
+                _ => logical_lines.push(content.to_string()),
+            }
+            continued = continues;
+        }
+        // Atoms are referenced by their index in the file, which need not be contiguous.
+        let mut indices: HashMap<&str, usize> = HashMap::new();
+        let mut section = "";
+        for line in logical_lines.iter() {
+            let invalid = || ParseMolfileError::InvalidLine(line.to_string());
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            match tokens.as_slice() {
+                ["BEGIN", block, ..] => section = *block,
+                ["END", ..] => section = "",
+                ["COUNTS", counts @ ..] => {
+                    self.chiral = counts.get(4) == Some(&"1");
+                }
+                [index, symbol, x, y, z, _, fields @ ..] if section == "ATOM" => {
+                    let coordinate = |t: &str| t.parse::<f64>().map_err(|_| invalid());
+                    let mut atom = MolAtom {
+                        symbol: symbol.to_string(),
+                        xyz: Point3::new(coordinate(x)?, coordinate(y)?, coordinate(z)?),
+                        charge: 0,
+                        isotope: None,
+                    };
+                    for field in fields {
+                        match field.split_once('=') {
+                            Some(("CHG", v)) => {
+                                atom.charge = v.parse::<i32>().map_err(|_| invalid())?
+                            }
+                            Some(("MASS", v)) => {
+                                atom.isotope = Some(v.parse::<u32>().map_err(|_| invalid())?)
+                            }
+                            _ => (),
+                        }
+                    }
+                    indices.insert(*index, self.atoms.len());
+                    self.atoms.push(atom);
+                }
+                [_, order, first, second, fields @ ..] if section == "BOND" => {
+                    let atom = |t: &str| indices.get(t).copied().ok_or_else(invalid);
+                    let configuration = fields
+                        .iter()
+                        .find_map(|f| f.strip_prefix("CFG="))
+                        .map(|v| v.parse::<u8>().map_err(|_| invalid()))
+                        .transpose()?;
+                    self.bonds.push(MolBond {
+                        atoms: (atom(first)?, atom(second)?),
+                        order: order.parse::<u8>().map_err(|_| invalid())?,
+                        stereo: STEREO_CONFIGURATIONS
+                            .iter()
+                            .find(|(c, _)| Some(*c) == configuration)
+                            .map_or(0, |(_, s)| *s),
+                    });
+                }
+                _ => (),
+            }
+        }
+        Ok(read)
+    }
+
+    /// Returns the geometry in angstrom. The bonds refer to the lines of the geometry by their
+    /// indices.
+    pub fn geometry(&self) -> Xyz<f64> {
+        Xyz {
+            number_of_atoms: self.atoms.len(),
+            distances_in: "ang".to_string(),
+            info_line: self.name.clone(),
+            lines: self
+                .atoms
+                .iter()
+                .map(|a| {
+                    XyzLine::Symbolic(XYZLineSymbol {
+                        symbol: a.symbol.to_lowercase(),
+                        xyz: a.xyz,
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// Constructor for a V2000 Molfile struct from a geometry. The info line becomes the name.
+    /// # Arguments:
+    ///  * `geometry` - The geometry, in bohr or angstrom.
+    ///  * `bonds` - The bonds between the lines of the geometry.
+    pub fn from_xyz(geometry: &Xyz<f64>, bonds: &[MolBond]) -> Self {
+        let atoms = geometry
+            .labeled_atoms_in_angstrom()
+            .into_iter()
+            .map(|(symbol, xyz)| MolAtom {
+                symbol,
+                xyz,
+                charge: 0,
+                isotope: None,
+            })
+            .collect();
+        Self {
+            name: geometry.info_line.trim().to_string(),
+            atoms,
+            bonds: bonds.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    /// Writes the Molfile up to `M  END`. Connection tables with more than 999 atoms or bonds
+    /// are written as V3000.
+    /// # Arguments
+    ///  * `dest` - Where to write to.
+    pub fn write<W: Write>(&self, dest: &mut W) -> Result<(), ParseMolfileError> {
+        writeln!(dest, "{}", self.name)?;
+        writeln!(dest, "{}", self.program)?;
+        writeln!(dest, "{}", self.comment)?;
+        if self.version == MolfileVersion::V3000 || self.atoms.len() > 999 || self.bonds.len() > 999
+        {
+            self.write_v3000(dest)?;
+        } else {
+            self.write_v2000(dest)?;
+        }
+        writeln!(dest, "M  END")?;
+        Ok(())
+    }
+
+    /// Function to write the counts line, the atom, bond and properties blocks of a V2000
+    /// connection table.
+    fn write_v2000<W: Write>(&self, dest: &mut W) -> Result<(), ParseMolfileError> {
+        writeln!(
+            dest,
+            "{:3}{:3}  0  0{:3}  0            999 V2000",
+            self.atoms.len(),
+            self.bonds.len(),
+            i32::from(self.chiral)
+        )?;
+        for atom in self.atoms.iter() {
+            writeln!(
+                dest,
+                "{:10.4}{:10.4}{:10.4} {:<3} 0{:3}  0  0  0  0  0  0  0  0  0  0",
+                atom.xyz.x,
+                atom.xyz.y,
+                atom.xyz.z,
+                atom.symbol,
+                code_from_charge(atom.charge)
+            )?;
+        }
+        for bond in self.bonds.iter() {
+            writeln!(
+                dest,
+                "{:3}{:3}{:3}{:3}",
+                bond.atoms.0 + 1,
+                bond.atoms.1 + 1,
+                bond.order,
+                bond.stereo
+            )?;
+        }
+        let charges: Vec<(usize, i32)> = self
+            .atoms
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| a.charge != 0)
+            .map(|(i, a)| (i + 1, a.charge))
+            .collect();
+        let isotopes: Vec<(usize, i32)> = self
+            .atoms
+            .iter()
+            .enumerate()
+            .filter_map(|(i, a)| a.isotope.map(|m| (i + 1, m as i32)))
+            .collect();
+        for (property, entries) in [("CHG", charges), ("ISO", isotopes)] {
+            for chunk in entries.chunks(8) {
+                write!(dest, "M  {}{:3}", property, chunk.len())?;
+                for (atom, value) in chunk {
+                    write!(dest, " {:3} {:3}", atom, value)?;
+                }
+                writeln!(dest)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Function to write the `M  V30` lines of a V3000 connection table.
+    fn write_v3000<W: Write>(&self, dest: &mut W) -> Result<(), ParseMolfileError> {
+        writeln!(dest, "  0  0  0     0  0            999 V3000")?;
+        writeln!(dest, "M  V30 BEGIN CTAB")?;
+        writeln!(
+            dest,
+            "M  V30 COUNTS {} {} 0 0 {}",
+            self.atoms.len(),
+            self.bonds.len(),
+            i32::from(self.chiral)
+        )?;
+        writeln!(dest, "M  V30 BEGIN ATOM")?;
+        for (i, atom) in self.atoms.iter().enumerate() {
+            write!(
+                dest,
+                "M  V30 {} {} {:.4} {:.4} {:.4} 0",
+                i + 1,
+                atom.symbol,
+                atom.xyz.x,
+                atom.xyz.y,
+                atom.xyz.z
+            )?;
+            if atom.charge != 0 {
+                write!(dest, " CHG={}", atom.charge)?;
+            }
+            if let Some(mass) = atom.isotope {
+                write!(dest, " MASS={}", mass)?;
+            }
+            writeln!(dest)?;
+        }
+        writeln!(dest, "M  V30 END ATOM")?;
+        if !self.bonds.is_empty() {
+            writeln!(dest, "M  V30 BEGIN BOND")?;
+            for (i, bond) in self.bonds.iter().enumerate() {
+                write!(
+                    dest,
+                    "M  V30 {} {} {} {}",
+                    i + 1,
+                    bond.order,
+                    bond.atoms.0 + 1,
+                    bond.atoms.1 + 1
+                )?;
+                if let Some((configuration, _)) = STEREO_CONFIGURATIONS
+                    .iter()
+                    .find(|(_, s)| *s == bond.stereo)
+                {
+                    write!(dest, " CFG={}", configuration)?;
+                }
+                writeln!(dest)?;
+            }
+            writeln!(dest, "M  V30 END BOND")?;
+        }
+        writeln!(dest, "M  V30 END CTAB")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_charge_codes() {
+        for charge in -3..=3 {
+            assert_eq!(charge_from_code(code_from_charge(charge)), charge);
+        }
+        assert_eq!(code_from_charge(1), 3);
+        assert_eq!(charge_from_code(4), 0);
+    }
+
+    #[test]
+    fn test_out_of_range_values() {
+        let header = "h2\n\n\n  2  1  0  0  0  0  0  0  0  0999 V2000
+    0.0000    0.0000    0.0000 H   0  0  0  0  0  0  0  0  0  0  0  0
+    0.7400    0.0000    0.0000 H   0  0  0  0  0  0  0  0  0  0  0  0\n";
+        for tail in [
+            "  1  2300  0\nM  END\n",
+            "  1  2  1 -1\nM  END\n",
+            "  1  2  1  0\nM  CHG -1\nM  END\n",
+            "  1  2  1  0\nM  CHG  1-2147483648   1\nM  END\n",
+        ] {
+            let text = format!("{}{}", header, tail);
+            assert!(matches!(
+                Molfile::new(&mut text.as_bytes()),
+                Err(ParseMolfileError::InvalidLine(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::error;
+use std::fmt::Display;
+
+use crate::format_string::frmtstngerror::ParseFortranFormattedError;
+
+#[derive(Debug)]
+pub enum ParseMolfileError {
+    /// Raised when the underlying reader or writer fails.
+    Io(std::io::Error),
+    /// Raised when the connection table ends prematurely.
+    UnexpectedEof,
+    /// Raised when a line of the connection table cannot be parsed.
+    InvalidLine(String),
+    /// Raised when the fixed columns of a V2000 line cannot be parsed.
+    FormatError(ParseFortranFormattedError),
+}
+
+impl Display for ParseMolfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Could not read the Molfile: {}", e),
+            Self::UnexpectedEof => write!(f, "The connection table ended prematurely."),
+            Self::InvalidLine(line) => write!(f, "Could not parse the line '{}'.", line),
+            Self::FormatError(e) => write!(f, "Could not parse the columns of a line: {}", e),
+        }
+    }
+}
+
+impl error::Error for ParseMolfileError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::FormatError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseMolfileError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<ParseFortranFormattedError> for ParseMolfileError {
+    fn from(value: ParseFortranFormattedError) -> Self {
+        Self::FormatError(value)
+    }
+}
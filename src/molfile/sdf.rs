@@ -0,0 +1,154 @@
+//! Reading and writing of structure-data files, i.e. Molfiles followed by data items and
+//! terminated by `$$$$`. Each data item starts with a header line holding the tag in angle
+//! brackets, its value spans the following lines up to a blank line.
+//! ```text
+//! formate
+//! ...
+//! M  END
+//! > <energy>
+//! -188.5
+//!
+//! $$$$
+//! ```
+use std::io::{BufRead, Lines, Write};
+
+use super::molfileerrors::ParseMolfileError;
+use super::Molfile;
+
+/// A record of a structure-data file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SdfRecord {
+    pub molecule: Molfile,
+    /// Tags and values of the data items in the order of the file.
+    pub data: Vec<(String, String)>,
+}
+
+impl SdfRecord {
+    /// Returns the value of a data item.
+    /// # Arguments
+    ///  * `tag` - The tag of the data item.
+    pub fn data(&self, tag: &str) -> Option<&str> {
+        self.data
+            .iter()
+            .find(|(t, _)| t == tag)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Sets the value of a data item, which is appended if the tag is not present yet.
+    /// # Arguments
+    ///  * `tag` - The tag of the data item.
+    ///  * `value` - The value, possibly spanning several lines.
+    pub fn set_data(&mut self, tag: &str, value: &str) {
+        match self.data.iter_mut().find(|(t, _)| t == tag) {
+            Some((_, v)) => *v = value.to_string(),
+            None => self.data.push((tag.to_string(), value.to_string())),
+        }
+    }
+
+    /// Function to parse a record without its `$$$$` line.
+    fn parse(lines: &[String]) -> Result<Self, ParseMolfileError> {
+        let (molecule, read) = Molfile::parse_lines(lines)?;
+        let mut data: Vec<(String, String)> = Vec::new();
+        let mut value: Option<Vec<&str>> = None;
+        for line in lines[read..].iter() {
+            if line.starts_with('>') {
+                let tag = line
+                    .split_once('<')
+                    .and_then(|(_, t)| t.split_once('>'))
+                    .map(|(t, _)| t.to_string())
+                    .ok_or_else(|| ParseMolfileError::InvalidLine(line.to_string()))?;
+                if let Some(v) = value.take() {
+                    data.last_mut().expect("A tag precedes the value.").1 = v.join("\n");
+                }
+                data.push((tag, String::new()));
+                value = Some(Vec::new());
+            } else if line.trim().is_empty() {
+                if let Some(v) = value.take() {
+                    data.last_mut().expect("A tag precedes the value.").1 = v.join("\n");
+                }
+            } else if let Some(v) = value.as_mut() {
+                v.push(line.trim_end());
+            }
+        }
+        if let Some(v) = value.take() {
+            data.last_mut().expect("A tag precedes the value.").1 = v.join("\n");
+        }
+        Ok(Self { molecule, data })
+    }
+
+    /// Writes the record including its `$$$$` line.
+    /// # Arguments
+    ///  * `dest` - Where to write to.
+    pub fn write<W: Write>(&self, dest: &mut W) -> Result<(), ParseMolfileError> {
+        self.molecule.write(dest)?;
+        for (tag, value) in self.data.iter() {
+            writeln!(dest, "> <{}>", tag)?;
+            writeln!(dest, "{}", value)?;
+            writeln!(dest)?;
+        }
+        writeln!(dest, "$$$$")?;
+        Ok(())
+    }
+}
+
+/// Iterator over the records of a structure-data file, reading one record at a time.
+pub struct SdfReader<I: BufRead> {
+    lines: Lines<I>,
+}
+
+impl<I: BufRead> SdfReader<I> {
+    /// Constructor for an SdfReader.
+    /// # Arguments:
+    ///  * `file_handle` - A `BufReader` of the structure-data file.
+    ///
+    ///  # Example:
+    ///
+    /// ```no_run
+    ///  use std::fs::File;
+    ///  use std::io::BufReader;
+    ///  use qc_file_parsers::molfile::sdf::SdfReader;
+    ///  fn main() -> std::io::Result<()> {
+    ///     let f = File::open("conformers.sdf")?;
+    ///     for record in SdfReader::new(BufReader::new(f)) {
+    ///         let record = record.unwrap();
+    ///         let geometry = record.molecule.geometry();
+    ///     }
+    ///     Ok(())
+    ///  }
+    ///  ```
+    pub fn new(file_handle: I) -> Self {
+        Self {
+            lines: file_handle.lines(),
+        }
+    }
+}
+
+impl<I: BufRead> Iterator for SdfReader<I> {
+    type Item = Result<SdfRecord, ParseMolfileError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut lines = Vec::new();
+        for line in self.lines.by_ref() {
+            match line {
+                Ok(l) if l.starts_with("$$$$") => break,
+                Ok(l) => lines.push(l),
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+        if lines.iter().all(|l| l.trim().is_empty()) {
+            return None;
+        }
+        Some(SdfRecord::parse(&lines))
+    }
+}
+
+/// Function to write a structure-data file.
+/// # Arguments
+///  * `dest` - Where to write to.
+///  * `records` - The records to write.
+pub fn write_sdf<W: Write>(dest: &mut W, records: &[SdfRecord]) -> Result<(), ParseMolfileError> {
+    for record in records.iter() {
+        record.write(dest)?;
+    }
+    Ok(())
+}
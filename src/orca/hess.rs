@@ -0,0 +1,169 @@
+//! Reading of the `.hess` files written by ORCA frequency calculations. The file consists of
+//! blocks starting with `$name` and their dimension, the matrices are printed in labeled blocks
+//! of five columns:
+//! ```text
+//! $hessian
+//! 9
+//!                   0          1          2          3          4
+//!       0       0.513170  -0.000000   0.000000  -0.256585  -0.000000
+//! ```
+use nalgebra::{DMatrix, DVector, Point3};
+use std::io::BufRead;
+
+use crate::array_text::arraytexterrors::ParseArrayTextError;
+use crate::array_text::blocks::parse_labeled_block_lines;
+use crate::format_string::parse_fortran_real;
+use crate::xyz::xyzline::symbol::XYZLineSymbol;
+use crate::xyz::{Xyz, XyzLine};
+
+use super::orcaerrors::ParseOrcaError;
+
+/// Represents an ORCA `.hess` file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrcaHess {
+    /// The cartesian Hessian in hartree/bohr^2 from `$hessian`.
+    pub hessian: DMatrix<f64>,
+    /// The harmonic frequencies in cm^-1 from `$vibrational_frequencies`, including the zero
+    /// frequencies of translations and rotations.
+    pub frequencies: Option<DVector<f64>>,
+    /// The normal modes from `$normal_modes` with one column per mode.
+    pub normal_modes: Option<DMatrix<f64>>,
+    /// The element symbols from `$atoms`.
+    pub symbols: Vec<String>,
+    /// The atomic masses from `$atoms`.
+    pub masses: DVector<f64>,
+    /// The positions in bohr from `$atoms` with one row per atom.
+    pub coordinates: DMatrix<f64>,
+}
+
+/// Parses the dimension line following a block name.
+fn parse_dimension(lines: &[String], index: usize) -> Result<usize, ParseOrcaError> {
+    let line = lines.get(index).map_or("", |l| l.as_str());
+    line.split_whitespace()
+        .next()
+        .and_then(|t| t.parse::<usize>().ok())
+        .ok_or_else(|| ParseOrcaError::InvalidLine(line.to_string()))
+}
+
+/// Parses the labeled square matrix after the dimension line of the block starting at `index`.
+fn parse_square_block(lines: &[String], index: usize) -> Result<DMatrix<f64>, ParseOrcaError> {
+    let dimension = parse_dimension(lines, index + 1)?;
+    let mut block_lines = lines[index + 2..].iter().peekable();
+    let labeled = parse_labeled_block_lines::<_, _, f64>(&mut block_lines, false)?;
+    if labeled.matrix.shape() != (dimension, dimension) {
+        return Err(ParseArrayTextError::ShapeMismatch {
+            expected: dimension * dimension,
+            found: labeled.matrix.len(),
+        }
+        .into());
+    }
+    Ok(labeled.matrix)
+}
+
+/// Returns the tokens of the `count` lines following the dimension line.
+fn block_rows(lines: &[String], index: usize) -> Result<Vec<Vec<&str>>, ParseOrcaError> {
+    let count = parse_dimension(lines, index + 1)?;
+    let rows: Vec<Vec<&str>> = lines
+        .iter()
+        .skip(index + 2)
+        .take(count)
+        .map(|l| l.split_whitespace().collect())
+        .collect();
+    if rows.len() != count {
+        return Err(ParseOrcaError::InvalidLine(lines[index].clone()));
+    }
+    Ok(rows)
+}
+
+/// Parses a token as real, reporting the whole row on failure.
+fn parse_value(row: &[&str], index: usize) -> Result<f64, ParseOrcaError> {
+    row.get(index)
+        .and_then(|t| parse_fortran_real::<f64>(t).ok())
+        .ok_or_else(|| ParseOrcaError::InvalidLine(row.join(" ")))
+}
+
+impl OrcaHess {
+    /// Constructor for an OrcaHess struct.
+    /// # Arguments:
+    ///  * `file_handle` - A handle to a `BufReader`, i.e. the `.hess` file.
+    ///
+    ///  # Example:
+    ///
+    /// ```no_run
+    ///  use std::fs::File;
+    ///  use std::io::BufReader;
+    ///  use qc_file_parsers::orca::hess::OrcaHess;
+    ///  fn main() -> std::io::Result<()> {
+    ///     let f = File::open("water.hess")?;
+    ///     let mut b = BufReader::new(f);
+    ///     let hess = OrcaHess::new(&mut b).unwrap();
+    ///     let eigenvalues = hess.hessian.symmetric_eigenvalues();
+    ///     Ok(())
+    ///  }
+    ///  ```
+    pub fn new<I: BufRead>(file_handle: &mut I) -> Result<Self, ParseOrcaError> {
+        let lines = file_handle.lines().collect::<Result<Vec<String>, _>>()?;
+        let mut hessian = None;
+        let mut frequencies = None;
+        let mut normal_modes = None;
+        let mut atoms = None;
+        for (index, line) in lines.iter().enumerate() {
+            match line.trim() {
+                "$hessian" => hessian = Some(parse_square_block(&lines, index)?),
+                "$normal_modes" => normal_modes = Some(parse_square_block(&lines, index)?),
+                "$vibrational_frequencies" => {
+                    let values = block_rows(&lines, index)?
+                        .iter()
+                        .map(|row| parse_value(row, 1))
+                        .collect::<Result<Vec<f64>, _>>()?;
+                    frequencies = Some(DVector::from_vec(values));
+                }
+                "$atoms" => atoms = Some(block_rows(&lines, index)?),
+                _ => (),
+            }
+        }
+        let hessian = hessian.ok_or_else(|| ParseOrcaError::MissingBlock("$hessian".into()))?;
+        let atoms = atoms.ok_or_else(|| ParseOrcaError::MissingBlock("$atoms".into()))?;
+        let mut symbols = Vec::with_capacity(atoms.len());
+        let mut masses = DVector::zeros(atoms.len());
+        let mut coordinates = DMatrix::zeros(atoms.len(), 3);
+        for (i, row) in atoms.iter().enumerate() {
+            let symbol = row
+                .first()
+                .ok_or_else(|| ParseOrcaError::InvalidLine(row.join(" ")))?;
+            symbols.push(symbol.to_string());
+            masses[i] = parse_value(row, 1)?;
+            for k in 0..3 {
+                coordinates[(i, k)] = parse_value(row, 2 + k)?;
+            }
+        }
+        Ok(Self {
+            hessian,
+            frequencies,
+            normal_modes,
+            symbols,
+            masses,
+            coordinates,
+        })
+    }
+
+    /// Returns the geometry of `$atoms` in bohr.
+    pub fn geometry(&self) -> Xyz<f64> {
+        Xyz {
+            number_of_atoms: self.symbols.len(),
+            distances_in: "bohr".to_string(),
+            info_line: String::new(),
+            lines: self
+                .symbols
+                .iter()
+                .zip(self.coordinates.row_iter())
+                .map(|(s, r)| {
+                    XyzLine::Symbolic(XYZLineSymbol {
+                        symbol: s.to_lowercase(),
+                        xyz: Point3::new(r[0], r[1], r[2]),
+                    })
+                })
+                .collect(),
+        }
+    }
+}
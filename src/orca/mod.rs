@@ -0,0 +1,337 @@
+//! This module provides parsers for the output (`.out`) and Hessian (`.hess`) files of ORCA.
+//! The output is scanned for the blocks headed by their titles, e.g.
+//! ```text
+//! ---------------------------------
+//! CARTESIAN COORDINATES (ANGSTROEM)
+//! ---------------------------------
+//!   O      0.000000    0.000000    0.119748
+//!
+//! FINAL SINGLE POINT ENERGY       -76.026632734
+//! ```
+//! Blocks printed in every cycle of an optimization are collected in order, for all others the
+//! last one printed is kept.
+use nalgebra::{Point3, Vector3};
+use std::io::BufRead;
+
+use crate::format_string::parse_fortran_real;
+use crate::xyz::xyzline::symbol::XYZLineSymbol;
+use crate::xyz::{Xyz, XyzLine};
+
+use self::orcaerrors::ParseOrcaError;
+pub mod hess;
+pub mod orcaerrors;
+
+/// An excited state of a TD-DFT or CIS calculation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrcaExcitedState {
+    /// The number of the state.
+    pub state: usize,
+    /// The excitation energy in hartree.
+    pub energy: f64,
+    /// The excitation energy in eV.
+    pub energy_ev: f64,
+    /// The oscillator strength from the absorption spectrum, if printed.
+    pub oscillator_strength: Option<f64>,
+}
+
+/// A line of the IR spectrum table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrcaIrMode {
+    /// The index of the mode, counting translations and rotations.
+    pub mode: usize,
+    /// The frequency in cm^-1.
+    pub frequency: f64,
+    /// The intensity in km/mol.
+    pub intensity: f64,
+}
+
+/// A printed geometry together with the final single point energy of its cycle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrcaGeometry {
+    /// The atoms in angstrom.
+    pub atoms: Vec<XYZLineSymbol<f64>>,
+    /// The final single point energy printed after the geometry, if any.
+    pub energy: Option<f64>,
+}
+
+/// Represents an ORCA output file.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct OrcaOutput {
+    /// All final single point energies, one per optimization cycle.
+    pub energies: Vec<f64>,
+    /// All printed geometries in angstrom, one per optimization cycle.
+    pub geometries: Vec<OrcaGeometry>,
+    /// The last printed Mulliken charges.
+    pub mulliken_charges: Vec<f64>,
+    /// The last printed Loewdin charges.
+    pub loewdin_charges: Vec<f64>,
+    /// The last total dipole moment in atomic units.
+    pub dipole_moment: Option<Vector3<f64>>,
+    /// The excited states of the last TD-DFT or CIS calculation.
+    pub excited_states: Vec<OrcaExcitedState>,
+    /// The IR spectrum.
+    pub ir_spectrum: Vec<OrcaIrMode>,
+}
+
+/// Returns the rows of the table following `start`, i.e. the lines after the `ndashes`-th dashed
+/// line up to the next blank line.
+fn table_rows(lines: &[String], start: usize, ndashes: usize) -> &[String] {
+    let Some(dashes) = lines
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(16)
+        .filter(|(_, l)| l.trim().starts_with("---"))
+        .nth(ndashes - 1)
+        .map(|(i, _)| i)
+    else {
+        return &[];
+    };
+    let first = dashes + 1;
+    let count = lines[first..]
+        .iter()
+        .position(|l| l.trim().is_empty())
+        .unwrap_or(lines.len() - first);
+    &lines[first..first + count]
+}
+
+/// Parses a token as real, reporting the whole line on failure.
+fn parse_token(token: Option<&&str>, line: &str) -> Result<f64, ParseOrcaError> {
+    token
+        .and_then(|t| parse_fortran_real::<f64>(t).ok())
+        .ok_or_else(|| ParseOrcaError::InvalidLine(line.to_string()))
+}
+
+/// Parses the rows of an atomic charges block, `0 O :   -0.330244`, which end before the sum.
+fn parse_charges(lines: &[String]) -> Result<Vec<f64>, ParseOrcaError> {
+    let mut charges = Vec::new();
+    for line in lines.iter().skip_while(|l| l.trim().starts_with("---")) {
+        let Some((_, values)) = line.split_once(':') else {
+            break;
+        };
+        if line.trim_start().starts_with("Sum of") {
+            break;
+        }
+        let tokens: Vec<&str> = values.split_whitespace().collect();
+        charges.push(parse_token(tokens.first(), line)?);
+    }
+    Ok(charges)
+}
+
+impl OrcaOutput {
+    /// Constructor for an OrcaOutput struct.
+    /// # Arguments:
+    ///  * `file_handle` - A handle to a `BufReader`, i.e. the output file.
+    ///
+    ///  # Example:
+    ///
+    /// ```no_run
+    ///  use std::fs::File;
+    ///  use std::io::BufReader;
+    ///  use qc_file_parsers::orca::OrcaOutput;
+    ///  fn main() -> std::io::Result<()> {
+    ///     let f = File::open("water.out")?;
+    ///     let mut b = BufReader::new(f);
+    ///     let output = OrcaOutput::new(&mut b).unwrap();
+    ///     let energy = output.final_energy();
+    ///     Ok(())
+    ///  }
+    ///  ```
+    pub fn new<I: BufRead>(file_handle: &mut I) -> Result<Self, ParseOrcaError> {
+        let lines = file_handle.lines().collect::<Result<Vec<String>, _>>()?;
+        let mut output = Self::default();
+        for (i, line) in lines.iter().enumerate() {
+            let trimmed = line.trim();
+            let tokens: Vec<&str> = trimmed.split_whitespace().collect();
+            if trimmed.starts_with("FINAL SINGLE POINT ENERGY") {
+                let energy = parse_token(tokens.last(), line)?;
+                output.energies.push(energy);
+                if let Some(geometry) = output.geometries.last_mut() {
+                    geometry.energy.get_or_insert(energy);
+                }
+            } else if trimmed == "CARTESIAN COORDINATES (ANGSTROEM)" {
+                let mut atoms = Vec::new();
+                for row in table_rows(&lines, i + 1, 1) {
+                    let tokens: Vec<&str> = row.split_whitespace().collect();
+                    atoms.push(XYZLineSymbol {
+                        symbol: tokens[0].to_lowercase(),
+                        xyz: Point3::new(
+                            parse_token(tokens.get(1), row)?,
+                            parse_token(tokens.get(2), row)?,
+                            parse_token(tokens.get(3), row)?,
+                        ),
+                    });
+                }
+                output.geometries.push(OrcaGeometry {
+                    atoms,
+                    energy: None,
+                });
+            } else if trimmed.starts_with("MULLIKEN ATOMIC CHARGES") {
+                output.mulliken_charges = parse_charges(&lines[i + 1..])?;
+            } else if trimmed.starts_with("LOEWDIN ATOMIC CHARGES") {
+                output.loewdin_charges = parse_charges(&lines[i + 1..])?;
+            } else if trimmed.starts_with("Total Dipole Moment") {
+                let (_, values) = trimmed.split_once(':').unwrap_or_default();
+                let values: Vec<&str> = values.split_whitespace().collect();
+                output.dipole_moment = Some(Vector3::new(
+                    parse_token(values.first(), line)?,
+                    parse_token(values.get(1), line)?,
+                    parse_token(values.get(2), line)?,
+                ));
+            } else if trimmed.contains("EXCITED STATES") && !trimmed.contains("TRIPLETS") {
+                output.excited_states.clear();
+            } else if tokens.first() == Some(&"STATE") && trimmed.contains("E=") {
+                let state = tokens
+                    .get(1)
+                    .and_then(|t| t.trim_end_matches(':').parse::<usize>().ok())
+                    .ok_or_else(|| ParseOrcaError::InvalidLine(line.clone()))?;
+                let energy = tokens.iter().position(|t| *t == "E=").map(|p| p + 1);
+                let energy_ev = tokens.iter().position(|t| *t == "eV").map(|p| p - 1);
+                output.excited_states.push(OrcaExcitedState {
+                    state,
+                    energy: parse_token(energy.and_then(|p| tokens.get(p)), line)?,
+                    energy_ev: parse_token(energy_ev.and_then(|p| tokens.get(p)), line)?,
+                    oscillator_strength: None,
+                });
+            } else if trimmed == "ABSORPTION SPECTRUM VIA TRANSITION ELECTRIC DIPOLE MOMENTS" {
+                for row in table_rows(&lines, i + 1, 2) {
+                    let tokens: Vec<&str> = row.split_whitespace().collect();
+                    // Newer versions print the transition, `0-1A  ->  1-1A`, and the energy in eV.
+                    let (state, fosc) = if tokens.get(1) == Some(&"->") {
+                        let to = tokens
+                            .get(2)
+                            .ok_or_else(|| ParseOrcaError::InvalidLine(row.clone()))?;
+                        (to.split('-').next().unwrap_or_default(), 6)
+                    } else {
+                        (tokens[0], 3)
+                    };
+                    let state = state
+                        .parse::<usize>()
+                        .map_err(|_| ParseOrcaError::InvalidLine(row.clone()))?;
+                    let fosc = parse_token(tokens.get(fosc), row)?;
+                    if let Some(excited) = output
+                        .excited_states
+                        .iter_mut()
+                        .find(|s| s.state == state && s.oscillator_strength.is_none())
+                    {
+                        excited.oscillator_strength = Some(fosc);
+                    }
+                }
+            } else if trimmed == "IR SPECTRUM" {
+                output.ir_spectrum = table_rows(&lines, i + 1, 2)
+                    .iter()
+                    .map(|row| {
+                        let tokens: Vec<&str> = row.split_whitespace().collect();
+                        Ok(OrcaIrMode {
+                            mode: tokens[0]
+                                .trim_end_matches(':')
+                                .parse::<usize>()
+                                .map_err(|_| ParseOrcaError::InvalidLine(row.clone()))?,
+                            frequency: parse_token(tokens.get(1), row)?,
+                            intensity: parse_token(tokens.get(3), row)?,
+                        })
+                    })
+                    .collect::<Result<Vec<OrcaIrMode>, ParseOrcaError>>()?;
+            }
+        }
+        Ok(output)
+    }
+
+    /// Returns the last final single point energy.
+    pub fn final_energy(&self) -> Option<f64> {
+        self.energies.last().copied()
+    }
+
+    /// Returns the printed geometries as trajectory. The info line of each frame holds the energy
+    /// of its cycle, if printed.
+    pub fn trajectory(&self) -> Vec<Xyz<f64>> {
+        self.geometries
+            .iter()
+            .map(|geometry| Xyz {
+                number_of_atoms: geometry.atoms.len(),
+                distances_in: "ang".to_string(),
+                info_line: geometry
+                    .energy
+                    .map_or_else(String::new, |e| format!("E {}", e)),
+                lines: geometry
+                    .atoms
+                    .iter()
+                    .map(|a| XyzLine::Symbolic(a.clone()))
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_excited_states() {
+        let text = "TD-DFT/TDA EXCITED STATES (SINGLETS)
+
+STATE  1:  E=   0.288119 au      7.840 eV    63234.3 cm**-1 <S**2> =   0.000000 Mult 1
+STATE  2:  E=   0.358905 au      9.766 eV    78769.9 cm**-1 <S**2> =   0.000000 Mult 1
+
+-----------------------------------------------------------------------------------------
+                ABSORPTION SPECTRUM VIA TRANSITION ELECTRIC DIPOLE MOMENTS
+-----------------------------------------------------------------------------------------
+     Transition      Energy     Energy  Wavelength fosc(D2)      D2        DX        DY
+                      (eV)      (cm-1)    (nm)                 (au**2)    (au)      (au)
+-----------------------------------------------------------------------------------------
+  0-1A  ->  1-1A    7.840074   63234.3   158.1   0.017877172   0.09307  -0.00000   0.30508
+  0-1A  ->  2-1A    9.766243   78769.9   127.0   0.000000000   0.00000   0.00000   0.00000
+
+";
+        let output = OrcaOutput::new(&mut Cursor::new(text)).unwrap();
+        assert_eq!(output.excited_states.len(), 2);
+        assert_eq!(output.excited_states[0].energy, 0.288119);
+        assert_eq!(output.excited_states[1].energy_ev, 9.766);
+        assert_eq!(
+            output.excited_states[0].oscillator_strength,
+            Some(0.017877172)
+        );
+    }
+
+    #[test]
+    fn test_trajectory_energies() {
+        let text = "FINAL SINGLE POINT ENERGY       -76.0
+
+---------------------------------
+CARTESIAN COORDINATES (ANGSTROEM)
+---------------------------------
+  H      0.000000    0.000000    0.000000
+
+FINAL SINGLE POINT ENERGY       -1.1
+
+---------------------------------
+CARTESIAN COORDINATES (ANGSTROEM)
+---------------------------------
+  H      0.000000    0.000000    0.700000
+
+";
+        let output = OrcaOutput::new(&mut Cursor::new(text)).unwrap();
+        let trajectory = output.trajectory();
+        assert_eq!(trajectory.len(), 2);
+        assert_eq!(trajectory[0].info_line, "E -1.1");
+        assert_eq!(trajectory[1].info_line, "");
+        assert_eq!(output.final_energy(), Some(-1.1));
+    }
+
+    #[test]
+    fn test_truncated_transition() {
+        let text = "ABSORPTION SPECTRUM VIA TRANSITION ELECTRIC DIPOLE MOMENTS
+-----------------------------------------------------------------------------------------
+     Transition      Energy     Energy  Wavelength fosc(D2)      D2        DX        DY
+-----------------------------------------------------------------------------------------
+  0-1A  ->
+
+";
+        assert!(matches!(
+            OrcaOutput::new(&mut Cursor::new(text)),
+            Err(ParseOrcaError::InvalidLine(_))
+        ));
+    }
+}
@@ -0,0 +1,49 @@
+use std::error;
+use std::fmt::Display;
+
+use crate::array_text::arraytexterrors::ParseArrayTextError;
+
+#[derive(Debug)]
+pub enum ParseOrcaError {
+    /// Raised when the underlying reader fails.
+    Io(std::io::Error),
+    /// Raised when a line of a recognized block cannot be parsed.
+    InvalidLine(String),
+    /// Raised when a required block is not in the file.
+    MissingBlock(String),
+    /// Raised when a matrix block is malformed or does not have the announced dimension.
+    BlockError(ParseArrayTextError),
+}
+
+impl Display for ParseOrcaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Could not read the ORCA file: {}", e),
+            Self::InvalidLine(line) => write!(f, "Could not parse the line '{}'.", line),
+            Self::MissingBlock(name) => write!(f, "There is no block '{}'.", name),
+            Self::BlockError(e) => write!(f, "Could not read the matrix block: {}", e),
+        }
+    }
+}
+
+impl error::Error for ParseOrcaError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::BlockError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseOrcaError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<ParseArrayTextError> for ParseOrcaError {
+    fn from(value: ParseArrayTextError) -> Self {
+        Self::BlockError(value)
+    }
+}
@@ -0,0 +1,454 @@
+//! This module provides a reader and a writer for Protein Data Bank files. The records are read
+//! by their fixed columns, described by Fortran format strings:
+//! ```text
+//! CRYST1   18.650   18.650   18.650  90.00  90.00  90.00 P 1           1
+//! MODEL        1
+//! HETATM    1  O   HOH A   1       0.000   0.000   0.117  1.00 20.00           O
+//! HETATM    2  H1  HOH A   1       0.000   0.757  -0.469  1.00 20.00           H
+//! ENDMDL
+//! CONECT    1    2
+//! END
+//! ```
+//! Multi-model files hold one `MODEL`/`ENDMDL` section per model, alternate locations of an atom
+//! are distinguished by the column after the atom name.
+use nalgebra::Point3;
+use std::io::{BufRead, Write};
+
+use crate::format_string::{
+    get_formats, parse_fortran_formatted_values, FortranFormat, ParsedValue,
+};
+use crate::xyz::xyzline::symbol::XYZLineSymbol;
+use crate::xyz::{Xyz, XyzLine};
+
+use self::pdberrors::ParsePdbError;
+pub mod pdberrors;
+
+/// Columns of `ATOM` and `HETATM` records.
+const ATOM_FORMAT: &str = "(a6,a5,1x,a4,a1,a3,1x,a1,a4,a1,3x,3f8.3,a6,a6,10x,a2,a2)";
+/// Columns of the `CRYST1` record.
+const CRYST1_FORMAT: &str = "(a6,3f9.3,3f7.2,1x,a11,a4)";
+/// Columns of `CONECT` records.
+const CONECT_FORMAT: &str = "(a6,5a5)";
+
+/// The unit cell of the `CRYST1` record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdbCell {
+    /// Lengths a, b and c in angstrom.
+    pub lengths: [f64; 3],
+    /// Angles alpha, beta and gamma in degrees.
+    pub angles: [f64; 3],
+    pub space_group: String,
+    /// Number of polymeric chains in the unit cell.
+    pub z: usize,
+}
+
+/// An `ATOM` or `HETATM` record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdbAtom {
+    /// Whether the record is `HETATM`.
+    pub hetero: bool,
+    pub serial: usize,
+    pub name: String,
+    pub alt_loc: Option<char>,
+    pub residue_name: String,
+    pub chain_id: Option<char>,
+    pub residue_seq: i32,
+    pub insertion_code: Option<char>,
+    /// The position in angstrom.
+    pub xyz: Point3<f64>,
+    pub occupancy: f64,
+    pub b_factor: f64,
+    /// The element symbol, guessed from the atom name if the column is empty.
+    pub element: String,
+    pub charge: i32,
+}
+
+/// A model, i.e. a `MODEL`/`ENDMDL` section or all atoms of a single model file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdbModel {
+    pub serial: usize,
+    pub atoms: Vec<PdbAtom>,
+}
+
+/// Represents a PDB file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pdb {
+    pub cell: Option<PdbCell>,
+    pub models: Vec<PdbModel>,
+    /// Pairs of bonded atom serials from `CONECT` records, the smaller serial first.
+    pub bonds: Vec<(usize, usize)>,
+}
+
+/// Returns a text field, which is empty if the line ends before it.
+fn text(values: &[ParsedValue], index: usize) -> &str {
+    match values.get(index) {
+        Some(ParsedValue::St(s)) => s.as_str(),
+        _ => "",
+    }
+}
+
+/// Returns a real field.
+fn real(values: &[ParsedValue], index: usize) -> Option<f64> {
+    match values.get(index) {
+        Some(ParsedValue::Fl(v)) => Some(*v),
+        _ => None,
+    }
+}
+
+/// Returns a single character field, `None` if it is blank.
+fn character(values: &[ParsedValue], index: usize) -> Option<char> {
+    text(values, index).chars().next()
+}
+
+/// Guesses the element from an atom name like `CA` or `1HB`.
+fn element_from_name(name: &str) -> String {
+    name.chars()
+        .find(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase().to_string())
+        .unwrap_or_default()
+}
+
+/// Parses a charge like `2+` or `1-`.
+fn parse_charge(charge: &str) -> Option<i32> {
+    if charge.is_empty() {
+        return Some(0);
+    }
+    let (magnitude, sign) = charge.split_at(charge.len() - 1);
+    let magnitude = magnitude.parse::<i32>().ok()?;
+    match sign {
+        "+" => Some(magnitude),
+        "-" => Some(-magnitude),
+        _ => None,
+    }
+}
+
+/// Formats a charge like `2+` or `1-`, blank for neutral atoms.
+fn format_charge(charge: i32) -> String {
+    match charge {
+        0 => String::new(),
+        c if c > 0 => format!("{}+", c),
+        c => format!("{}-", -c),
+    }
+}
+
+impl PdbAtom {
+    /// Function to parse an `ATOM` or `HETATM` record.
+    fn parse(line: &str, formats: &[FortranFormat]) -> Result<Self, ParsePdbError> {
+        let invalid = || ParsePdbError::InvalidLine(line.to_string());
+        let values = parse_fortran_formatted_values(line, formats)?;
+        let name = text(&values, 2).to_string();
+        let element = match text(&values, 13) {
+            "" => element_from_name(&name),
+            e => e.to_string(),
+        };
+        Ok(Self {
+            hetero: text(&values, 0) == "HETATM",
+            serial: text(&values, 1).parse::<usize>().map_err(|_| invalid())?,
+            name,
+            alt_loc: character(&values, 3),
+            residue_name: text(&values, 4).to_string(),
+            chain_id: character(&values, 5),
+            residue_seq: match text(&values, 6) {
+                "" => 0,
+                s => s.parse::<i32>().map_err(|_| invalid())?,
+            },
+            insertion_code: character(&values, 7),
+            xyz: Point3::new(
+                real(&values, 8).ok_or_else(invalid)?,
+                real(&values, 9).ok_or_else(invalid)?,
+                real(&values, 10).ok_or_else(invalid)?,
+            ),
+            occupancy: match text(&values, 11) {
+                "" => 1.0,
+                s => s.parse::<f64>().map_err(|_| invalid())?,
+            },
+            b_factor: match text(&values, 12) {
+                "" => 0.0,
+                s => s.parse::<f64>().map_err(|_| invalid())?,
+            },
+            element,
+            charge: parse_charge(text(&values, 14)).ok_or_else(invalid)?,
+        })
+    }
+
+    /// Function to write the record with every field in its column.
+    fn write<W: Write>(&self, dest: &mut W) -> Result<(), ParsePdbError> {
+        // Names of atoms with single letter elements start in the second column of the field.
+        let name = if self.name.len() < 4 && self.element.len() < 2 {
+            format!(" {:<3}", self.name)
+        } else {
+            format!("{:<4}", self.name)
+        };
+        let record = format!(
+            "{:<6}{:>5} {}{}{:>3} {}{:>4}{}   {:8.3}{:8.3}{:8.3}{:6.2}{:6.2}          {:>2}{:<2}",
+            if self.hetero { "HETATM" } else { "ATOM" },
+            self.serial,
+            name,
+            self.alt_loc.unwrap_or(' '),
+            self.residue_name,
+            self.chain_id.unwrap_or(' '),
+            self.residue_seq,
+            self.insertion_code.unwrap_or(' '),
+            self.xyz.x,
+            self.xyz.y,
+            self.xyz.z,
+            self.occupancy,
+            self.b_factor,
+            self.element.to_uppercase(),
+            format_charge(self.charge)
+        );
+        // Blank charges are not padded.
+        writeln!(dest, "{}", record.trim_end())?;
+        Ok(())
+    }
+}
+
+impl PdbCell {
+    /// Function to parse the `CRYST1` record.
+    fn parse(line: &str, formats: &[FortranFormat]) -> Result<Self, ParsePdbError> {
+        let invalid = || ParsePdbError::InvalidLine(line.to_string());
+        let values = parse_fortran_formatted_values(line, formats)?;
+        let mut cell = [0.0; 6];
+        for (i, value) in cell.iter_mut().enumerate() {
+            *value = real(&values, i + 1).ok_or_else(invalid)?;
+        }
+        Ok(Self {
+            lengths: [cell[0], cell[1], cell[2]],
+            angles: [cell[3], cell[4], cell[5]],
+            space_group: text(&values, 7).to_string(),
+            z: match text(&values, 8) {
+                "" => 1,
+                z => z.parse::<usize>().map_err(|_| invalid())?,
+            },
+        })
+    }
+}
+
+impl Pdb {
+    /// Constructor for a Pdb struct. Records other than `CRYST1`, `MODEL`, `ENDMDL`, `ATOM`,
+    /// `HETATM` and `CONECT` are skipped.
+    /// # Arguments:
+    ///  * `file_handle` - A handle to a `BufReader`, i.e. the PDB file.
+    ///
+    ///  # Example:
+    ///
+    /// ```no_run
+    ///  use std::fs::File;
+    ///  use std::io::BufReader;
+    ///  use qc_file_parsers::pdb::Pdb;
+    ///  fn main() -> std::io::Result<()> {
+    ///     let f = File::open("cluster.pdb")?;
+    ///     let mut b = BufReader::new(f);
+    ///     let pdb = Pdb::new(&mut b).unwrap();
+    ///     let geometry = pdb.geometry(0);
+    ///     Ok(())
+    ///  }
+    ///  ```
+    pub fn new<I: BufRead>(file_handle: &mut I) -> Result<Self, ParsePdbError> {
+        let atom_formats = get_formats(ATOM_FORMAT.to_string())?;
+        let cryst1_formats = get_formats(CRYST1_FORMAT.to_string())?;
+        let conect_formats = get_formats(CONECT_FORMAT.to_string())?;
+        let mut pdb = Self::default();
+        let mut in_model = false;
+        for line in file_handle.lines() {
+            let line = line?;
+            let record = line.get(..6).unwrap_or(&line).trim_end();
+            match record {
+                "CRYST1" => pdb.cell = Some(PdbCell::parse(&line, &cryst1_formats)?),
+                "MODEL" => {
+                    let serial = line
+                        .get(6..)
+                        .and_then(|s| s.trim().parse::<usize>().ok())
+                        .ok_or_else(|| ParsePdbError::InvalidLine(line.clone()))?;
+                    pdb.models.push(PdbModel {
+                        serial,
+                        atoms: Vec::new(),
+                    });
+                    in_model = true;
+                }
+                "ENDMDL" => in_model = false,
+                "ATOM" | "HETATM" => {
+                    let atom = PdbAtom::parse(&line, &atom_formats)?;
+                    if !in_model && pdb.models.is_empty() {
+                        pdb.models.push(PdbModel {
+                            serial: 1,
+                            atoms: Vec::new(),
+                        });
+                    }
+                    pdb.models
+                        .last_mut()
+                        .expect("A model is added above.")
+                        .atoms
+                        .push(atom);
+                }
+                "CONECT" => {
+                    let values = parse_fortran_formatted_values(&line, &conect_formats)?;
+                    let serials = values
+                        .iter()
+                        .skip(1)
+                        .filter_map(|v| match v {
+                            ParsedValue::St(s) if !s.is_empty() => Some(s.parse::<usize>()),
+                            _ => None,
+                        })
+                        .collect::<Result<Vec<usize>, _>>()
+                        .map_err(|_| ParsePdbError::InvalidLine(line.clone()))?;
+                    if let Some((&first, bonded)) = serials.split_first() {
+                        for &other in bonded {
+                            let bond = (first.min(other), first.max(other));
+                            if !pdb.bonds.contains(&bond) {
+                                pdb.bonds.push(bond);
+                            }
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        Ok(pdb)
+    }
+
+    /// Returns the atoms of a model. Of atoms with alternate locations only the first location
+    /// found in the model is kept.
+    /// # Arguments
+    ///  * `model` - Index of the model, starting at 0.
+    pub fn atoms(&self, model: usize) -> Vec<&PdbAtom> {
+        let Some(model) = self.models.get(model) else {
+            return Vec::new();
+        };
+        let first_alt_loc = model.atoms.iter().find_map(|a| a.alt_loc);
+        model
+            .atoms
+            .iter()
+            .filter(|a| a.alt_loc.is_none() || a.alt_loc == first_alt_loc)
+            .collect()
+    }
+
+    /// Returns the geometry of a model in angstrom, see [`Pdb::atoms`].
+    /// # Arguments
+    ///  * `model` - Index of the model, starting at 0.
+    pub fn geometry(&self, model: usize) -> Xyz<f64> {
+        let lines: Vec<XyzLine<f64>> = self
+            .atoms(model)
+            .into_iter()
+            .map(|a| {
+                XyzLine::Symbolic(XYZLineSymbol {
+                    symbol: a.element.to_lowercase(),
+                    xyz: a.xyz,
+                })
+            })
+            .collect();
+        Xyz {
+            number_of_atoms: lines.len(),
+            distances_in: "ang".to_string(),
+            info_line: String::new(),
+            lines,
+        }
+    }
+
+    /// Returns the geometries of all models in angstrom.
+    pub fn trajectory(&self) -> Vec<Xyz<f64>> {
+        (0..self.models.len()).map(|m| self.geometry(m)).collect()
+    }
+
+    /// Constructor for a single model Pdb struct from a geometry. Every atom becomes a `HETATM`
+    /// record of residue `UNL` 1 in chain A, named after its element.
+    /// # Arguments:
+    ///  * `geometry` - The geometry, in bohr or angstrom.
+    pub fn from_xyz(geometry: &Xyz<f64>) -> Self {
+        let atoms = geometry
+            .labeled_atoms_in_angstrom()
+            .into_iter()
+            .enumerate()
+            .map(|(i, (element, xyz))| PdbAtom {
+                hetero: true,
+                serial: i + 1,
+                name: element.to_uppercase(),
+                alt_loc: None,
+                residue_name: "UNL".to_string(),
+                chain_id: Some('A'),
+                residue_seq: 1,
+                insertion_code: None,
+                xyz,
+                occupancy: 1.0,
+                b_factor: 0.0,
+                element: element.to_uppercase(),
+                charge: 0,
+            })
+            .collect();
+        Self {
+            cell: None,
+            models: vec![PdbModel { serial: 1, atoms }],
+            bonds: Vec::new(),
+        }
+    }
+
+    /// Writes the PDB file. `MODEL`/`ENDMDL` records are only written for several models.
+    /// # Arguments
+    ///  * `dest` - Where to write to.
+    pub fn write<W: Write>(&self, dest: &mut W) -> Result<(), ParsePdbError> {
+        if let Some(cell) = self.cell.as_ref() {
+            writeln!(
+                dest,
+                "CRYST1{:9.3}{:9.3}{:9.3}{:7.2}{:7.2}{:7.2} {:<11}{:4}",
+                cell.lengths[0],
+                cell.lengths[1],
+                cell.lengths[2],
+                cell.angles[0],
+                cell.angles[1],
+                cell.angles[2],
+                cell.space_group,
+                cell.z
+            )?;
+        }
+        let several = self.models.len() > 1;
+        for model in self.models.iter() {
+            if several {
+                writeln!(dest, "MODEL     {:4}", model.serial)?;
+            }
+            for atom in model.atoms.iter() {
+                atom.write(dest)?;
+            }
+            if several {
+                writeln!(dest, "ENDMDL")?;
+            }
+        }
+        let mut serials: Vec<usize> = self.bonds.iter().flat_map(|&(a, b)| [a, b]).collect();
+        serials.sort_unstable();
+        serials.dedup();
+        for serial in serials {
+            let bonded: Vec<usize> = self
+                .bonds
+                .iter()
+                .filter_map(|&(a, b)| match (a == serial, b == serial) {
+                    (true, _) => Some(b),
+                    (_, true) => Some(a),
+                    _ => None,
+                })
+                .collect();
+            for chunk in bonded.chunks(4) {
+                write!(dest, "CONECT{:5}", serial)?;
+                for other in chunk {
+                    write!(dest, "{:5}", other)?;
+                }
+                writeln!(dest)?;
+            }
+        }
+        writeln!(dest, "END")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_charges_and_names() {
+        assert_eq!(parse_charge("2+"), Some(2));
+        assert_eq!(parse_charge("1-"), Some(-1));
+        assert_eq!(parse_charge(""), Some(0));
+        assert_eq!(parse_charge("x"), None);
+        assert_eq!(format_charge(-2), "2-");
+        assert_eq!(element_from_name("1HB"), "H");
+    }
+}
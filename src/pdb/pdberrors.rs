@@ -0,0 +1,46 @@
+use std::error;
+use std::fmt::Display;
+
+use crate::format_string::frmtstngerror::ParseFortranFormattedError;
+
+#[derive(Debug)]
+pub enum ParsePdbError {
+    /// Raised when the underlying reader or writer fails.
+    Io(std::io::Error),
+    /// Raised when a record cannot be parsed.
+    InvalidLine(String),
+    /// Raised when the fixed columns of a record cannot be parsed.
+    FormatError(ParseFortranFormattedError),
+}
+
+impl Display for ParsePdbError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Could not read the PDB file: {}", e),
+            Self::InvalidLine(line) => write!(f, "Could not parse the record '{}'.", line),
+            Self::FormatError(e) => write!(f, "Could not parse the columns of a record: {}", e),
+        }
+    }
+}
+
+impl error::Error for ParsePdbError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::FormatError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParsePdbError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<ParseFortranFormattedError> for ParsePdbError {
+    fn from(value: ParseFortranFormattedError) -> Self {
+        Self::FormatError(value)
+    }
+}
@@ -0,0 +1,418 @@
+//! This module provides a parser for Psi4 output files (`output.dat`). The file is scanned for
+//! the blocks printed by the SCF, DF-MP2, CC and vibrational analysis modules, e.g.
+//! ```text
+//!     Geometry (in Angstrom), charge = 0, multiplicity = 1:
+//!
+//!        Center              X                  Y                   Z               Mass
+//!     ------------   -----------------  -----------------  -----------------  -----------------
+//!          O            0.000000000000     0.000000000000    -0.065775570547    15.994914619570
+//!
+//!    @DF-RHF iter   1:   -75.95376270516482   -4.46042e-01   3.01483e-02 DIIS/ADIIS
+//!   @DF-RHF Final Energy:   -76.02663273509006
+//! ```
+//! Every program banner starts a new job, so outputs of several runs appended to one file are
+//! split into jobs.
+use nalgebra::{DMatrix, Point3};
+use std::io::BufRead;
+
+use crate::format_string::parse_fortran_real;
+use crate::xyz::xyzline::symbol::XYZLineSymbol;
+use crate::xyz::{Xyz, XyzLine};
+
+use self::psi4errors::ParsePsi4Error;
+pub mod psi4errors;
+
+/// The banner printed at the start of every Psi4 run.
+const BANNER: &str = "Psi4: An Open-Source Ab Initio Electronic Structure Package";
+
+/// Start of the message of the exceptions Psi4 raises when the SCF, CC or geometry optimization
+/// iterations do not converge, e.g.
+/// `SCFConvergenceError: Could not converge SCF iterations in 100 iterations.`
+const NOT_CONVERGED: &str = "Could not converge";
+
+/// A final energy with the energies of the iterations leading to it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Psi4Energy {
+    /// The method, e.g. `DF-RHF`, `DF-MP2`, `CCSD` or `CCSD(T)`.
+    pub method: String,
+    /// The total energy in hartree.
+    pub energy: f64,
+    /// Total energies of the SCF iterations or correlation energies of the CC iterations,
+    /// empty for non-iterative methods.
+    pub history: Vec<f64>,
+}
+
+/// The results of a single Psi4 run.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Psi4Job {
+    /// The unit of the geometry, 'bohr' === `bohr` or 'angstroem' === `ang`.
+    pub distances_in: String,
+    /// The last printed geometry.
+    pub atoms: Vec<XYZLineSymbol<f64>>,
+    /// All final energies in the order they were printed.
+    pub energies: Vec<Psi4Energy>,
+    /// The last total gradient in hartree/bohr with one row per atom.
+    pub gradient: Option<DMatrix<f64>>,
+    /// Harmonic frequencies in cm^-1, imaginary ones as negative numbers.
+    pub frequencies: Vec<f64>,
+    /// The irreducible representations of the vibrations, if symmetry is used.
+    pub irreps: Vec<String>,
+    /// The cartesian displacements of each normal mode with one row per atom.
+    pub normal_modes: Vec<DMatrix<f64>>,
+    /// The last printed Mulliken charges.
+    pub mulliken_charges: Vec<f64>,
+    /// The last printed Lowdin charges.
+    pub lowdin_charges: Vec<f64>,
+    /// The message of the exception with which the run stopped, if it did not converge.
+    pub convergence_failure: Option<String>,
+}
+
+/// Represents a Psi4 output file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Psi4Output {
+    /// One entry per run of Psi4.
+    pub jobs: Vec<Psi4Job>,
+}
+
+/// Returns the rows of the table starting at `start`, i.e. the lines between the first dashed
+/// line and the next blank line.
+fn table_rows(lines: &[String], start: usize) -> &[String] {
+    let Some(dashes) = lines
+        .iter()
+        .skip(start)
+        .take(16)
+        .position(|l| l.trim().starts_with("---"))
+    else {
+        return &[];
+    };
+    let first = start + dashes + 1;
+    let count = lines[first..]
+        .iter()
+        .position(|l| l.trim().is_empty())
+        .unwrap_or(lines.len() - first);
+    &lines[first..first + count]
+}
+
+/// Parses tokens as reals, where a trailing `i` marks an imaginary frequency.
+fn parse_reals(tokens: &[&str], line: &str) -> Result<Vec<f64>, ParsePsi4Error> {
+    tokens
+        .iter()
+        .map(|t| match t.strip_suffix('i') {
+            Some(imaginary) => parse_fortran_real::<f64>(imaginary).map(|v| -v),
+            None => parse_fortran_real::<f64>(t),
+        })
+        .collect::<Result<Vec<f64>, _>>()
+        .map_err(|_| ParsePsi4Error::InvalidLine(line.to_string()))
+}
+
+/// Parses the value after the first occurrence of `delimiter`.
+fn value_after(line: &str, delimiter: char) -> Result<f64, ParsePsi4Error> {
+    let invalid = || ParsePsi4Error::InvalidLine(line.to_string());
+    let (_, rest) = line.split_once(delimiter).ok_or_else(invalid)?;
+    let token = rest.split_whitespace().next().ok_or_else(invalid)?;
+    parse_fortran_real(token).map_err(|_| invalid())
+}
+
+/// Returns the message of an exception line reporting failed convergence. Warnings mentioning
+/// convergence are not exceptions and are ignored.
+fn convergence_failure(line: &str) -> Option<&str> {
+    let start = line.find(NOT_CONVERGED)?;
+    let exception = line[..start].trim_end();
+    (exception.ends_with("Error:") || exception.ends_with("Exception:")).then(|| &line[start..])
+}
+
+/// Parses rows of an atom index or symbol followed by three reals into a matrix.
+fn parse_vector_rows(rows: &[String]) -> Result<DMatrix<f64>, ParsePsi4Error> {
+    let mut values = Vec::with_capacity(3 * rows.len());
+    for row in rows {
+        let tokens: Vec<&str> = row.split_whitespace().collect();
+        if tokens.len() < 4 {
+            return Err(ParsePsi4Error::InvalidLine(row.clone()));
+        }
+        values.extend(parse_reals(&tokens[1..4], row)?);
+    }
+    Ok(DMatrix::from_row_slice(rows.len(), 3, &values))
+}
+
+impl Psi4Job {
+    /// Returns the last printed geometry, if any.
+    pub fn geometry(&self) -> Option<Xyz<f64>> {
+        if self.atoms.is_empty() {
+            return None;
+        }
+        Some(Xyz {
+            number_of_atoms: self.atoms.len(),
+            distances_in: self.distances_in.clone(),
+            info_line: String::new(),
+            lines: self
+                .atoms
+                .iter()
+                .map(|a| XyzLine::Symbolic(a.clone()))
+                .collect(),
+        })
+    }
+
+    /// Returns the last energy of a method.
+    /// # Arguments
+    ///  * `method` - The method, matched case insensitively, e.g. `ccsd(t)`.
+    pub fn energy(&self, method: &str) -> Option<f64> {
+        self.energies
+            .iter()
+            .rev()
+            .find(|e| e.method.eq_ignore_ascii_case(method))
+            .map(|e| e.energy)
+    }
+
+    /// Returns an error if the run stopped because its iterations did not converge.
+    pub fn check_convergence(&self) -> Result<(), ParsePsi4Error> {
+        match &self.convergence_failure {
+            Some(message) => Err(ParsePsi4Error::NotConverged(message.clone())),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Psi4Output {
+    /// Constructor for a Psi4Output struct. Jobs which did not converge are kept, so the
+    /// results of the other jobs stay available; call `check_convergence` to get an error for
+    /// them.
+    /// # Arguments:
+    ///  * `file_handle` - A handle to a `BufReader`, i.e. the output file.
+    ///
+    ///  # Example:
+    ///
+    /// ```no_run
+    ///  use std::fs::File;
+    ///  use std::io::BufReader;
+    ///  use qc_file_parsers::psi4::Psi4Output;
+    ///  fn main() -> std::io::Result<()> {
+    ///     let f = File::open("output.dat")?;
+    ///     let mut b = BufReader::new(f);
+    ///     let output = Psi4Output::new(&mut b).unwrap();
+    ///     output.check_convergence().unwrap();
+    ///     let energy = output.jobs[0].energy("CCSD(T)");
+    ///     Ok(())
+    ///  }
+    ///  ```
+    pub fn new<I: BufRead>(file_handle: &mut I) -> Result<Self, ParsePsi4Error> {
+        let lines = file_handle.lines().collect::<Result<Vec<String>, _>>()?;
+        let empty_job = Psi4Job {
+            distances_in: "ang".to_string(),
+            ..Default::default()
+        };
+        let mut jobs: Vec<Psi4Job> = Vec::new();
+        let mut scf_history: Vec<f64> = Vec::new();
+        let mut cc_history: Vec<f64> = Vec::new();
+        let mut energies_block: Option<String> = None;
+        let mut i = 0;
+        while i < lines.len() {
+            let line = &lines[i];
+            let trimmed = line.trim();
+            i += 1;
+            let lowercase = trimmed.to_lowercase();
+            // Lines in front of the first banner belong to its job.
+            if trimmed.contains(BANNER) {
+                // Iterations of a job stopped before its final energy are dropped.
+                scf_history.clear();
+                cc_history.clear();
+                energies_block = None;
+            }
+            if jobs.is_empty() || (trimmed.contains(BANNER) && jobs.last() != Some(&empty_job)) {
+                jobs.push(empty_job.clone());
+            }
+            let job = jobs.last_mut().expect("A job is pushed above.");
+            let tokens: Vec<&str> = trimmed.split_whitespace().collect();
+            if let Some(message) = convergence_failure(trimmed) {
+                job.convergence_failure = Some(message.to_string());
+            } else if trimmed.starts_with("Geometry (in ") {
+                job.distances_in = if trimmed.starts_with("Geometry (in Bohr") {
+                    "bohr".to_string()
+                } else {
+                    "ang".to_string()
+                };
+                let rows = table_rows(&lines, i);
+                job.atoms = Vec::with_capacity(rows.len());
+                for row in rows {
+                    let tokens: Vec<&str> = row.split_whitespace().collect();
+                    if tokens.len() < 4 {
+                        return Err(ParsePsi4Error::InvalidLine(row.clone()));
+                    }
+                    let xyz = parse_reals(&tokens[1..4], row)?;
+                    job.atoms.push(XYZLineSymbol {
+                        symbol: tokens[0].to_lowercase(),
+                        xyz: Point3::new(xyz[0], xyz[1], xyz[2]),
+                    });
+                }
+            } else if trimmed.starts_with('@') && trimmed.contains(" iter ") {
+                scf_history.push(value_after(trimmed, ':')?);
+            } else if trimmed.starts_with('@') && trimmed.contains(" Final Energy:") {
+                let method = trimmed[1..trimmed.find(" Final Energy:").unwrap_or(1)].to_string();
+                job.energies.push(Psi4Energy {
+                    method,
+                    energy: value_after(trimmed, ':')?,
+                    history: std::mem::take(&mut scf_history),
+                });
+            } else if tokens.starts_with(&["Iter", "Energy", "RMS"]) {
+                for row in table_rows(&lines, i) {
+                    let tokens: Vec<&str> = row.split_whitespace().collect();
+                    if tokens.len() > 1 && tokens[0].parse::<usize>().is_ok() {
+                        cc_history.extend(parse_reals(&tokens[1..2], row)?);
+                    }
+                }
+            } else if trimmed.starts_with("* ") && trimmed.contains(" total energy") {
+                let method = trimmed[2..trimmed.find(" total energy").unwrap_or(2)].trim();
+                job.energies.push(Psi4Energy {
+                    method: method.to_string(),
+                    energy: value_after(trimmed, '=')?,
+                    history: std::mem::take(&mut cc_history),
+                });
+            } else if trimmed.contains("Energies <=") {
+                energies_block = trimmed
+                    .split('>')
+                    .nth(1)
+                    .and_then(|s| s.split(" Energies").next())
+                    .map(|s| s.trim().to_string());
+            } else if trimmed.starts_with("Total Energy") && energies_block.is_some() {
+                job.energies.push(Psi4Energy {
+                    method: energies_block.take().unwrap_or_default(),
+                    energy: value_after(trimmed, '=')?,
+                    history: Vec::new(),
+                });
+            } else if lowercase.starts_with("-total gradient:") {
+                job.gradient = Some(parse_vector_rows(table_rows(&lines, i))?);
+            } else if trimmed.starts_with("==> Harmonic Vibrational Analysis <==") {
+                job.frequencies.clear();
+                job.irreps.clear();
+                job.normal_modes.clear();
+            } else if tokens.first() == Some(&"Vibration") && tokens.len() > 1 {
+                let nmodes = tokens.len() - 1;
+                let mut modes: Vec<Vec<f64>> = vec![Vec::new(); nmodes];
+                for header in lines[i..]
+                    .iter()
+                    .take_while(|l| !l.trim().starts_with("---"))
+                {
+                    let header = header.trim();
+                    if let Some(values) = header.strip_prefix("Freq [cm^-1]") {
+                        let values: Vec<&str> = values.split_whitespace().collect();
+                        job.frequencies.extend(parse_reals(&values, header)?);
+                    } else if let Some(irreps) = header.strip_prefix("Irrep") {
+                        job.irreps
+                            .extend(irreps.split_whitespace().map(|s| s.to_string()));
+                    }
+                }
+                let rows = table_rows(&lines, i);
+                for row in rows {
+                    let tokens: Vec<&str> = row.split_whitespace().collect();
+                    if tokens.len() != 2 + 3 * nmodes {
+                        return Err(ParsePsi4Error::InvalidLine(row.clone()));
+                    }
+                    let values = parse_reals(&tokens[2..], row)?;
+                    for (mode, displacement) in modes.iter_mut().zip(values.chunks(3)) {
+                        mode.extend_from_slice(displacement);
+                    }
+                }
+                job.normal_modes.extend(
+                    modes
+                        .iter()
+                        .map(|m| DMatrix::from_row_slice(rows.len(), 3, m)),
+                );
+            } else if trimmed.starts_with("Mulliken Charges:")
+                || trimmed.starts_with("Lowdin Charges:")
+            {
+                // The rows follow a column header and end before the totals.
+                let mut charges = Vec::new();
+                for row in lines.get(i + 1..).unwrap_or_default() {
+                    let tokens: Vec<&str> = row.split_whitespace().collect();
+                    if tokens.len() < 3 || tokens[0].parse::<usize>().is_err() {
+                        break;
+                    }
+                    charges.extend(parse_reals(&tokens[tokens.len() - 1..], row)?);
+                }
+                if trimmed.starts_with("Mulliken") {
+                    job.mulliken_charges = charges;
+                } else {
+                    job.lowdin_charges = charges;
+                }
+            }
+        }
+        Ok(Self { jobs })
+    }
+
+    /// Returns the error of the first job which did not converge.
+    pub fn check_convergence(&self) -> Result<(), ParsePsi4Error> {
+        self.jobs.iter().try_for_each(Psi4Job::check_convergence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_table_rows() {
+        let lines: Vec<String> = ["header", "", " ---- ----", "1 2", "3 4", "", "5 6"]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        assert_eq!(table_rows(&lines, 1), &lines[3..5]);
+        assert!(table_rows(&lines, 3).is_empty());
+    }
+
+    #[test]
+    fn test_truncated_charges() {
+        let text = "  @DF-RHF Final Energy:   -76.02663273509006\n  Mulliken Charges: (a.u.)\n";
+        let output = Psi4Output::new(&mut Cursor::new(text)).unwrap();
+        assert!(output.jobs[0].mulliken_charges.is_empty());
+        let text = format!(
+            "{}   Center  Symbol  Alpha    Beta     Spin     Total\n",
+            text
+        );
+        let output = Psi4Output::new(&mut Cursor::new(text)).unwrap();
+        assert!(output.jobs[0].mulliken_charges.is_empty());
+    }
+
+    #[test]
+    fn test_history_per_job() {
+        let text = format!(
+            "{BANNER}\n\
+             @DF-RHF iter   1:   -75.9   -4.4e-01   3.0e-02 DIIS\n\
+             SCFConvergenceError: Could not converge SCF iterations in 1 iterations.\n\
+             {BANNER}\n\
+             @DF-RHF iter   1:   -76.0   -4.4e-01   3.0e-02 DIIS\n\
+             @DF-RHF Final Energy:   -76.1\n"
+        );
+        let output = Psi4Output::new(&mut Cursor::new(text)).unwrap();
+        assert_eq!(output.jobs.len(), 2);
+        assert_eq!(output.jobs[1].energies[0].history, vec![-76.0]);
+    }
+
+    #[test]
+    fn test_not_converged() {
+        let text = format!(
+            "{BANNER}\n\
+             Warning: the orbitals did not converge in the guess.\n\
+             @DF-RHF Final Energy:   -76.02663273509006\n\
+             {BANNER}\n\
+             @DF-RHF iter 100:   -75.9   -4.4e-01   3.0e-02 DIIS\n\
+             PsiException: Could not converge SCF iterations in 100 iterations.\n"
+        );
+        let output = Psi4Output::new(&mut Cursor::new(text)).unwrap();
+        assert_eq!(output.jobs.len(), 2);
+        assert!(output.jobs[0].check_convergence().is_ok());
+        assert!(matches!(
+            output.check_convergence(),
+            Err(ParsePsi4Error::NotConverged(_))
+        ));
+        assert_eq!(output.jobs[0].energy("DF-RHF"), Some(-76.02663273509006));
+        assert!(output.jobs[1].energies.is_empty());
+        assert!(matches!(
+            output.jobs[1].check_convergence(),
+            Err(ParsePsi4Error::NotConverged(_))
+        ));
+        assert_eq!(
+            output.jobs[1].convergence_failure.as_deref(),
+            Some("Could not converge SCF iterations in 100 iterations.")
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use std::error;
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum ParsePsi4Error {
+    /// Raised when the underlying reader fails.
+    Io(std::io::Error),
+    /// Raised when a line of a recognized block cannot be parsed.
+    InvalidLine(String),
+    /// Raised by `check_convergence` for a run which did not converge.
+    NotConverged(String),
+}
+
+impl Display for ParsePsi4Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Could not read the Psi4 output: {}", e),
+            Self::InvalidLine(line) => write!(f, "Could not parse the line '{}'.", line),
+            Self::NotConverged(line) => write!(f, "A calculation did not converge: '{}'", line),
+        }
+    }
+}
+
+impl error::Error for ParsePsi4Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParsePsi4Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
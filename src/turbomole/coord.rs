@@ -0,0 +1,123 @@
+//! Reading and writing of `$coord`. Each line holds the position in bohr followed by the element
+//! symbol and optionally `f` for atoms fixed during optimizations.
+//! ```text
+//! $coord
+//!     0.00000000000000      0.00000000000000     -0.12430200000000      o
+//!     0.00000000000000      1.43215600000000      0.98641500000000      h
+//! $end
+//! ```
+use nalgebra::Point3;
+use std::io::Write;
+
+use crate::format_string::parse_fortran_real;
+use crate::xyz::xyzline::symbol::XYZLineSymbol;
+use crate::xyz::{Xyz, XyzLine, BOHR_IN_ANGSTROM};
+
+use super::turbomoleerrors::ParseTurbomoleError;
+use super::DataGroup;
+
+/// Function to read a single `$coord` line.
+/// # Arguments
+///  * `line` - The line to parse.
+pub(crate) fn parse_coord_line(line: &str) -> Result<XYZLineSymbol<f64>, ParseTurbomoleError> {
+    let invalid = || ParseTurbomoleError::InvalidLine(line.to_string());
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    if tokens.len() < 4 {
+        return Err(invalid());
+    }
+    let mut xyz = [0.0; 3];
+    for (value, token) in xyz.iter_mut().zip(tokens.iter()) {
+        *value = parse_fortran_real::<f64>(token).map_err(|_| invalid())?;
+    }
+    Ok(XYZLineSymbol {
+        symbol: tokens[3].to_lowercase(),
+        xyz: Point3::from(xyz),
+    })
+}
+
+/// Function to read the geometry of a `$coord` data group.
+/// # Arguments
+///  * `group` - The `$coord` data group.
+pub fn parse_coord(group: &DataGroup) -> Result<Xyz<f64>, ParseTurbomoleError> {
+    let lines = group
+        .lines
+        .iter()
+        .map(|l| parse_coord_line(l).map(XyzLine::Symbolic))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Xyz {
+        number_of_atoms: lines.len(),
+        distances_in: "bohr".to_string(),
+        info_line: String::new(),
+        lines,
+    })
+}
+
+/// Function to write a geometry as `coord` file. Positions in angstrom are converted to bohr.
+/// # Arguments
+///  * `dest` - Where to write to.
+///  * `geometry` - The geometry to write.
+pub fn write_coord<W: Write>(dest: &mut W, geometry: &Xyz<f64>) -> Result<(), ParseTurbomoleError> {
+    writeln!(dest, "$coord")?;
+    for (label, xyz) in geometry.labeled_atoms_in_angstrom() {
+        let xyz = xyz / BOHR_IN_ANGSTROM;
+        writeln!(
+            dest,
+            "{:20.14}{:22.14}{:22.14}      {}",
+            xyz.x,
+            xyz.y,
+            xyz.z,
+            label.to_lowercase()
+        )?;
+    }
+    writeln!(dest, "$end")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turbomole::parse_data_groups;
+    use crate::xyz::xyzline::numeric::XYZLineNumeric;
+
+    #[test]
+    fn test_coord_round_trip() {
+        let text = "$coord\n  0.0 0.0 -0.1243D+00 o\n  0.0 1.432156 0.986415 h f\n$end\n";
+        let groups = parse_data_groups(&mut text.as_bytes()).unwrap();
+        let geometry = parse_coord(&groups[0]).unwrap();
+        assert_eq!(geometry.number_of_atoms, 2);
+        assert_eq!(
+            geometry.lines[0],
+            XyzLine::Symbolic(XYZLineSymbol {
+                symbol: "o".to_string(),
+                xyz: Point3::new(0.0, 0.0, -0.1243)
+            })
+        );
+        let mut written: Vec<u8> = Vec::new();
+        write_coord(&mut written, &geometry).unwrap();
+        let reread = parse_data_groups(&mut written.as_slice()).unwrap();
+        assert_eq!(parse_coord(&reread[0]).unwrap().lines, geometry.lines);
+    }
+
+    #[test]
+    fn test_write_coord_from_angstrom() {
+        let geometry = Xyz {
+            number_of_atoms: 1,
+            distances_in: "angstrom".to_string(),
+            info_line: String::new(),
+            lines: vec![XyzLine::Numeric(XYZLineNumeric {
+                z_value: 119,
+                xyz: Point3::new(0.0, 0.0, BOHR_IN_ANGSTROM),
+            })],
+        };
+        let mut written: Vec<u8> = Vec::new();
+        write_coord(&mut written, &geometry).unwrap();
+        let reread = parse_data_groups(&mut written.as_slice()).unwrap();
+        assert_eq!(
+            parse_coord(&reread[0]).unwrap().lines[0],
+            XyzLine::Symbolic(XYZLineSymbol {
+                symbol: "x".to_string(),
+                xyz: Point3::new(0.0, 0.0, 1.0)
+            })
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! Reading of `$grad`, which collects the geometry and cartesian gradient of every optimization
+//! cycle.
+//! ```text
+//! $grad          cartesian gradients
+//!   cycle =      1    SCF energy =      -76.0107465155   |dE/dxyz| =  0.023416
+//!     0.00000000000000      0.00000000000000     -0.12430200000000      o
+//!     ...
+//!    0.00000000000000D+00   0.00000000000000D+00   0.11523830439542D-01
+//!     ...
+//! $end
+//! ```
+use nalgebra::DMatrix;
+
+use crate::format_string::parse_fortran_real;
+use crate::xyz::xyzline::symbol::XYZLineSymbol;
+use crate::xyz::{Xyz, XyzLine};
+
+use super::coord::parse_coord_line;
+use super::turbomoleerrors::ParseTurbomoleError;
+use super::DataGroup;
+
+/// A cycle of `$grad`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradientCycle {
+    pub cycle: usize,
+    /// The total energy in hartree.
+    pub energy: f64,
+    /// The positions in bohr.
+    pub atoms: Vec<XYZLineSymbol<f64>>,
+    /// The gradient in hartree/bohr with one row per atom.
+    pub gradient: DMatrix<f64>,
+}
+
+impl GradientCycle {
+    /// Returns the geometry of the cycle in bohr.
+    pub fn geometry(&self) -> Xyz<f64> {
+        Xyz {
+            number_of_atoms: self.atoms.len(),
+            distances_in: "bohr".to_string(),
+            info_line: format!("cycle {}", self.cycle),
+            lines: self
+                .atoms
+                .iter()
+                .map(|a| XyzLine::Symbolic(a.clone()))
+                .collect(),
+        }
+    }
+}
+
+/// Function to read the cycle number and the energy of a `cycle =` line.
+fn parse_cycle_line(line: &str) -> Result<(usize, f64), ParseTurbomoleError> {
+    let invalid = || ParseTurbomoleError::InvalidLine(line.to_string());
+    let value_after = |key: &str| {
+        line.split_once(key)
+            .and_then(|(_, rest)| rest.trim_start().strip_prefix('='))
+            .and_then(|rest| rest.split_whitespace().next())
+    };
+    let cycle = value_after("cycle")
+        .and_then(|t| t.parse::<usize>().ok())
+        .ok_or_else(invalid)?;
+    let energy = value_after("energy")
+        .and_then(|t| parse_fortran_real::<f64>(t).ok())
+        .ok_or_else(invalid)?;
+    Ok((cycle, energy))
+}
+
+/// Function to read all cycles of a `$grad` data group.
+/// # Arguments
+///  * `group` - The `$grad` data group.
+pub fn parse_grad(group: &DataGroup) -> Result<Vec<GradientCycle>, ParseTurbomoleError> {
+    let mut cycles = Vec::new();
+    let mut lines = group.lines.iter().peekable();
+    while let Some(line) = lines.next() {
+        let (cycle, energy) = parse_cycle_line(line)?;
+        let mut atoms = Vec::new();
+        while let Some(l) = lines.next_if(|l| l.split_whitespace().count() > 3) {
+            atoms.push(parse_coord_line(l)?);
+        }
+        let mut gradient = DMatrix::zeros(atoms.len(), 3);
+        for mut row in gradient.row_iter_mut() {
+            let l = lines
+                .next()
+                .ok_or_else(|| ParseTurbomoleError::InvalidLine(line.to_string()))?;
+            let values = l
+                .split_whitespace()
+                .map(parse_fortran_real::<f64>)
+                .collect::<Result<Vec<f64>, _>>()
+                .map_err(|_| ParseTurbomoleError::InvalidLine(l.to_string()))?;
+            if values.len() != 3 {
+                return Err(ParseTurbomoleError::InvalidLine(l.to_string()));
+            }
+            row.copy_from_slice(&values);
+        }
+        cycles.push(GradientCycle {
+            cycle,
+            energy,
+            atoms,
+            gradient,
+        });
+    }
+    Ok(cycles)
+}
@@ -0,0 +1,172 @@
+//! This module provides a reader for Turbomole's `control` file and the files it references.
+//! Everything is stored in data groups, which start with a `$` keyword and end at the next one.
+//! The content of a data group may also live in a separate file given by `file=`.
+//! ```text
+//! $title
+//! water
+//! $coord    file=coord
+//! $scfmo    file=mos
+//! $grad    file=gradient
+//! $end
+//! ```
+use nalgebra::DMatrix;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::xyz::Xyz;
+
+use self::grad::GradientCycle;
+use self::mos::TurbomoleMo;
+use self::turbomoleerrors::ParseTurbomoleError;
+pub mod coord;
+pub mod grad;
+pub mod mos;
+pub mod turbomoleerrors;
+
+/// A data group, i.e. the `$` keyword, the options given in its line and the following lines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataGroup {
+    /// The keyword without `$`, e.g. `coord`.
+    pub name: String,
+    /// The rest of the keyword line, e.g. `file=coord`.
+    pub options: String,
+    pub lines: Vec<String>,
+}
+
+impl DataGroup {
+    /// Returns the value of an option given as `key=value`.
+    /// # Arguments
+    ///  * `key` - Name of the option.
+    pub fn option(&self, key: &str) -> Option<&str> {
+        self.options
+            .split_whitespace()
+            .filter_map(|t| t.split_once('='))
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v)
+    }
+}
+
+/// Function to split a Turbomole file into its data groups. Comment lines starting with `#` are
+/// dropped and `$end` terminates the file.
+/// # Arguments
+///  * `source` - mutable reference of the file buffer.
+pub fn parse_data_groups<I: BufRead>(
+    source: &mut I,
+) -> Result<Vec<DataGroup>, ParseTurbomoleError> {
+    let mut groups: Vec<DataGroup> = Vec::new();
+    for line in source.lines() {
+        let line = line?;
+        let trimmed = line.trim_start();
+        if let Some(header) = trimmed.strip_prefix('$') {
+            let (name, options) = header
+                .split_once(char::is_whitespace)
+                .unwrap_or((header, ""));
+            if name == "end" {
+                break;
+            }
+            groups.push(DataGroup {
+                name: name.to_string(),
+                options: options.trim().to_string(),
+                lines: Vec::new(),
+            });
+        } else if trimmed.starts_with('#') || trimmed.is_empty() {
+            continue;
+        } else if let Some(group) = groups.last_mut() {
+            group.lines.push(line);
+        } else {
+            return Err(ParseTurbomoleError::InvalidLine(line));
+        }
+    }
+    Ok(groups)
+}
+
+/// Represents a `control` file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Control {
+    pub groups: Vec<DataGroup>,
+}
+
+impl Control {
+    /// Constructor for a Control struct. Data groups referencing a file stay empty until
+    /// [`Control::resolve_files`] is called.
+    /// # Arguments:
+    ///  * `file_handle` - A handle to a `BufReader`, i.e. the `control` file.
+    ///
+    ///  # Example:
+    ///
+    /// ```no_run
+    ///  use std::fs::File;
+    ///  use std::io::BufReader;
+    ///  use std::path::Path;
+    ///  use qc_file_parsers::turbomole::Control;
+    ///  fn main() -> std::io::Result<()> {
+    ///     let f = File::open("water/control")?;
+    ///     let mut b = BufReader::new(f);
+    ///     let mut control = Control::new(&mut b).unwrap();
+    ///     control.resolve_files(Path::new("water")).unwrap();
+    ///     let geometry = control.coord().unwrap();
+    ///     Ok(())
+    ///  }
+    ///  ```
+    pub fn new<I: BufRead>(file_handle: &mut I) -> Result<Self, ParseTurbomoleError> {
+        Ok(Self {
+            groups: parse_data_groups(file_handle)?,
+        })
+    }
+
+    /// Reads the content of all data groups with a `file=` option from the referenced files.
+    /// # Arguments
+    ///  * `directory` - The directory containing the `control` file.
+    pub fn resolve_files(&mut self, directory: &Path) -> Result<(), ParseTurbomoleError> {
+        for group in self.groups.iter_mut() {
+            let Some(file_name) = group.option("file") else {
+                continue;
+            };
+            let mut reader = BufReader::new(File::open(directory.join(file_name))?);
+            let referenced = parse_data_groups(&mut reader)?
+                .into_iter()
+                .find(|g| g.name == group.name)
+                .ok_or_else(|| ParseTurbomoleError::MissingGroup(group.name.clone()))?;
+            group.lines = referenced.lines;
+            if !referenced.options.is_empty() {
+                group.options = referenced.options;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a data group.
+    /// # Arguments
+    ///  * `name` - The keyword without `$`.
+    pub fn group(&self, name: &str) -> Result<&DataGroup, ParseTurbomoleError> {
+        self.groups
+            .iter()
+            .find(|g| g.name == name)
+            .ok_or_else(|| ParseTurbomoleError::MissingGroup(name.to_string()))
+    }
+
+    /// Returns the geometry of `$coord` in bohr.
+    pub fn coord(&self) -> Result<Xyz<f64>, ParseTurbomoleError> {
+        coord::parse_coord(self.group("coord")?)
+    }
+
+    /// Returns the cycles of `$grad`.
+    pub fn gradient_cycles(&self) -> Result<Vec<GradientCycle>, ParseTurbomoleError> {
+        grad::parse_grad(self.group("grad")?)
+    }
+
+    /// Returns the orbitals of a MO data group, e.g. `scfmo`, `uhfmo_alpha` or `uhfmo_beta`.
+    /// # Arguments
+    ///  * `name` - The keyword without `$`.
+    pub fn mos(&self, name: &str) -> Result<Vec<TurbomoleMo>, ParseTurbomoleError> {
+        mos::parse_mos(self.group(name)?)
+    }
+
+    /// Returns the MO coefficients of a MO data group with one column per orbital.
+    /// # Arguments
+    ///  * `name` - The keyword without `$`.
+    pub fn mo_coefficients(&self, name: &str) -> Result<DMatrix<f64>, ParseTurbomoleError> {
+        Ok(mos::coefficient_matrix(&self.mos(name)?))
+    }
+}
@@ -0,0 +1,110 @@
+//! Reading of the MO data groups `$scfmo`, `$uhfmo_alpha` and `$uhfmo_beta`. Each orbital starts
+//! with a line holding its index, irreducible representation, eigenvalue and the number of basis
+//! functions, followed by the coefficients in the Fortran format of the keyword line.
+//! ```text
+//! $scfmo    scfconv=7   format(4d20.14)
+//!      1  a1      eigenvalue=-.20556214089508D+02   nsaos=24
+//! 0.99450317616271D+00-.25983587098398D-010.00000000000000D+00-.45231453432553D-02
+//! ```
+use nalgebra::{DMatrix, DVector};
+
+use crate::format_string::{
+    get_formats, parse_fortran_formatted_line, parse_fortran_real, FortranFormat, ParsedValue,
+};
+
+use super::turbomoleerrors::ParseTurbomoleError;
+use super::DataGroup;
+
+/// The format used if the keyword line does not give one.
+const DEFAULT_FORMAT: &str = "(4d20.14)";
+
+/// An orbital of a MO data group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurbomoleMo {
+    /// The index within the irreducible representation.
+    pub index: usize,
+    pub irrep: String,
+    /// The orbital energy in hartree.
+    pub eigenvalue: f64,
+    pub coefficients: DVector<f64>,
+}
+
+/// Returns the Fortran formats given as `format(...)` in the options of a data group.
+fn coefficient_formats(group: &DataGroup) -> Result<Vec<FortranFormat>, ParseTurbomoleError> {
+    let format = group
+        .options
+        .split_whitespace()
+        .find_map(|t| t.strip_prefix("format"))
+        .unwrap_or(DEFAULT_FORMAT);
+    Ok(get_formats(format.to_string())?)
+}
+
+/// Function to read the header line of an orbital.
+fn parse_orbital_header(line: &str) -> Result<(usize, String, f64, usize), ParseTurbomoleError> {
+    let invalid = || ParseTurbomoleError::InvalidLine(line.to_string());
+    let mut tokens = line.split_whitespace();
+    let index = tokens
+        .next()
+        .and_then(|t| t.parse::<usize>().ok())
+        .ok_or_else(invalid)?;
+    let irrep = tokens.next().ok_or_else(invalid)?.to_string();
+    let value_of = |key: &str| {
+        line.split_once(key)
+            .and_then(|(_, rest)| rest.split_whitespace().next())
+            .ok_or_else(invalid)
+    };
+    let eigenvalue = parse_fortran_real::<f64>(value_of("eigenvalue=")?).map_err(|_| invalid())?;
+    let nsaos = value_of("nsaos=")?
+        .parse::<usize>()
+        .map_err(|_| invalid())?;
+    Ok((index, irrep, eigenvalue, nsaos))
+}
+
+/// Function to read all orbitals of a MO data group.
+/// # Arguments
+///  * `group` - The MO data group, e.g. `$scfmo`.
+pub fn parse_mos(group: &DataGroup) -> Result<Vec<TurbomoleMo>, ParseTurbomoleError> {
+    let formats = coefficient_formats(group)?;
+    let mut mos = Vec::new();
+    let mut lines = group.lines.iter();
+    while let Some(line) = lines.next() {
+        let (index, irrep, eigenvalue, nsaos) = parse_orbital_header(line)?;
+        let mut coefficients = Vec::with_capacity(nsaos);
+        while coefficients.len() < nsaos {
+            let l = lines
+                .next()
+                .ok_or_else(|| ParseTurbomoleError::InvalidLine(line.to_string()))?;
+            for value in parse_fortran_formatted_line(l, &formats)?
+                .into_iter()
+                .flatten()
+            {
+                match value {
+                    ParsedValue::Fl(v) => coefficients.push(v),
+                    _ => return Err(ParseTurbomoleError::InvalidLine(l.to_string())),
+                }
+            }
+        }
+        if coefficients.len() != nsaos {
+            return Err(ParseTurbomoleError::InvalidLine(line.to_string()));
+        }
+        mos.push(TurbomoleMo {
+            index,
+            irrep,
+            eigenvalue,
+            coefficients: DVector::from_vec(coefficients),
+        });
+    }
+    Ok(mos)
+}
+
+/// Function to collect the coefficients of orbitals into a matrix with one column per orbital.
+/// # Arguments
+///  * `mos` - The orbitals, all with the same number of basis functions.
+pub fn coefficient_matrix(mos: &[TurbomoleMo]) -> DMatrix<f64> {
+    let columns: Vec<DVector<f64>> = mos.iter().map(|mo| mo.coefficients.clone()).collect();
+    if columns.is_empty() {
+        DMatrix::zeros(0, 0)
+    } else {
+        DMatrix::from_columns(&columns)
+    }
+}
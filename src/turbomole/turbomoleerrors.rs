@@ -0,0 +1,49 @@
+use std::error;
+use std::fmt::Display;
+
+use crate::format_string::frmtstngerror::ParseFortranFormattedError;
+
+#[derive(Debug)]
+pub enum ParseTurbomoleError {
+    /// Raised when the underlying reader or writer fails.
+    Io(std::io::Error),
+    /// Raised when a requested data group is not present.
+    MissingGroup(String),
+    /// Raised when a line of a data group cannot be parsed.
+    InvalidLine(String),
+    /// Raised when Fortran formatted values cannot be parsed.
+    FormatError(ParseFortranFormattedError),
+}
+
+impl Display for ParseTurbomoleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Could not read the Turbomole file: {}", e),
+            Self::MissingGroup(name) => write!(f, "The data group '${}' is missing.", name),
+            Self::InvalidLine(line) => write!(f, "Could not parse the line '{}'.", line),
+            Self::FormatError(e) => write!(f, "Could not parse formatted values: {}", e),
+        }
+    }
+}
+
+impl error::Error for ParseTurbomoleError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::FormatError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseTurbomoleError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<ParseFortranFormattedError> for ParseTurbomoleError {
+    fn from(value: ParseFortranFormattedError) -> Self {
+        Self::FormatError(value)
+    }
+}
@@ -0,0 +1,489 @@
+//! This module provides a reader and a writer for VASP POSCAR and CONTCAR files and a reader for
+//! XDATCAR trajectories, see [`xdatcar`]. A POSCAR file holds a comment, the scaling, the lattice
+//! vectors, the species and their counts followed by the positions and, in CONTCAR files, the
+//! velocities:
+//! ```text
+//! Si2 cubic diamond
+//!    5.43
+//!      0.0    0.5    0.5
+//!      0.5    0.0    0.5
+//!      0.5    0.5    0.0
+//!    Si
+//!     2
+//! Selective dynamics
+//! Direct
+//!   0.00  0.00  0.00  F F F
+//!   0.25  0.25  0.25  T T T
+//! ```
+//! The lattice vectors are the rows of the lattice matrix, Cartesian coordinates are given in
+//! angstrom.
+use nalgebra::{Matrix3, Point3, Vector3};
+use std::io::{BufRead, Write};
+
+use crate::common::{cartesian_to_fractional, fractional_to_cartesian};
+use crate::format_string::format_fortran_real;
+use crate::xyz::xyzline::symbol::XYZLineSymbol;
+use crate::xyz::{Xyz, XyzLine};
+
+use self::vasperrors::ParseVaspError;
+pub mod vasperrors;
+pub mod xdatcar;
+
+/// The scaling of the lattice vectors and Cartesian coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scaling {
+    /// A universal scaling factor.
+    Factor(f64),
+    /// The volume of the cell in cubic angstrom, given as negative number in the file.
+    Volume(f64),
+    /// Scaling factors of the x, y and z components.
+    Axes([f64; 3]),
+}
+
+impl Scaling {
+    /// Function to parse the scaling line.
+    fn parse(line: &str) -> Result<Self, ParseVaspError> {
+        let values = parse_reals(line)?;
+        match values.as_slice() {
+            [v] if *v < 0.0 => Ok(Self::Volume(-v)),
+            [v] => Ok(Self::Factor(*v)),
+            [x, y, z] => Ok(Self::Axes([*x, *y, *z])),
+            _ => Err(ParseVaspError::InvalidLine(line.to_string())),
+        }
+    }
+
+    /// The scaling factors of the x, y and z components.
+    /// # Arguments
+    ///  * `lattice` - The unscaled lattice vectors as rows.
+    pub fn factors(&self, lattice: &Matrix3<f64>) -> Vector3<f64> {
+        match self {
+            Self::Factor(f) => Vector3::repeat(*f),
+            Self::Volume(v) => Vector3::repeat((v / lattice.determinant().abs()).cbrt()),
+            Self::Axes(f) => Vector3::from(*f),
+        }
+    }
+
+    /// Function to write the scaling line.
+    fn write<W: Write>(&self, dest: &mut W) -> Result<(), ParseVaspError> {
+        match self {
+            Self::Factor(f) => writeln!(dest, "{:19.14}", f)?,
+            Self::Volume(v) => writeln!(dest, "{:19.14}", -v)?,
+            Self::Axes(f) => writeln!(dest, "{:19.14}{:19.14}{:19.14}", f[0], f[1], f[2])?,
+        }
+        Ok(())
+    }
+}
+
+/// Whether positions are given as fractional or as Cartesian coordinates.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CoordinateMode {
+    #[default]
+    Direct,
+    Cartesian,
+}
+
+impl CoordinateMode {
+    /// Function to parse a mode line, which VASP identifies by its first character.
+    fn parse(line: &str) -> Self {
+        match line.trim_start().chars().next() {
+            Some('c' | 'C' | 'k' | 'K') => Self::Cartesian,
+            _ => Self::Direct,
+        }
+    }
+
+    /// The keyword of the mode.
+    fn keyword(&self) -> &'static str {
+        match self {
+            Self::Direct => "Direct",
+            Self::Cartesian => "Cartesian",
+        }
+    }
+}
+
+/// Function to parse all tokens of a line as reals.
+fn parse_reals(line: &str) -> Result<Vec<f64>, ParseVaspError> {
+    line.split_whitespace()
+        .map(|t| t.parse::<f64>())
+        .collect::<Result<Vec<f64>, _>>()
+        .map_err(|_| ParseVaspError::InvalidLine(line.to_string()))
+}
+
+/// Function to parse the first three tokens of a line as vector.
+fn parse_vector(line: &str) -> Result<Vector3<f64>, ParseVaspError> {
+    let values = line
+        .split_whitespace()
+        .take(3)
+        .map(|t| t.parse::<f64>())
+        .collect::<Result<Vec<f64>, _>>()
+        .map_err(|_| ParseVaspError::InvalidLine(line.to_string()))?;
+    if values.len() != 3 {
+        return Err(ParseVaspError::InvalidLine(line.to_string()));
+    }
+    Ok(Vector3::new(values[0], values[1], values[2]))
+}
+
+/// Returns the next line or an error at the end of the file.
+fn next_line<L>(lines: &mut L) -> Result<String, ParseVaspError>
+where
+    L: Iterator<Item = std::io::Result<String>>,
+{
+    Ok(lines.next().ok_or(ParseVaspError::UnexpectedEof)??)
+}
+
+/// The header shared by POSCAR and XDATCAR files.
+#[derive(Debug, Clone, PartialEq)]
+struct Header {
+    comment: String,
+    scaling: Scaling,
+    lattice: Matrix3<f64>,
+    species: Vec<String>,
+    counts: Vec<usize>,
+}
+
+impl Header {
+    /// Function to parse the header after its comment line. Returns the header and the line
+    /// following the counts.
+    fn parse<L>(comment: String, lines: &mut L) -> Result<(Self, String), ParseVaspError>
+    where
+        L: Iterator<Item = std::io::Result<String>>,
+    {
+        let scaling = Scaling::parse(&next_line(lines)?)?;
+        let mut rows = Vec::with_capacity(3);
+        for _ in 0..3 {
+            rows.push(parse_vector(&next_line(lines)?)?.transpose());
+        }
+        let lattice = Matrix3::from_rows(&rows);
+        let mut line = next_line(lines)?;
+        // Files written by VASP 4 lack the species line.
+        let species = if line.split_whitespace().all(|t| t.parse::<usize>().is_ok()) {
+            Vec::new()
+        } else {
+            let species = line
+                .split_whitespace()
+                .map(|t| t.split('/').next().unwrap_or_default().to_string())
+                .collect();
+            line = next_line(lines)?;
+            species
+        };
+        let counts = line
+            .split_whitespace()
+            .map(|t| t.parse::<usize>())
+            .collect::<Result<Vec<usize>, _>>()
+            .map_err(|_| ParseVaspError::InvalidLine(line.clone()))?;
+        if counts.is_empty() || (!species.is_empty() && species.len() != counts.len()) {
+            return Err(ParseVaspError::InvalidLine(line));
+        }
+        let header = Self {
+            comment: comment.trim().to_string(),
+            scaling,
+            lattice,
+            species,
+            counts,
+        };
+        Ok((header, next_line(lines)?))
+    }
+
+    /// The scaled lattice vectors as rows.
+    fn cell(&self) -> Matrix3<f64> {
+        let factors = self.scaling.factors(&self.lattice);
+        let mut cell = self.lattice;
+        for (mut column, factor) in cell.column_iter_mut().zip(factors.iter()) {
+            column *= *factor;
+        }
+        cell
+    }
+
+    /// The element symbols of all atoms, lowercase. Empty if the species are not given.
+    fn symbols(&self) -> Vec<String> {
+        self.species
+            .iter()
+            .zip(self.counts.iter())
+            .flat_map(|(s, &n)| std::iter::repeat_n(s.to_lowercase(), n))
+            .collect()
+    }
+
+    /// Function to write the header up to the counts line.
+    fn write<W: Write>(&self, dest: &mut W) -> Result<(), ParseVaspError> {
+        writeln!(dest, "{}", self.comment)?;
+        self.scaling.write(dest)?;
+        for row in self.lattice.row_iter() {
+            writeln!(dest, "{:22.16}{:22.16}{:22.16}", row[0], row[1], row[2])?;
+        }
+        if !self.species.is_empty() {
+            for species in self.species.iter() {
+                write!(dest, "{:>5}", species)?;
+            }
+            writeln!(dest)?;
+        }
+        for count in self.counts.iter() {
+            write!(dest, "{:6}", count)?;
+        }
+        writeln!(dest)?;
+        Ok(())
+    }
+}
+
+/// Represents a POSCAR or CONTCAR file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Poscar {
+    pub comment: String,
+    pub scaling: Scaling,
+    /// The unscaled lattice vectors as rows.
+    pub lattice: Matrix3<f64>,
+    /// The species, empty for files of VASP 4 without species line.
+    pub species: Vec<String>,
+    /// Number of atoms per species.
+    pub counts: Vec<usize>,
+    /// Whether the x, y and z coordinates of each atom may relax, if selective dynamics is on.
+    pub selective_dynamics: Option<Vec<[bool; 3]>>,
+    pub coordinate_mode: CoordinateMode,
+    /// The positions as given in the file, i.e. fractional or unscaled Cartesian coordinates.
+    pub positions: Vec<Point3<f64>>,
+    /// The velocities as given in the file, in angstrom per femtosecond.
+    pub velocities: Option<Vec<Vector3<f64>>>,
+}
+
+impl Poscar {
+    /// Constructor for a Poscar struct. The predictor-corrector block of CONTCAR files is
+    /// skipped.
+    /// # Arguments:
+    ///  * `file_handle` - A handle to a `BufReader`, i.e. the POSCAR or CONTCAR file.
+    ///
+    ///  # Example:
+    ///
+    /// ```no_run
+    ///  use std::fs::File;
+    ///  use std::io::BufReader;
+    ///  use qc_file_parsers::vasp::Poscar;
+    ///  fn main() -> std::io::Result<()> {
+    ///     let f = File::open("CONTCAR")?;
+    ///     let mut b = BufReader::new(f);
+    ///     let poscar = Poscar::new(&mut b).unwrap();
+    ///     let cell = poscar.cell();
+    ///     Ok(())
+    ///  }
+    ///  ```
+    pub fn new<I: BufRead>(file_handle: &mut I) -> Result<Self, ParseVaspError> {
+        let mut lines = file_handle.lines();
+        let comment = next_line(&mut lines)?;
+        let (header, mut line) = Header::parse(comment, &mut lines)?;
+        let natoms: usize = header.counts.iter().sum();
+        let selective = line.trim_start().starts_with(['s', 'S']);
+        if selective {
+            line = next_line(&mut lines)?;
+        }
+        let coordinate_mode = CoordinateMode::parse(&line);
+        let mut positions = Vec::with_capacity(natoms);
+        let mut flags = Vec::with_capacity(natoms);
+        for _ in 0..natoms {
+            let line = next_line(&mut lines)?;
+            positions.push(Point3::from(parse_vector(&line)?));
+            if selective {
+                let invalid = || ParseVaspError::InvalidLine(line.clone());
+                let tokens: Vec<&str> = line.split_whitespace().skip(3).take(3).collect();
+                let mut flag = [true; 3];
+                if tokens.len() != 3 {
+                    return Err(invalid());
+                }
+                for (f, t) in flag.iter_mut().zip(tokens) {
+                    *f = match t {
+                        "T" | "t" => true,
+                        "F" | "f" => false,
+                        _ => return Err(invalid()),
+                    };
+                }
+                flags.push(flag);
+            }
+        }
+        let rest = lines.collect::<Result<Vec<String>, _>>()?;
+        let mut rest = rest.iter().skip_while(|l| l.trim().is_empty()).peekable();
+        // The velocities may be preceded by their mode, which is always Cartesian.
+        rest.next_if(|l| {
+            l.split_whitespace()
+                .next()
+                .is_some_and(|t| t.parse::<f64>().is_err())
+        });
+        let velocities = if rest.peek().is_some() {
+            let mut velocities = Vec::with_capacity(natoms);
+            for _ in 0..natoms {
+                let line = rest.next().ok_or(ParseVaspError::UnexpectedEof)?;
+                velocities.push(parse_vector(line)?);
+            }
+            Some(velocities)
+        } else {
+            None
+        };
+        Ok(Self {
+            comment: header.comment,
+            scaling: header.scaling,
+            lattice: header.lattice,
+            species: header.species,
+            counts: header.counts,
+            selective_dynamics: if selective { Some(flags) } else { None },
+            coordinate_mode,
+            positions,
+            velocities,
+        })
+    }
+
+    /// The header of the file.
+    fn header(&self) -> Header {
+        Header {
+            comment: self.comment.clone(),
+            scaling: self.scaling,
+            lattice: self.lattice,
+            species: self.species.clone(),
+            counts: self.counts.clone(),
+        }
+    }
+
+    /// The scaled lattice vectors as rows, in angstrom.
+    pub fn cell(&self) -> Matrix3<f64> {
+        self.header().cell()
+    }
+
+    /// The volume of the cell in cubic angstrom.
+    pub fn volume(&self) -> f64 {
+        self.cell().determinant().abs()
+    }
+
+    /// The fractional coordinates of all atoms, `None` if Cartesian coordinates are given in a
+    /// singular cell.
+    pub fn fractional_positions(&self) -> Option<Vec<Point3<f64>>> {
+        match self.coordinate_mode {
+            CoordinateMode::Direct => Some(self.positions.clone()),
+            CoordinateMode::Cartesian => {
+                let cell = self.cell();
+                self.cartesian_positions()
+                    .iter()
+                    .map(|p| cartesian_to_fractional(&cell, p))
+                    .collect()
+            }
+        }
+    }
+
+    /// The Cartesian coordinates of all atoms in angstrom.
+    pub fn cartesian_positions(&self) -> Vec<Point3<f64>> {
+        match self.coordinate_mode {
+            CoordinateMode::Direct => {
+                let cell = self.cell();
+                self.positions
+                    .iter()
+                    .map(|p| fractional_to_cartesian(&cell, p))
+                    .collect()
+            }
+            CoordinateMode::Cartesian => {
+                let factors = self.scaling.factors(&self.lattice);
+                self.positions
+                    .iter()
+                    .map(|p| Point3::from(p.coords.component_mul(&factors)))
+                    .collect()
+            }
+        }
+    }
+
+    /// Returns the geometry in angstrom, `None` if the file lacks the species.
+    pub fn geometry(&self) -> Option<Xyz<f64>> {
+        let symbols = self.header().symbols();
+        if symbols.is_empty() {
+            return None;
+        }
+        let lines: Vec<XyzLine<f64>> = symbols
+            .into_iter()
+            .zip(self.cartesian_positions())
+            .map(|(symbol, xyz)| XyzLine::Symbolic(XYZLineSymbol { symbol, xyz }))
+            .collect();
+        Some(Xyz {
+            number_of_atoms: lines.len(),
+            distances_in: "ang".to_string(),
+            info_line: self.comment.clone(),
+            lines,
+        })
+    }
+
+    /// Constructor for a Poscar struct with Cartesian coordinates from a geometry. The atoms are
+    /// grouped by species in the order of their first occurrence.
+    /// # Arguments:
+    ///  * `geometry` - The geometry, in bohr or angstrom.
+    ///  * `cell` - The lattice vectors as rows, in angstrom.
+    pub fn from_xyz(geometry: &Xyz<f64>, cell: Matrix3<f64>) -> Self {
+        let atoms = geometry.labeled_atoms_in_angstrom();
+        let mut species: Vec<String> = Vec::new();
+        for (element, _) in atoms.iter() {
+            if !species.contains(element) {
+                species.push(element.clone());
+            }
+        }
+        let mut counts = Vec::with_capacity(species.len());
+        let mut positions = Vec::with_capacity(atoms.len());
+        for s in species.iter() {
+            let members: Vec<Point3<f64>> = atoms
+                .iter()
+                .filter(|(element, _)| element == s)
+                .map(|(_, xyz)| *xyz)
+                .collect();
+            counts.push(members.len());
+            positions.extend(members);
+        }
+        Self {
+            comment: geometry.info_line.trim().to_string(),
+            scaling: Scaling::Factor(1.0),
+            lattice: cell,
+            species,
+            counts,
+            selective_dynamics: None,
+            coordinate_mode: CoordinateMode::Cartesian,
+            positions,
+            velocities: None,
+        }
+    }
+
+    /// Writes the POSCAR file.
+    /// # Arguments
+    ///  * `dest` - Where to write to.
+    pub fn write<W: Write>(&self, dest: &mut W) -> Result<(), ParseVaspError> {
+        self.header().write(dest)?;
+        if self.selective_dynamics.is_some() {
+            writeln!(dest, "Selective dynamics")?;
+        }
+        writeln!(dest, "{}", self.coordinate_mode.keyword())?;
+        for (i, p) in self.positions.iter().enumerate() {
+            write!(dest, "{:20.16}{:20.16}{:20.16}", p.x, p.y, p.z)?;
+            if let Some(flags) = self.selective_dynamics.as_ref().and_then(|f| f.get(i)) {
+                for flag in flags {
+                    write!(dest, " {}", if *flag { 'T' } else { 'F' })?;
+                }
+            }
+            writeln!(dest)?;
+        }
+        if let Some(velocities) = self.velocities.as_ref() {
+            writeln!(dest)?;
+            for v in velocities {
+                writeln!(
+                    dest,
+                    "{}{}{}",
+                    format_fortran_real(v.x, 16, 8, 'E'),
+                    format_fortran_real(v.y, 16, 8, 'E'),
+                    format_fortran_real(v.z, 16, 8, 'E')
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scaling() {
+        let lattice = Matrix3::new(0.0, 0.5, 0.5, 0.5, 0.0, 0.5, 0.5, 0.5, 0.0);
+        assert_eq!(Scaling::parse(" 5.43").unwrap(), Scaling::Factor(5.43));
+        let volume = Scaling::parse("-40.0").unwrap();
+        assert_eq!(volume, Scaling::Volume(40.0));
+        let factors = volume.factors(&lattice);
+        assert!(((lattice * factors[0]).determinant().abs() - 40.0).abs() < 1e-10);
+        assert!(Scaling::parse("1.0 2.0").is_err());
+    }
+}
@@ -0,0 +1,37 @@
+use std::error;
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum ParseVaspError {
+    /// Raised when the underlying reader or writer fails.
+    Io(std::io::Error),
+    /// Raised when the file ends within the header or a block of positions.
+    UnexpectedEof,
+    /// Raised when a line cannot be parsed.
+    InvalidLine(String),
+}
+
+impl Display for ParseVaspError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Could not read the VASP file: {}", e),
+            Self::UnexpectedEof => write!(f, "The file ended prematurely."),
+            Self::InvalidLine(line) => write!(f, "Could not parse the line '{}'.", line),
+        }
+    }
+}
+
+impl error::Error for ParseVaspError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseVaspError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
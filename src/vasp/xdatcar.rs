@@ -0,0 +1,175 @@
+//! Reading of VASP XDATCAR trajectories. The header of a POSCAR file is followed by one block
+//! of positions per frame:
+//! ```text
+//! Direct configuration=     1
+//!    0.00000000  0.00000000  0.00000000
+//!    0.25000000  0.25000000  0.25000000
+//! Direct configuration=     2
+//! ...
+//! ```
+//! Runs with a variable cell repeat the header before every frame.
+use nalgebra::{Matrix3, Point3};
+use std::io::{BufRead, Lines};
+
+use crate::common::{cartesian_to_fractional, fractional_to_cartesian};
+use crate::xyz::xyzline::symbol::XYZLineSymbol;
+use crate::xyz::{Xyz, XyzLine};
+
+use super::vasperrors::ParseVaspError;
+use super::{next_line, parse_vector, CoordinateMode, Header};
+
+/// A frame of an XDATCAR trajectory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct XdatcarFrame {
+    /// The ionic step given in the configuration line.
+    pub step: usize,
+    /// The scaled lattice vectors as rows, in angstrom.
+    pub cell: Matrix3<f64>,
+    pub species: Vec<String>,
+    pub counts: Vec<usize>,
+    /// The fractional coordinates of all atoms.
+    pub positions: Vec<Point3<f64>>,
+}
+
+impl XdatcarFrame {
+    /// The Cartesian coordinates of all atoms in angstrom.
+    pub fn cartesian_positions(&self) -> Vec<Point3<f64>> {
+        self.positions
+            .iter()
+            .map(|p| fractional_to_cartesian(&self.cell, p))
+            .collect()
+    }
+
+    /// Returns the geometry in angstrom, `None` if the file lacks the species.
+    pub fn geometry(&self) -> Option<Xyz<f64>> {
+        if self.species.is_empty() {
+            return None;
+        }
+        let lines: Vec<XyzLine<f64>> = self
+            .species
+            .iter()
+            .zip(self.counts.iter())
+            .flat_map(|(s, &n)| std::iter::repeat_n(s.to_lowercase(), n))
+            .zip(self.cartesian_positions())
+            .map(|(symbol, xyz)| XyzLine::Symbolic(XYZLineSymbol { symbol, xyz }))
+            .collect();
+        Some(Xyz {
+            number_of_atoms: lines.len(),
+            distances_in: "ang".to_string(),
+            info_line: format!("configuration {}", self.step),
+            lines,
+        })
+    }
+}
+
+/// Iterator over the frames of an XDATCAR file, reading one frame at a time.
+pub struct XdatcarReader<I: BufRead> {
+    lines: Lines<I>,
+    header: Header,
+    /// A line read ahead, i.e. the configuration line after a header.
+    pending: Option<String>,
+}
+
+impl<I: BufRead> XdatcarReader<I> {
+    /// Constructor for an XdatcarReader, which reads the header.
+    /// # Arguments:
+    ///  * `file_handle` - A `BufReader` of the XDATCAR file.
+    ///
+    ///  # Example:
+    ///
+    /// ```no_run
+    ///  use std::fs::File;
+    ///  use std::io::BufReader;
+    ///  use qc_file_parsers::vasp::xdatcar::XdatcarReader;
+    ///  fn main() -> std::io::Result<()> {
+    ///     let f = File::open("XDATCAR")?;
+    ///     let reader = XdatcarReader::new(BufReader::new(f)).unwrap();
+    ///     for frame in reader {
+    ///         let geometry = frame.unwrap().geometry();
+    ///     }
+    ///     Ok(())
+    ///  }
+    ///  ```
+    pub fn new(file_handle: I) -> Result<Self, ParseVaspError> {
+        let mut lines = file_handle.lines();
+        let comment = next_line(&mut lines)?;
+        let (header, line) = Header::parse(comment, &mut lines)?;
+        Ok(Self {
+            lines,
+            header,
+            pending: Some(line),
+        })
+    }
+
+    /// The comment of the latest header.
+    pub fn comment(&self) -> &str {
+        &self.header.comment
+    }
+
+    /// Function to read the frame after its configuration line.
+    fn read_frame(&mut self, configuration: &str) -> Result<XdatcarFrame, ParseVaspError> {
+        let step = configuration
+            .rsplit(['=', ' '])
+            .find(|t| !t.is_empty())
+            .and_then(|t| t.parse::<usize>().ok())
+            .ok_or_else(|| ParseVaspError::InvalidLine(configuration.to_string()))?;
+        let natoms: usize = self.header.counts.iter().sum();
+        let cell = self.header.cell();
+        let factors = self.header.scaling.factors(&self.header.lattice);
+        let mode = CoordinateMode::parse(configuration);
+        let mut positions = Vec::with_capacity(natoms);
+        for _ in 0..natoms {
+            let line = next_line(&mut self.lines)?;
+            let position = Point3::from(parse_vector(&line)?);
+            positions.push(match mode {
+                CoordinateMode::Direct => position,
+                CoordinateMode::Cartesian => cartesian_to_fractional(
+                    &cell,
+                    &Point3::from(position.coords.component_mul(&factors)),
+                )
+                .ok_or(ParseVaspError::InvalidLine(line))?,
+            });
+        }
+        Ok(XdatcarFrame {
+            step,
+            cell,
+            species: self.header.species.clone(),
+            counts: self.header.counts.clone(),
+            positions,
+        })
+    }
+}
+
+/// Whether a line starts a frame.
+fn is_configuration(line: &str) -> bool {
+    let line = line.trim_start().to_lowercase();
+    (line.starts_with("direct") || line.starts_with("cartesian")) && line.contains("configuration")
+}
+
+impl<I: BufRead> Iterator for XdatcarReader<I> {
+    type Item = Result<XdatcarFrame, ParseVaspError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut line = match self.pending.take() {
+            Some(line) => line,
+            None => loop {
+                match self.lines.next()? {
+                    Ok(l) if l.trim().is_empty() => continue,
+                    Ok(l) => break l,
+                    Err(e) => return Some(Err(e.into())),
+                }
+            },
+        };
+        if !is_configuration(&line) {
+            // A variable cell run repeats the header, starting with the comment.
+            match Header::parse(line, &mut self.lines) {
+                Ok((header, configuration)) => {
+                    self.header = header;
+                    line = configuration;
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        Some(self.read_frame(&line))
+    }
+}
@@ -1,10 +1,14 @@
 use std::io::BufRead;
+use nalgebra::Point3;
 use num::Float;
 pub mod xyzerrors;
 pub mod xyzline;
 
 use crate::xyz::xyzline::{numeric::XYZLineNumeric, symbol::XYZLineSymbol};
 
+/// Conversion factor from bohr to angstrom.
+pub const BOHR_IN_ANGSTROM: f64 = 0.529177210903;
+
 /// Enum to wrap lines in a xyz file starting with a numeric or a symbolic line, i.e. either
 /// element symbol or atomic number.
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -44,7 +48,7 @@ where
     /// # Arguments:
     /// * `file_handle` - A handle to a `BufReader`, i.e. the xyz file.
     ///  * `distances_in` - The unit in which the components of the xyz-vectors are given. 'bohr' ===
-    ///  `bohr`or 'angstroem' === `ang`
+    ///    `bohr`or 'angstroem' === `ang`
     ///  
     ///  # Example:
     ///
@@ -101,3 +105,36 @@ where
         })
     }
 }
+
+impl Xyz<f64> {
+    /// Returns the capitalised element symbol, e.g. `Cl`, and the position in angstrom of each
+    /// atom, as written by most other file formats. Unknown atomic numbers are labeled `X`.
+    pub fn labeled_atoms_in_angstrom(&self) -> Vec<(String, Point3<f64>)> {
+        let scale = if self.distances_in.to_lowercase().starts_with("bohr") {
+            BOHR_IN_ANGSTROM
+        } else {
+            1.0
+        };
+        self.lines
+            .iter()
+            .map(|line| {
+                let atom = match line {
+                    XyzLine::Symbolic(atom) => atom.clone(),
+                    XyzLine::Numeric(atom) => XYZLineSymbol::from(atom.clone()),
+                };
+                (element_label(&atom.symbol), atom.xyz * scale)
+            })
+            .collect()
+    }
+}
+
+/// Function to capitalise an element symbol, e.g. `cl` or `CL` becomes `Cl`.
+/// # Arguments
+///  * `symbol` - The element symbol.
+pub fn element_label(symbol: &str) -> String {
+    let mut chars = symbol.chars();
+    chars
+        .next()
+        .map(|c| c.to_ascii_uppercase().to_string() + &chars.as_str().to_lowercase())
+        .unwrap_or_default()
+}
@@ -9,7 +9,17 @@ use std::fmt::Debug;
 use std::cmp::PartialEq;
 
 
-pub const PSE_SYMBOLS: [&str; 11] = ["", "h", "he", "li", "be", "b", "c", "n", "o", "f", "ne"];
+/// Lowercase element symbols indexed by the atomic number.
+pub const PSE_SYMBOLS: [&str; 119] = [
+    "", "h", "he", "li", "be", "b", "c", "n", "o", "f", "ne", "na", "mg", "al", "si", "p", "s",
+    "cl", "ar", "k", "ca", "sc", "ti", "v", "cr", "mn", "fe", "co", "ni", "cu", "zn", "ga", "ge",
+    "as", "se", "br", "kr", "rb", "sr", "y", "zr", "nb", "mo", "tc", "ru", "rh", "pd", "ag", "cd",
+    "in", "sn", "sb", "te", "i", "xe", "cs", "ba", "la", "ce", "pr", "nd", "pm", "sm", "eu", "gd",
+    "tb", "dy", "ho", "er", "tm", "yb", "lu", "hf", "ta", "w", "re", "os", "ir", "pt", "au", "hg",
+    "tl", "pb", "bi", "po", "at", "rn", "fr", "ra", "ac", "th", "pa", "u", "np", "pu", "am", "cm",
+    "bk", "cf", "es", "fm", "md", "no", "lr", "rf", "db", "sg", "bh", "hs", "mt", "ds", "rg", "cn",
+    "nh", "fl", "mc", "lv", "ts", "og",
+];
 
 
 /// Represents a line in an xyz file containing an element symbol and a triple of cartesian
@@ -85,7 +95,8 @@ where
 {
     fn from(value: XYZLineNumeric<T>) -> Self {
         Self {
-            symbol: PSE_SYMBOLS[value.z_value].to_string(),
+            // Atomic numbers beyond the periodic table become dummy atoms.
+            symbol: PSE_SYMBOLS.get(value.z_value).unwrap_or(&"x").to_string(),
             xyz: value.xyz,
         }
     }
@@ -100,4 +111,19 @@ mod tests {
         let expected: XYZLineSymbol<f32> = XYZLineSymbol::from("he 0.0 0.0 0.0".to_string());
         assert_eq!(expected.xyz, XYZLineSymbol::from(test).xyz);
     }
+
+    #[test]
+    fn test_from_numeric_heavy_elements() {
+        let iron: XYZLineNumeric<f64> = XYZLineNumeric::from("26 0.0 0.0 0.0".to_string());
+        assert_eq!(XYZLineSymbol::from(iron).symbol, "fe");
+        let oganesson: XYZLineNumeric<f64> = XYZLineNumeric::from("118 0.0 0.0 0.0".to_string());
+        assert_eq!(XYZLineSymbol::from(oganesson).symbol, "og");
+        assert_eq!(PSE_SYMBOLS[11], "na");
+    }
+
+    #[test]
+    fn test_from_numeric_unknown_element() {
+        let unknown: XYZLineNumeric<f64> = XYZLineNumeric::from("119 0.0 0.0 0.0".to_string());
+        assert_eq!(XYZLineSymbol::from(unknown).symbol, "x");
+    }
 }
@@ -17,3 +17,124 @@ pub fn setup_allene_fortran_format_string() -> Result<BufReader<File>> {
     let test_file = File::open("tests/test_allene_fortran_format.dat")?;
     Ok(BufReader::new(test_file))
 }
+
+pub fn setup_array_text() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_array_text.dat")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_water_fchk() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_water.fchk")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_water_cube() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_water.cube")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_water_molden() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_water.molden")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_water_psi4() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_water_psi4.out")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_water_orca() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_water_orca.out")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_water_hess() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_water.hess")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_water_gaussian_log() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_water_gaussian.log")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_water_zmat() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_water.zmat")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_genbas() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_genbas")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_water_fcm() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_water.fcm")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_water_grd() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_water.grd")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_turbomole_control() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/turbomole/control")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_h2_fcidump() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_h2.fcidump")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_sto3g_gbs() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_sto3g.gbs")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_lanl2dz_gbs() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_lanl2dz.gbs")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_ccpvdz_nwchem() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_ccpvdz.nw")?;
+    Ok(BufReader::new(test_file))
+}
+
+#[cfg(feature = "bse")]
+pub fn setup_sto3g_bse() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_sto3g_bse.json")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_water_pdb() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_water.pdb")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_formate_sdf() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_formate.sdf")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_ligands_mol2() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_ligands.mol2")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_vasp_contcar() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/vasp/CONTCAR")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_vasp_xdatcar() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/vasp/XDATCAR")?;
+    Ok(BufReader::new(test_file))
+}
+
+pub fn setup_nacl_cif() -> Result<BufReader<File>> {
+    let test_file = File::open("tests/test_nacl.cif")?;
+    Ok(BufReader::new(test_file))
+}
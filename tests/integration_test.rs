@@ -2,10 +2,40 @@ mod file_setup;
 #[cfg(test)]
 mod tests {
     use crate::file_setup;
-    use nalgebra::Point3;
+    use nalgebra::{DMatrix, Point3};
+    use qc_file_parsers::array_text::writer::{write_matrix, TextFormat};
+    use qc_file_parsers::array_text::{parse_text_into_matrix, MatrixLayout};
+    #[cfg(feature = "bse")]
+    use qc_file_parsers::basis::bse::{parse_bse_json, write_bse_json};
+    use qc_file_parsers::basis::gaussian94::{parse_gaussian94, write_gaussian94, write_gbs};
+    use qc_file_parsers::basis::nwchem::{parse_nwchem, write_nwchem};
+    use qc_file_parsers::basis::FunctionType;
+    use qc_file_parsers::cfour::fcm::{parse_fcm, write_fcm};
+    use qc_file_parsers::cfour::genbas::{parse_genbas, write_genbas};
+    use qc_file_parsers::cfour::grd::CfourGradient;
+    use qc_file_parsers::cfour::zmat::{Zmat, ZmatGeometry};
+    use qc_file_parsers::cif::crystal::Crystal;
+    use qc_file_parsers::cif::{parse_cif_number, Cif};
+    use qc_file_parsers::cube::Cube;
+    use qc_file_parsers::fchk::{Fchk, FchkValue, Spin};
+    use qc_file_parsers::fcidump::{Fcidump, IntegralStorage};
     use qc_file_parsers::format_string::{parse_fortran_formatted_buf, ParsedValue};
+    use qc_file_parsers::gaussian_log::{GaussianLog, Orientation};
+    use qc_file_parsers::mol2::{parse_mol2, write_mol2, Mol2BondType, Mol2Molecule};
+    use qc_file_parsers::molden::Molden;
+    use qc_file_parsers::molfile::sdf::{write_sdf, SdfReader, SdfRecord};
+    use qc_file_parsers::molfile::{MolBond, Molfile, MolfileVersion};
+    use qc_file_parsers::orca::hess::OrcaHess;
+    use qc_file_parsers::orca::OrcaOutput;
+    use qc_file_parsers::pdb::Pdb;
+    use qc_file_parsers::psi4::Psi4Output;
+    use qc_file_parsers::turbomole::coord::write_coord;
+    use qc_file_parsers::turbomole::{parse_data_groups, Control};
+    use qc_file_parsers::vasp::xdatcar::{XdatcarFrame, XdatcarReader};
+    use qc_file_parsers::vasp::{CoordinateMode, Poscar, Scaling};
+    use qc_file_parsers::xyz::xyzline::numeric::XYZLineNumeric;
     use qc_file_parsers::xyz::xyzline::symbol::XYZLineSymbol;
-    use qc_file_parsers::xyz::{Xyz, XyzLine};
+    use qc_file_parsers::xyz::{element_label, Xyz, XyzLine, BOHR_IN_ANGSTROM};
     #[test]
     fn test_symbolic_constructor() {
         let mut test_file = file_setup::setup_allene_symbolic().unwrap();
@@ -35,6 +65,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_labeled_atoms_in_angstrom() {
+        let mut test_file = file_setup::setup_acetaldehyde_numeric().unwrap();
+        let test_parsed: Xyz<f64> = Xyz::new(&mut test_file, "bohr").unwrap();
+        let XyzLine::Numeric(oxygen) = &test_parsed.lines[2] else {
+            panic!("The acetaldehyde file is numeric.");
+        };
+        let atoms = test_parsed.labeled_atoms_in_angstrom();
+        assert_eq!(atoms.len(), 7);
+        assert_eq!(atoms[2], ("O".to_string(), oxygen.xyz * BOHR_IN_ANGSTROM));
+        assert_eq!(element_label("CL"), "Cl");
+    }
+
     #[test]
     fn test_fortran_format_string() {
         let mut test_file = file_setup::setup_allene_fortran_format_string().unwrap();
@@ -90,4 +133,726 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_array_text() {
+        let mut test_file = file_setup::setup_array_text().unwrap();
+        let test_parsed: DMatrix<f64> =
+            parse_text_into_matrix(&mut test_file, " ", 9, 9, MatrixLayout::RowMajor).unwrap();
+        assert_eq!(test_parsed.shape(), (9, 9));
+        assert_eq!(test_parsed[(0, 0)], 0.0927643390);
+        assert_eq!(test_parsed[(1, 4)], -0.1585663567);
+        assert_eq!(test_parsed, test_parsed.transpose());
+    }
+
+    #[test]
+    fn test_array_text_write() {
+        let mut test_file = file_setup::setup_array_text().unwrap();
+        let test_parsed: DMatrix<f64> =
+            parse_text_into_matrix(&mut test_file, " ", 9, 9, MatrixLayout::RowMajor).unwrap();
+        let format = TextFormat {
+            separator: String::new(),
+            values_per_line: Some(3),
+            ..Default::default()
+        };
+        let mut written: Vec<u8> = Vec::new();
+        write_matrix(&mut written, &test_parsed, &format).unwrap();
+        let expected = std::fs::read_to_string("tests/test_array_text.dat").unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_fchk() {
+        let mut test_file = file_setup::setup_water_fchk().unwrap();
+        let fchk = Fchk::new(&mut test_file).unwrap();
+        assert_eq!(fchk.title, "water single point");
+        assert_eq!(
+            (fchk.job_type.as_str(), fchk.method.as_str(), fchk.basis.as_str()),
+            ("SP", "RHF", "STO-3G")
+        );
+        assert_eq!(fchk.integer("Number of basis functions").unwrap(), 7);
+        assert_eq!(
+            fchk.get("Route").unwrap(),
+            &FchkValue::CharacterArray("#P HF/STO-3G Freq".to_string())
+        );
+        assert_eq!(fchk.real("Total Energy").unwrap(), -74.9659011);
+        let geometry = fchk.geometry().unwrap();
+        assert_eq!(geometry.number_of_atoms, 3);
+        assert_eq!(
+            geometry.lines[1],
+            XyzLine::Numeric(XYZLineNumeric {
+                z_value: 1,
+                xyz: Point3::new(0.0, 1.43996597, -0.88761792)
+            })
+        );
+        let shells = fchk.basis_shells().unwrap();
+        assert_eq!(shells.len(), 4);
+        assert_eq!(shells[1].shell_type, -1);
+        assert_eq!(shells[1].exponents, vec![5.0331513, 1.1695961, 0.380389]);
+        assert_eq!(
+            shells[1].sp_coefficients,
+            Some(vec![0.15591627, 0.60768372, 0.39195739])
+        );
+        assert_eq!(shells[3].atom, 2);
+        assert_eq!(fchk.mo_coefficients(Spin::Alpha).unwrap().shape(), (7, 7));
+        assert_eq!(fchk.mo_energies(Spin::Alpha).unwrap()[0], -20.2516);
+        assert!(fchk.mo_energies(Spin::Beta).is_err());
+        let density = fchk.total_scf_density().unwrap();
+        assert_eq!(density[(0, 0)], 2.1);
+        assert_eq!(density, density.transpose());
+        let gradient = fchk.gradient().unwrap();
+        assert_eq!(gradient.shape(), (3, 3));
+        assert_eq!(gradient[(2, 2)], 0.00615);
+        let hessian = fchk.force_constants().unwrap();
+        assert_eq!(hessian.shape(), (9, 9));
+        assert_eq!(hessian[(8, 8)], 0.58);
+    }
+
+    #[test]
+    fn test_cube() {
+        let mut test_file = file_setup::setup_water_cube().unwrap();
+        let cube = Cube::new(&mut test_file).unwrap();
+        assert_eq!(cube.comment, "SCF Total Density");
+        assert_eq!(cube.shape(), Some([3, 3, 4]));
+        assert_eq!(cube.atoms[0].charge, 8.0);
+        assert_eq!(cube.to_xyz().lines[0], XyzLine::Numeric(XYZLineNumeric {
+            z_value: 8,
+            xyz: Point3::new(0.0, 0.0, 0.221904)
+        }));
+        assert_eq!(cube.point(1, 1, 2), Point3::new(0.0, 0.0, 0.0));
+        assert_eq!(cube.grids[0][&[1, 1, 2][..]], 0.5);
+        assert!((cube.integrate()[0] - 2.5364373).abs() < 1e-6);
+        let mut written: Vec<u8> = Vec::new();
+        cube.write(&mut written).unwrap();
+        let reread = Cube::new(&mut written.as_slice()).unwrap();
+        assert_eq!(reread, cube);
+        let difference = cube.difference(&reread).unwrap();
+        assert_eq!(difference.integrate()[0], 0.0);
+    }
+
+    #[test]
+    fn test_molden() {
+        let mut test_file = file_setup::setup_water_molden().unwrap();
+        let molden = Molden::new(&mut test_file).unwrap();
+        assert_eq!(molden.title, "water single point");
+        let geometry = molden.geometry();
+        assert_eq!(geometry.distances_in, "bohr");
+        assert_eq!(
+            geometry.lines[1],
+            XyzLine::Numeric(XYZLineNumeric {
+                z_value: 1,
+                xyz: Point3::new(0.0, 1.43996597, -0.88761792)
+            })
+        );
+        assert_eq!(molden.shells.len(), 4);
+        assert_eq!(molden.shells[1].sp_coefficients.as_ref().unwrap()[2], 0.39195739);
+        assert_eq!(molden.nbasis(), 7);
+        assert_eq!(molden.orbitals[2].symmetry, "1b2");
+        assert_eq!(molden.orbitals[4].occupation, 2.0);
+        let coefficients = molden.mo_coefficients(Spin::Alpha);
+        assert_eq!(coefficients.shape(), (7, 7));
+        assert_eq!(coefficients[(1, 0)], 0.088458);
+        assert_eq!(molden.frequencies, vec![1775.6021, 4113.7211, 4212.1318]);
+        assert_eq!(molden.frequency_coordinates[0].symbol, "o");
+        assert_eq!(molden.normal_modes[2][(1, 2)], 0.43);
+        let mut written: Vec<u8> = Vec::new();
+        molden.write(&mut written).unwrap();
+        assert_eq!(Molden::new(&mut written.as_slice()).unwrap(), molden);
+    }
+
+    #[test]
+    fn test_psi4() {
+        let mut test_file = file_setup::setup_water_psi4().unwrap();
+        let output = Psi4Output::new(&mut test_file).unwrap();
+        assert_eq!(output.jobs.len(), 2);
+        assert!(output.check_convergence().is_ok());
+        let scf = &output.jobs[0];
+        let geometry = scf.geometry().unwrap();
+        assert_eq!(geometry.distances_in, "ang");
+        assert_eq!(
+            geometry.lines[1],
+            XyzLine::Symbolic(XYZLineSymbol {
+                symbol: "h".to_string(),
+                xyz: Point3::new(0.0, -0.759061990794, 0.521953018286)
+            })
+        );
+        assert_eq!(scf.energies[0].method, "DF-RHF");
+        assert_eq!(scf.energies[0].history.len(), 5);
+        assert_eq!(scf.energy("df-rhf"), Some(-76.02663273509006));
+        assert_eq!(scf.energy("DF-MP2"), Some(-76.22824431911533));
+        assert_eq!(scf.mulliken_charges, vec![-0.33581, 0.16790, 0.16790]);
+        assert_eq!(scf.lowdin_charges[0], -0.25743);
+        assert_eq!(scf.gradient.as_ref().unwrap()[(1, 1)], 0.00437042627);
+        let cc = &output.jobs[1];
+        assert_eq!(cc.distances_in, "bohr");
+        assert_eq!(cc.energy("CCSD(T)"), Some(-76.240898842293));
+        let ccsd = cc.energies.iter().find(|e| e.method == "CCSD").unwrap();
+        assert_eq!(ccsd.history.len(), 4);
+        assert_eq!(ccsd.history[3], -0.211256022458121);
+        assert_eq!(cc.frequencies, vec![1775.6345, 4113.3838, 4212.2394]);
+        assert_eq!(cc.irreps, vec!["A1", "A1", "B2"]);
+        assert_eq!(cc.normal_modes[2][(2, 1)], 0.55);
+    }
+
+    #[test]
+    fn test_orca_output() {
+        let mut test_file = file_setup::setup_water_orca().unwrap();
+        let output = OrcaOutput::new(&mut test_file).unwrap();
+        assert_eq!(output.final_energy(), Some(-76.026745411));
+        let trajectory = output.trajectory();
+        assert_eq!(trajectory.len(), 2);
+        assert_eq!(trajectory[0].info_line, "E -76.026632734");
+        assert_eq!(
+            trajectory[1].lines[1],
+            XyzLine::Symbolic(XYZLineSymbol {
+                symbol: "h".to_string(),
+                xyz: Point3::new(0.0, 0.763239, -0.478993)
+            })
+        );
+        assert_eq!(output.mulliken_charges, vec![-0.330244, 0.165122, 0.165122]);
+        assert_eq!(output.loewdin_charges[0], -0.251836);
+        assert_eq!(output.dipole_moment.unwrap().z, 0.80327);
+        assert_eq!(output.excited_states.len(), 2);
+        assert_eq!(output.excited_states[0].oscillator_strength, Some(0.017877172));
+        assert_eq!(output.ir_spectrum.len(), 3);
+        assert_eq!(output.ir_spectrum[2].mode, 8);
+        assert_eq!(output.ir_spectrum[0].intensity, 70.22);
+    }
+
+    #[test]
+    fn test_orca_hess() {
+        let mut test_file = file_setup::setup_water_hess().unwrap();
+        let hess = OrcaHess::new(&mut test_file).unwrap();
+        assert_eq!(hess.hessian.shape(), (9, 9));
+        assert_eq!(hess.hessian, hess.hessian.transpose());
+        assert_eq!(hess.hessian[(8, 6)], -0.055379);
+        assert_eq!(hess.frequencies.as_ref().unwrap()[6], 1638.58);
+        assert_eq!(hess.normal_modes.as_ref().unwrap()[(2, 7)], -0.404425);
+        assert_eq!(hess.masses[0], 15.999);
+        assert_eq!(hess.coordinates[(1, 1)], 1.4423);
+        assert_eq!(hess.geometry().number_of_atoms, 3);
+    }
+
+    #[test]
+    fn test_gaussian_log() {
+        let mut test_file = file_setup::setup_water_gaussian_log().unwrap();
+        let log = GaussianLog::new(&mut test_file).unwrap();
+        assert_eq!(log.jobs.len(), 2);
+        let opt = &log.jobs[0];
+        assert_eq!(opt.route, "#P B3LYP/6-31G(d) Opt");
+        assert_eq!(opt.trajectory(Orientation::Input).len(), 1);
+        let standard = opt.trajectory(Orientation::Standard);
+        assert_eq!(standard.len(), 2);
+        assert_eq!(
+            standard[1].lines[1],
+            XyzLine::Numeric(XYZLineNumeric {
+                z_value: 1,
+                xyz: Point3::new(0.0, 0.763239, -0.478993)
+            })
+        );
+        assert_eq!(opt.scf_energies, vec![-76.4089533056, -76.4089698411]);
+        assert_eq!(opt.convergence.len(), 2);
+        assert_eq!(opt.convergence[1][2].name, "Maximum Displacement");
+        assert!(!opt.convergence[0][0].converged);
+        assert!(opt.convergence[1].iter().all(|c| c.converged));
+        assert_eq!(opt.forces[0][(0, 2)], -0.01152383);
+        let freq = &log.jobs[1];
+        assert_eq!(
+            freq.route,
+            "#P Geom=AllCheck Guess=TCheck SCRF=Check GenChk RB3LYP/6-31G(d) Freq Pop=NBO"
+        );
+        assert_eq!(freq.frequencies, vec![1713.7541, 3727.3814, 3849.1024]);
+        assert_eq!(freq.ir_intensities[0], 75.2391);
+        assert_eq!(freq.normal_modes[1][(2, 1)], 0.58);
+        let thermo = freq.thermochemistry.as_ref().unwrap();
+        assert_eq!(thermo.temperature, 298.15);
+        assert_eq!(thermo.zero_point_correction, 0.021108);
+        assert_eq!(thermo.free_energy, -76.405522);
+        assert_eq!(freq.nbo_charges, vec![-0.91868, 0.45934, 0.45934]);
+        assert_eq!(freq.archive_value("HF"), Some("-76.4089698"));
+        assert_eq!(freq.archive_value("RMSD"), Some("3.551e-09"));
+    }
+
+    #[test]
+    fn test_cfour_zmat() {
+        let mut test_file = file_setup::setup_water_zmat().unwrap();
+        let zmat = Zmat::new(&mut test_file).unwrap();
+        assert_eq!(zmat.title, "water CCSD(T)/cc-pVDZ");
+        match &zmat.geometry {
+            ZmatGeometry::ZMatrix(lines) => {
+                assert_eq!(lines.len(), 3);
+                assert_eq!(
+                    lines[2].references,
+                    vec![(1, "R".to_string()), (2, "A".to_string())]
+                );
+            }
+            ZmatGeometry::Cartesian(_) => panic!("expected a Z-matrix"),
+        }
+        assert_eq!(zmat.variables[1], ("A".to_string(), "104.5".to_string()));
+        assert_eq!(zmat.namelist, "CFOUR");
+        assert_eq!(zmat.keywords.len(), 6);
+        assert_eq!(zmat.keyword("calc"), Some("CCSD(T)"));
+        assert_eq!(zmat.keyword("MEM_UNIT"), Some("GB"));
+        assert!(zmat.extra.is_empty());
+        let mut written: Vec<u8> = Vec::new();
+        zmat.write(&mut written).unwrap();
+        assert_eq!(Zmat::new(&mut written.as_slice()).unwrap(), zmat);
+    }
+
+    #[test]
+    fn test_cfour_genbas() {
+        let mut test_file = file_setup::setup_genbas().unwrap();
+        let entries = parse_genbas(&mut test_file).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].element(), "O");
+        assert_eq!(entries[1].basis(), "STO-3G");
+        assert_eq!(entries[1].shells.len(), 2);
+        assert_eq!(entries[1].shells[0].coefficients.shape(), (3, 2));
+        assert_eq!(entries[1].shells[0].coefficients[(2, 1)], 0.70011547);
+        assert_eq!(entries[1].shells[1].angular_momentum, 1);
+        assert_eq!(entries[0].shells[0].exponents[2], 0.1688554);
+        let mut written: Vec<u8> = Vec::new();
+        write_genbas(&mut written, &entries).unwrap();
+        assert_eq!(parse_genbas(&mut written.as_slice()).unwrap(), entries);
+    }
+
+    #[test]
+    fn test_cfour_fcm() {
+        let mut test_file = file_setup::setup_water_fcm().unwrap();
+        let hessian = parse_fcm(&mut test_file).unwrap();
+        assert_eq!(hessian.shape(), (9, 9));
+        assert_eq!(hessian[(0, 2)], -0.1820627668);
+        assert_eq!(hessian[(8, 8)], 0.3797079395);
+        assert_eq!(hessian, hessian.transpose());
+        let mut written: Vec<u8> = Vec::new();
+        write_fcm(&mut written, &hessian).unwrap();
+        assert_eq!(
+            String::from_utf8(written).unwrap(),
+            std::fs::read_to_string("tests/test_water.fcm").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_cfour_grd() {
+        let mut test_file = file_setup::setup_water_grd().unwrap();
+        let grd = CfourGradient::new(&mut test_file).unwrap();
+        assert_eq!(grd.energy, -76.241289442);
+        assert_eq!(
+            grd.geometry().lines[1],
+            XyzLine::Numeric(XYZLineNumeric {
+                z_value: 1,
+                xyz: Point3::new(0.0, 1.432156, 0.986415)
+            })
+        );
+        assert_eq!(grd.gradient[(2, 1)], -0.0043615087);
+        let mut written: Vec<u8> = Vec::new();
+        grd.write(&mut written).unwrap();
+        assert_eq!(
+            String::from_utf8(written).unwrap(),
+            std::fs::read_to_string("tests/test_water.grd").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_turbomole_control() {
+        let mut test_file = file_setup::setup_turbomole_control().unwrap();
+        let mut control = Control::new(&mut test_file).unwrap();
+        assert_eq!(control.groups.len(), 8);
+        assert_eq!(control.group("coord").unwrap().option("file"), Some("coord"));
+        assert!(control.coord().unwrap().lines.is_empty());
+        control
+            .resolve_files(std::path::Path::new("tests/turbomole"))
+            .unwrap();
+        assert_eq!(control.group("atoms").unwrap().lines.len(), 4);
+        let coord = control.coord().unwrap();
+        assert_eq!(coord.number_of_atoms, 3);
+        assert_eq!(coord.distances_in, "bohr");
+        assert_eq!(
+            coord.lines[2],
+            XyzLine::Symbolic(XYZLineSymbol {
+                symbol: "h".to_string(),
+                xyz: Point3::new(0.0, -1.432156, 0.986415)
+            })
+        );
+        let cycles = control.gradient_cycles().unwrap();
+        assert_eq!(cycles.len(), 2);
+        assert_eq!(cycles[1].cycle, 2);
+        assert_eq!(cycles[1].energy, -74.9659011264);
+        assert_eq!(cycles[0].gradient[(1, 1)], 0.01823450129381);
+        assert_eq!(cycles[1].geometry().lines.len(), 3);
+        let mos = control.mos("scfmo").unwrap();
+        assert_eq!(mos.len(), 3);
+        assert_eq!(mos[1].irrep, "a");
+        assert_eq!(mos[0].eigenvalue, -20.2417549346);
+        let coefficients = control.mo_coefficients("scfmo").unwrap();
+        assert_eq!(coefficients.shape(), (7, 3));
+        assert_eq!(coefficients[(4, 0)], -0.00417);
+        assert_eq!(coefficients[(6, 2)], -0.44929);
+        assert!(control.mos("uhfmo_alpha").is_err());
+        let mut written: Vec<u8> = Vec::new();
+        write_coord(&mut written, &coord).unwrap();
+        assert_eq!(
+            String::from_utf8(written.clone()).unwrap(),
+            std::fs::read_to_string("tests/turbomole/coord")
+                .unwrap()
+                .replace("$user-defined bonds\n", "")
+        );
+        let groups = parse_data_groups(&mut written.as_slice()).unwrap();
+        assert_eq!(groups.len(), 1);
+    }
+
+    #[test]
+    fn test_fcidump() {
+        let mut test_file = file_setup::setup_h2_fcidump().unwrap();
+        let unfolded: Fcidump<f64> = Fcidump::new(&mut test_file, true).unwrap();
+        assert_eq!(unfolded.header.norb, 2);
+        assert_eq!(unfolded.header.nelec, 2);
+        assert_eq!(unfolded.header.orbsym, vec![1, 5]);
+        assert!(!unfolded.header.uhf);
+        assert_eq!(unfolded.core_energy, 0.7137539936876182);
+        assert_eq!(unfolded.one_electron[0][(1, 1)], -0.4718960244306283);
+        let eri = &unfolded.two_electron[0];
+        assert!(matches!(eri.storage, IntegralStorage::Unfolded(_)));
+        assert_eq!(eri.get([0, 0, 1, 1]), 0.664581745036456);
+        assert_eq!(eri.get([0, 1, 1, 0]), 0.1809270275041009);
+        assert_eq!(eri.get([0, 0, 0, 1]), 0.0);
+        let mut test_file = file_setup::setup_h2_fcidump().unwrap();
+        let packed: Fcidump<f64> = Fcidump::new(&mut test_file, false).unwrap();
+        match &packed.two_electron[0].storage {
+            IntegralStorage::Packed(integrals) => assert_eq!(integrals.len(), 4),
+            IntegralStorage::Unfolded(_) => panic!("expected packed integrals"),
+        }
+        assert_eq!(packed.two_electron[0].get([1, 0, 0, 1]), 0.1809270275041009);
+        assert_eq!(packed.two_electron[0].unfolded(2), eri.unfolded(2));
+        let mut written: Vec<u8> = Vec::new();
+        unfolded.write(&mut written, 1e-12).unwrap();
+        let text = String::from_utf8(written.clone()).unwrap();
+        assert_eq!(text.lines().count(), 11);
+        let reread: Fcidump<f64> = Fcidump::new(&mut written.as_slice(), true).unwrap();
+        assert_eq!(reread, unfolded);
+    }
+
+    #[test]
+    fn test_basis_gaussian94() {
+        let mut test_file = file_setup::setup_sto3g_gbs().unwrap();
+        let basis = parse_gaussian94(&mut test_file).unwrap();
+        assert_eq!(basis.elements.len(), 2);
+        let oxygen = basis.element("o").unwrap();
+        assert_eq!(oxygen.shells.len(), 2);
+        let sp = &oxygen.shells[1];
+        assert_eq!(sp.label(), "SP");
+        assert_eq!(sp.function_type, FunctionType::Spherical);
+        assert_eq!(sp.exponents[1], 1.1695961);
+        assert_eq!(sp.coefficients[1][2], 0.39195739);
+        let mut written: Vec<u8> = Vec::new();
+        write_gbs(&mut written, &basis).unwrap();
+        assert_eq!(parse_gaussian94(&mut written.as_slice()).unwrap(), basis);
+
+        let mut test_file = file_setup::setup_lanl2dz_gbs().unwrap();
+        let basis = parse_gaussian94(&mut test_file).unwrap();
+        let sodium = basis.element("Na").unwrap();
+        assert_eq!(sodium.shells.len(), 3);
+        assert_eq!(sodium.shells[0].exponents[1], 0.0556);
+        let ecp = sodium.ecp.as_ref().unwrap();
+        assert_eq!(ecp.core_electrons, 10);
+        assert_eq!(ecp.lmax(), 2);
+        assert_eq!(ecp.potentials[0].angular_momentum, 2);
+        assert_eq!(ecp.potentials[0].r_exponents, vec![1, 2, 2, 2, 2]);
+        assert_eq!(ecp.potentials[2].angular_momentum, 1);
+        assert_eq!(ecp.potentials[2].gaussian_exponents, vec![1257.6533309]);
+        let mut written: Vec<u8> = Vec::new();
+        write_gaussian94(&mut written, &basis).unwrap();
+        assert_eq!(parse_gaussian94(&mut written.as_slice()).unwrap(), basis);
+        let mut written: Vec<u8> = Vec::new();
+        write_nwchem(&mut written, &basis).unwrap();
+        assert_eq!(parse_nwchem(&mut written.as_slice()).unwrap(), basis);
+    }
+
+    #[test]
+    fn test_basis_nwchem() {
+        let mut test_file = file_setup::setup_ccpvdz_nwchem().unwrap();
+        let basis = parse_nwchem(&mut test_file).unwrap();
+        let hydrogen = basis.element("H").unwrap();
+        assert_eq!(hydrogen.shells.len(), 2);
+        assert_eq!(hydrogen.shells[0].coefficients.len(), 2);
+        assert_eq!(hydrogen.shells[0].coefficients[1], vec![0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(hydrogen.shells[1].angular_momentum, vec![1]);
+        let ecp = basis.element("na").unwrap().ecp.as_ref().unwrap();
+        assert_eq!(ecp.potentials[0].angular_momentum, 2);
+        assert_eq!(ecp.potentials[0].coefficients, vec![-10.0, -47.4902024]);
+        let mut written: Vec<u8> = Vec::new();
+        write_nwchem(&mut written, &basis).unwrap();
+        assert_eq!(parse_nwchem(&mut written.as_slice()).unwrap(), basis);
+        let mut written: Vec<u8> = Vec::new();
+        write_gbs(&mut written, &basis).unwrap();
+        let converted = parse_gaussian94(&mut written.as_slice()).unwrap();
+        assert_eq!(converted.element("h").unwrap().shells.len(), 3);
+        assert_eq!(converted.element("na").unwrap().ecp, Some(ecp.clone()));
+    }
+
+    #[cfg(feature = "bse")]
+    #[test]
+    fn test_basis_bse_json() {
+        let mut test_file = file_setup::setup_sto3g_bse().unwrap();
+        let basis = parse_bse_json(&mut test_file).unwrap();
+        assert_eq!(basis.name, "STO-3G");
+        assert_eq!(basis.description, "STO-3G Minimal Basis (3 functions/AO)");
+        let symbols: Vec<&str> = basis.elements.iter().map(|e| e.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["H", "O", "Na"]);
+        let oxygen = basis.element("O").unwrap();
+        assert_eq!(oxygen.shells[1].function_type, FunctionType::Cartesian);
+        assert_eq!(oxygen.shells[1].label(), "SP");
+        assert_eq!(oxygen.shells[1].coefficients[0][0], -0.09996722919);
+        assert_eq!(oxygen.references[0].keys, vec!["hehre1969a"]);
+        let ecp = basis.element("Na").unwrap().ecp.as_ref().unwrap();
+        assert_eq!(ecp.core_electrons, 10);
+        assert_eq!(ecp.lmax(), 2);
+        assert_eq!(ecp.potentials[1].coefficients, vec![3.0]);
+        assert_eq!(basis.references[0].key, "hehre1969a");
+        assert_eq!(basis.references[0].authors.len(), 3);
+        assert!(basis.references[0]
+            .fields
+            .contains(&("doi".to_string(), "10.1063/1.1672392".to_string())));
+        let mut written: Vec<u8> = Vec::new();
+        write_bse_json(&mut written, &basis).unwrap();
+        assert_eq!(parse_bse_json(&mut written.as_slice()).unwrap(), basis);
+        let mut written: Vec<u8> = Vec::new();
+        write_gaussian94(&mut written, &basis).unwrap();
+        let converted = parse_gaussian94(&mut written.as_slice()).unwrap();
+        assert_eq!(
+            converted.element("O").unwrap().shells[0].exponents,
+            oxygen.shells[0].exponents
+        );
+        assert_eq!(converted.element("Na").unwrap().ecp.as_ref(), Some(ecp));
+    }
+
+    #[test]
+    fn test_pdb() {
+        let mut test_file = file_setup::setup_water_pdb().unwrap();
+        let pdb = Pdb::new(&mut test_file).unwrap();
+        let cell = pdb.cell.as_ref().unwrap();
+        assert_eq!(cell.lengths, [18.65; 3]);
+        assert_eq!(cell.space_group, "P 1");
+        assert_eq!(pdb.models.len(), 2);
+        assert_eq!(pdb.models[1].serial, 2);
+        let atoms = &pdb.models[0].atoms;
+        assert!(!atoms[0].hetero);
+        assert_eq!(atoms[0].residue_name, "GLY");
+        assert_eq!(atoms[3].alt_loc, Some('A'));
+        assert_eq!(atoms[4].occupancy, 0.4);
+        assert_eq!(atoms[4].b_factor, 22.5);
+        assert_eq!(atoms[5].element, "NA");
+        assert_eq!(atoms[5].charge, 1);
+        assert_eq!(atoms[5].chain_id, Some('C'));
+        assert_eq!(pdb.bonds, vec![(2, 3), (2, 4), (2, 5)]);
+        let geometry = pdb.geometry(0);
+        assert_eq!(geometry.number_of_atoms, 5);
+        assert_eq!(
+            geometry.lines[3],
+            XyzLine::Symbolic(XYZLineSymbol {
+                symbol: "h".to_string(),
+                xyz: Point3::new(0.0, -0.757, -0.469)
+            })
+        );
+        assert_eq!(pdb.trajectory()[1].number_of_atoms, 5);
+        let mut written: Vec<u8> = Vec::new();
+        pdb.write(&mut written).unwrap();
+        assert_eq!(
+            String::from_utf8(written).unwrap(),
+            std::fs::read_to_string("tests/test_water.pdb").unwrap()
+        );
+        let converted = Pdb::from_xyz(&geometry);
+        assert_eq!(converted.models[0].atoms[4].name, "NA");
+        assert_eq!(converted.geometry(0).lines, geometry.lines);
+    }
+
+    #[test]
+    fn test_molfile_sdf() {
+        let test_file = file_setup::setup_formate_sdf().unwrap();
+        let mut records = SdfReader::new(test_file)
+            .collect::<Result<Vec<SdfRecord>, _>>()
+            .unwrap();
+        assert_eq!(records.len(), 2);
+        let (first, second) = (&records[0].molecule, &records[1].molecule);
+        assert_eq!(first.version, MolfileVersion::V2000);
+        assert_eq!(second.version, MolfileVersion::V3000);
+        assert_eq!(first.comment, "conformer 1");
+        assert_eq!(first.atoms[2].charge, -1);
+        assert_eq!(first.atoms[3].isotope, Some(2));
+        assert_eq!(second.atoms[2].charge, -1);
+        assert_eq!(second.atoms[3].isotope, Some(2));
+        assert_eq!(
+            second.bonds[2],
+            MolBond {
+                atoms: (0, 3),
+                order: 1,
+                stereo: 1
+            }
+        );
+        assert_eq!(first.bonds[0].order, 2);
+        assert_eq!(records[0].data("energy"), Some("-188.5123"));
+        assert_eq!(records[0].data("comment"), Some("deuterated\nformate anion"));
+        assert_eq!(records[1].data("comment"), None);
+        let geometry = second.geometry();
+        assert_eq!(geometry.number_of_atoms, 4);
+        assert_eq!(
+            geometry.lines[1],
+            XyzLine::Symbolic(XYZLineSymbol {
+                symbol: "o".to_string(),
+                xyz: Point3::new(1.12, 0.0, -0.42)
+            })
+        );
+        let converted = Molfile::from_xyz(&geometry, &second.bonds);
+        assert_eq!(converted.atoms[1].symbol, "O");
+        assert_eq!(converted.bonds, second.bonds);
+        let mut test_file = file_setup::setup_formate_sdf().unwrap();
+        assert_eq!(&Molfile::new(&mut test_file).unwrap(), first);
+        records[1].set_data("dipole", "0.25");
+        let mut written: Vec<u8> = Vec::new();
+        write_sdf(&mut written, &records).unwrap();
+        let reread = SdfReader::new(written.as_slice())
+            .collect::<Result<Vec<SdfRecord>, _>>()
+            .unwrap();
+        assert_eq!(reread, records);
+        assert_eq!(reread[1].data("dipole"), Some("0.25"));
+    }
+
+    #[test]
+    fn test_mol2() {
+        let mut test_file = file_setup::setup_ligands_mol2().unwrap();
+        let molecules = parse_mol2(&mut test_file).unwrap();
+        assert_eq!(molecules.len(), 2);
+        let (water, formamide) = (&molecules[0], &molecules[1]);
+        assert_eq!(water.name, "water");
+        assert_eq!(water.comment, "TIP3P water");
+        assert_eq!(water.charges(), vec![-0.834, 0.417, 0.417]);
+        assert_eq!(water.atoms[0].atom_type, "O.3");
+        assert_eq!(water.substructures[0].chain.as_deref(), Some("A"));
+        assert_eq!(water.substructures[0].residue.as_deref(), Some("HOH"));
+        assert_eq!(formamide.atoms.len(), 6);
+        assert_eq!(formamide.atoms[2].element(), "N");
+        assert_eq!(formamide.bonds[1].atoms, (0, 2));
+        assert_eq!(formamide.bonds[1].bond_type, Mol2BondType::Amide);
+        assert_eq!(formamide.bonds[0].bond_type.order(), Some(2.0));
+        assert_eq!(formamide.substructures[0].kind, "RESIDUE");
+        assert_eq!(formamide.substructures[0].chain, None);
+        let geometry = formamide.geometry();
+        assert_eq!(
+            geometry.lines[1],
+            XyzLine::Symbolic(XYZLineSymbol {
+                symbol: "o".to_string(),
+                xyz: Point3::new(1.204, 0.642, 0.0)
+            })
+        );
+        let converted = Mol2Molecule::from_xyz(&geometry, &formamide.charges());
+        assert_eq!(converted.charge_type, "USER_CHARGES");
+        assert_eq!(converted.atoms[1].name, "O2");
+        assert_eq!(converted.charges(), formamide.charges());
+        let mut written: Vec<u8> = Vec::new();
+        write_mol2(&mut written, &molecules).unwrap();
+        assert_eq!(parse_mol2(&mut written.as_slice()).unwrap(), molecules);
+    }
+
+    #[test]
+    fn test_vasp_contcar() {
+        let mut test_file = file_setup::setup_vasp_contcar().unwrap();
+        let contcar = Poscar::new(&mut test_file).unwrap();
+        assert_eq!(contcar.comment, "SiC with adsorbed water");
+        assert_eq!(contcar.scaling, Scaling::Factor(1.0));
+        assert_eq!(contcar.species, vec!["Si", "C", "O", "H"]);
+        assert_eq!(contcar.counts, vec![2, 2, 1, 2]);
+        assert_eq!(contcar.coordinate_mode, CoordinateMode::Direct);
+        let flags = contcar.selective_dynamics.as_ref().unwrap();
+        assert_eq!(flags[1], [false; 3]);
+        assert_eq!(flags[6], [true; 3]);
+        let velocities = contcar.velocities.as_ref().unwrap();
+        assert_eq!(velocities.len(), 7);
+        assert_eq!(velocities[3].y, -0.0023456789);
+        assert!((contcar.volume() - 123.23195085432).abs() < 1e-8);
+        let geometry = contcar.geometry().unwrap();
+        assert_eq!(geometry.number_of_atoms, 7);
+        let XyzLine::Symbolic(oxygen) = &geometry.lines[4] else {
+            panic!("The geometry is symbolic.");
+        };
+        assert_eq!(oxygen.symbol, "o");
+        assert!((oxygen.xyz - Point3::new(0.0, 1.7782388292, 6.15)).norm() < 1e-8);
+        let cartesian = Poscar::from_xyz(&geometry, contcar.cell());
+        assert_eq!(cartesian.species, contcar.species);
+        assert_eq!(cartesian.coordinate_mode, CoordinateMode::Cartesian);
+        for (a, b) in cartesian
+            .fractional_positions()
+            .unwrap()
+            .iter()
+            .zip(contcar.positions.iter())
+        {
+            assert!((a - b).norm() < 1e-12);
+        }
+        let mut written: Vec<u8> = Vec::new();
+        contcar.write(&mut written).unwrap();
+        assert_eq!(Poscar::new(&mut written.as_slice()).unwrap(), contcar);
+    }
+
+    #[test]
+    fn test_vasp_xdatcar() {
+        let test_file = file_setup::setup_vasp_xdatcar().unwrap();
+        let reader = XdatcarReader::new(test_file).unwrap();
+        assert_eq!(reader.comment(), "Si2 NPT");
+        let frames = reader.collect::<Result<Vec<XdatcarFrame>, _>>().unwrap();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[1].step, 2);
+        assert_eq!(frames[1].positions[0], Point3::new(0.001, 0.0, -0.001));
+        assert!((frames[0].cell.determinant().abs() - 40.02575175).abs() < 1e-6);
+        assert!((frames[2].cell.determinant().abs() - 42.0).abs() < 1e-10);
+        let geometry = frames[0].geometry().unwrap();
+        assert_eq!(
+            geometry.lines[1],
+            XyzLine::Symbolic(XYZLineSymbol {
+                symbol: "si".to_string(),
+                xyz: Point3::new(1.3575, 1.3575, 1.3575)
+            })
+        );
+    }
+
+    #[test]
+    fn test_cif() {
+        let mut test_file = file_setup::setup_nacl_cif().unwrap();
+        let cif = Cif::new(&mut test_file).unwrap();
+        assert_eq!(cif.blocks.len(), 2);
+        let block = cif.block("nacl").unwrap();
+        assert_eq!(block.value("_chemical_name_mineral"), Some("Halite"));
+        assert_eq!(block.value("_symmetry_space_group_name_H-M"), Some("F m -3 m"));
+        assert_eq!(
+            block.value("_publ_section_title"),
+            Some(" The crystal structure of sodium chloride,\n refined at room temperature")
+        );
+        assert_eq!(
+            parse_cif_number(block.value("_cell.volume").unwrap()),
+            Some((179.43, Some(0.02)))
+        );
+        assert_eq!(block.loops.len(), 2);
+        assert!(Crystal::from_block(cif.block("empty").unwrap()).is_err());
+        let crystal = Crystal::from_block(block).unwrap();
+        assert_eq!(crystal.cell.lengths, [5.6402; 3]);
+        assert_eq!(crystal.operators.len(), 5);
+        assert_eq!(crystal.sites[0].symbol, "Na");
+        assert_eq!(crystal.sites[1].symbol, "Cl");
+        assert_eq!(crystal.sites[1].occupancy, 1.0);
+        let unit_cell = crystal.unit_cell();
+        assert_eq!(unit_cell.number_of_atoms, 8);
+        assert_eq!(
+            unit_cell.lines[1],
+            XyzLine::Symbolic(XYZLineSymbol {
+                symbol: "na".to_string(),
+                xyz: Point3::new(0.0, 2.8201, 2.8201)
+            })
+        );
+        let cluster = crystal.cluster(&Point3::origin(), 2.9).unwrap();
+        assert_eq!(cluster.number_of_atoms, 7);
+        let chlorides = cluster
+            .lines
+            .iter()
+            .filter(|l| matches!(l, XyzLine::Symbolic(a) if a.symbol == "cl"))
+            .count();
+        assert_eq!(chlorides, 6);
+        assert_eq!(crystal.cluster(&Point3::origin(), 4.0).unwrap().number_of_atoms, 19);
+    }
 }